@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::fs;
+use std::io;
+
+use failure::Fail;
+use ggez::event::KeyCode;
+use serde::Deserialize;
+
+use aoc_2019::y2019::day13::{Difficulty, Error};
+
+use crate::palette::{Palette, UnknownPalette};
+
+
+#[derive(Debug, Fail)]
+pub enum ConfigError {
+    #[fail(display = "IO error: {}", _0)]
+    Io(#[cause] io::Error),
+    #[fail(display = "TOML error: {}", _0)]
+    Toml(#[cause] toml::de::Error),
+    #[fail(display = "Unknown key: {}", _0)]
+    UnknownKey(String),
+    #[fail(display = "Invalid difficulty: {}", _0)]
+    Difficulty(#[cause] Error),
+    #[fail(display = "{}", _0)]
+    Palette(#[cause] UnknownPalette),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Left,
+    Right,
+    Autopilot,
+    SpeedDown,
+    SpeedUp,
+    ToggleFps,
+    Mute,
+    SaveRecording,
+    Fullscreen,
+    NextTheme,
+    MouseControl,
+    Screenshot,
+    DebugOverlay,
+    Trajectory,
+    NextDifficulty,
+    ToggleStepping,
+    StepFrame,
+}
+
+/// The `[keys]` table of the config file. Every action takes a list of key names, e.g. `left = ["A", "Left"]`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeysConfig {
+    left: Option<Vec<String>>,
+    right: Option<Vec<String>>,
+    autopilot: Option<Vec<String>>,
+    speed_down: Option<Vec<String>>,
+    speed_up: Option<Vec<String>>,
+    toggle_fps: Option<Vec<String>>,
+    mute: Option<Vec<String>>,
+    save_recording: Option<Vec<String>>,
+    fullscreen: Option<Vec<String>>,
+    next_theme: Option<Vec<String>>,
+    mouse_control: Option<Vec<String>>,
+    screenshot: Option<Vec<String>>,
+    debug_overlay: Option<Vec<String>>,
+    trajectory: Option<Vec<String>>,
+    next_difficulty: Option<Vec<String>>,
+    toggle_stepping: Option<Vec<String>>,
+    step_frame: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    theme: Option<String>,
+    #[serde(default)]
+    lives: bool,
+    difficulty: Option<String>,
+    pilot_seed: Option<u64>,
+    palette: Option<String>,
+    #[serde(default)]
+    keys: KeysConfig,
+}
+
+/// Settings from `arcade.toml`.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    pub keys: KeyBindings,
+    /// Name of the tileset theme to start with.
+    pub theme: Option<String>,
+    /// Play with lives instead of ending the game on the first miss.
+    pub lives: bool,
+    /// How well the autopilot plays.
+    pub difficulty: Difficulty,
+    /// Seed of the autopilot's mistakes.
+    pub pilot_seed: Option<u64>,
+    pub palette: Palette,
+}
+
+impl Config {
+    /// Loads the config from a TOML file. Everything that isn't listed keeps its default.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let config = fs::read_to_string(path)
+            .map_err(ConfigError::Io)?;
+        Self::from_toml(&config)
+    }
+
+    pub fn from_toml(config: &str) -> Result<Self, ConfigError> {
+        let config: ConfigFile = toml::from_str(config)
+            .map_err(ConfigError::Toml)?;
+
+        Ok(Self {
+            keys: KeyBindings::from_config(config.keys)?,
+            theme: config.theme,
+            lives: config.lives,
+            difficulty: config.difficulty
+                .map(|difficulty| difficulty.parse())
+                .transpose()
+                .map_err(ConfigError::Difficulty)?
+                .unwrap_or_default(),
+            pilot_seed: config.pilot_seed,
+            palette: config.palette
+                .map(|palette| palette.parse())
+                .transpose()
+                .map_err(ConfigError::Palette)?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+macro_rules! key_names {
+    ($($key:ident),*) => {
+        fn parse_key(name: &str) -> Option<KeyCode> {
+            match name {
+                $(stringify!($key) => Some(KeyCode::$key),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+key_names!(
+    Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0,
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Escape, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    Insert, Home, Delete, End, PageDown, PageUp, Left, Up, Right, Down, Back, Return, Space,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    Add, Subtract, Multiply, Divide, Apostrophe, Backslash, Comma, Equals, Grave, LBracket, RBracket, Minus, Period,
+    Semicolon, Slash, Tab, LAlt, RAlt, LControl, RControl, LShift, RShift
+);
+
+#[derive(Clone, Debug)]
+pub struct KeyBindings {
+    keys: HashMap<KeyCode, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let mut keys = HashMap::new();
+        keys.insert(KeyCode::A, Action::Left);
+        keys.insert(KeyCode::Left, Action::Left);
+        keys.insert(KeyCode::D, Action::Right);
+        keys.insert(KeyCode::Right, Action::Right);
+        keys.insert(KeyCode::F7, Action::Autopilot);
+        keys.insert(KeyCode::Minus, Action::SpeedDown);
+        keys.insert(KeyCode::Subtract, Action::SpeedDown);
+        keys.insert(KeyCode::Equals, Action::SpeedUp);
+        keys.insert(KeyCode::Add, Action::SpeedUp);
+        keys.insert(KeyCode::F3, Action::ToggleFps);
+        keys.insert(KeyCode::M, Action::Mute);
+        keys.insert(KeyCode::F6, Action::SaveRecording);
+        keys.insert(KeyCode::F11, Action::Fullscreen);
+        keys.insert(KeyCode::F8, Action::NextTheme);
+        keys.insert(KeyCode::F2, Action::MouseControl);
+        keys.insert(KeyCode::F12, Action::Screenshot);
+        keys.insert(KeyCode::F4, Action::DebugOverlay);
+        keys.insert(KeyCode::F1, Action::Trajectory);
+        keys.insert(KeyCode::F10, Action::NextDifficulty);
+        keys.insert(KeyCode::Comma, Action::ToggleStepping);
+        keys.insert(KeyCode::Period, Action::StepFrame);
+        Self { keys }
+    }
+}
+
+impl KeyBindings {
+    /// Actions that aren't listed keep their default keys.
+    fn from_config(keys: KeysConfig) -> Result<Self, ConfigError> {
+        let mut bindings = Self::default();
+
+        let actions = vec![
+            (Action::Left, keys.left),
+            (Action::Right, keys.right),
+            (Action::Autopilot, keys.autopilot),
+            (Action::SpeedDown, keys.speed_down),
+            (Action::SpeedUp, keys.speed_up),
+            (Action::ToggleFps, keys.toggle_fps),
+            (Action::Mute, keys.mute),
+            (Action::SaveRecording, keys.save_recording),
+            (Action::Fullscreen, keys.fullscreen),
+            (Action::NextTheme, keys.next_theme),
+            (Action::MouseControl, keys.mouse_control),
+            (Action::Screenshot, keys.screenshot),
+            (Action::DebugOverlay, keys.debug_overlay),
+            (Action::Trajectory, keys.trajectory),
+            (Action::NextDifficulty, keys.next_difficulty),
+            (Action::ToggleStepping, keys.toggle_stepping),
+            (Action::StepFrame, keys.step_frame),
+        ];
+
+        for (action, names) in actions {
+            if let Some(names) = names {
+                bindings.bind(action, &names)?;
+            }
+        }
+
+        Ok(bindings)
+    }
+
+    /// Replaces the keys of an action.
+    pub fn bind<S: AsRef<str>>(&mut self, action: Action, names: &[S]) -> Result<(), ConfigError> {
+        let keys = names.iter()
+            .map(|name| {
+                parse_key(name.as_ref())
+                    .ok_or_else(|| ConfigError::UnknownKey(name.as_ref().to_owned()))
+            })
+            .collect::<Result<Vec<KeyCode>, ConfigError>>()?;
+
+        self.keys.retain(|_, bound| *bound != action);
+        for key in keys {
+            self.keys.insert(key, action);
+        }
+
+        Ok(())
+    }
+
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        self.keys.get(&key).copied()
+    }
+
+    /// Any key bound to the action.
+    pub fn key(&self, action: Action) -> Option<KeyCode> {
+        self.keys.iter()
+            .filter(|(_, bound)| **bound == action)
+            .map(|(key, _)| *key)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_key_names() {
+        assert_eq!(parse_key("A"), Some(KeyCode::A));
+        assert_eq!(parse_key("Key1"), Some(KeyCode::Key1));
+        assert_eq!(parse_key("F12"), Some(KeyCode::F12));
+        assert_eq!(parse_key("LControl"), Some(KeyCode::LControl));
+        assert_eq!(parse_key("a"), None);
+        assert_eq!(parse_key("Ctrl"), None);
+    }
+
+    #[test]
+    fn empty_config_is_the_default() {
+        let config = Config::from_toml("").unwrap();
+        assert_eq!(config.keys.action(KeyCode::A), Some(Action::Left));
+        assert_eq!(config.keys.action(KeyCode::F7), Some(Action::Autopilot));
+        assert_eq!(config.theme, None);
+        assert!(!config.lives);
+        assert_eq!(config.difficulty, Difficulty::Perfect);
+        assert_eq!(config.pilot_seed, None);
+        assert_eq!(config.palette, Palette::GameBoy);
+    }
+
+    #[test]
+    fn bindings_replace_the_default_keys() {
+        let config = Config::from_toml("[keys]\nleft = [\"J\", \"Numpad4\"]\nmute = []").unwrap();
+        assert_eq!(config.keys.action(KeyCode::J), Some(Action::Left));
+        assert_eq!(config.keys.action(KeyCode::Numpad4), Some(Action::Left));
+        assert_eq!(config.keys.action(KeyCode::A), None);
+        assert_eq!(config.keys.action(KeyCode::Left), None);
+        assert_eq!(config.keys.key(Action::Mute), None);
+        // Actions that aren't listed keep their keys.
+        assert_eq!(config.keys.action(KeyCode::D), Some(Action::Right));
+    }
+
+    #[test]
+    fn bind_takes_a_key_from_another_action() {
+        let mut keys = KeyBindings::default();
+        keys.bind(Action::Autopilot, &["A"]).unwrap();
+        assert_eq!(keys.action(KeyCode::A), Some(Action::Autopilot));
+        assert_eq!(keys.key(Action::Left), Some(KeyCode::Left));
+        assert_eq!(keys.key(Action::Autopilot), Some(KeyCode::A));
+    }
+
+    #[test]
+    fn unknown_key_is_an_error() {
+        match Config::from_toml("[keys]\nleft = [\"A\", \"Spacebar\"]") {
+            Err(ConfigError::UnknownKey(key)) => assert_eq!(key, "Spacebar"),
+            other => panic!("Expected an unknown key, got {:?}", other),
+        }
+
+        // A failed bind leaves the keys as they were.
+        let mut keys = KeyBindings::default();
+        assert!(keys.bind(Action::Left, &["J", "Nope"]).is_err());
+        assert_eq!(keys.action(KeyCode::A), Some(Action::Left));
+        assert_eq!(keys.action(KeyCode::J), None);
+    }
+
+    #[test]
+    fn unknown_fields_are_errors() {
+        assert!(matches!(Config::from_toml("colour = \"red\""), Err(ConfigError::Toml(_))));
+        assert!(matches!(Config::from_toml("[keys]\njump = [\"Space\"]"), Err(ConfigError::Toml(_))));
+    }
+
+    #[test]
+    fn parses_settings() {
+        let config = Config::from_toml(
+            "theme = \"classic\"\nlives = true\ndifficulty = \"hard\"\npilot_seed = 42\npalette = \"high-contrast\""
+        ).unwrap();
+        assert_eq!(config.theme.as_deref(), Some("classic"));
+        assert!(config.lives);
+        assert_eq!(config.difficulty, Difficulty::Hard);
+        assert_eq!(config.pilot_seed, Some(42));
+        assert_eq!(config.palette, Palette::HighContrast);
+
+        assert!(matches!(Config::from_toml("difficulty = \"insane\""), Err(ConfigError::Difficulty(_))));
+        assert!(matches!(Config::from_toml("palette = \"sepia\""), Err(ConfigError::Palette(_))));
+    }
+}
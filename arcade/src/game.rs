@@ -0,0 +1,1737 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::io;
+use std::collections::{HashMap, BTreeMap};
+use std::cell::RefCell;
+use std::fmt::{Debug, Display};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use ggez::{Context, ContextBuilder, GameResult, GameError};
+use failure::Fail;
+use ggez::event::{self, EventHandler, KeyCode, KeyMods, Axis, Button, GamepadId};
+use ggez::graphics::{self, Color, Image, DrawParam, Text, Scale, Font};
+use ggez::graphics::spritebatch::SpriteBatch;
+use ggez::graphics::{Canvas, MeshBuilder, Rect};
+use ggez::conf::{WindowMode, WindowSetup, FullscreenType, NumSamples};
+use ggez::audio::{Source, SoundSource};
+use itertools::Itertools;
+use nalgebra::Vector2;
+use num_traits::identities::Zero;
+use rand::Rng;
+
+use aoc_2019::intcode::{Program, Error as IntcodeError};
+use aoc_2019::arcade_tui;
+use aoc_2019::y2019::day13::{Arcade, Error, Tile, JoystickPosition, InputLog, Level, FrameEvent, Difficulty, Screen};
+
+use crate::config::{Config, KeyBindings, Action};
+use crate::palette::Palette;
+
+
+#[derive(Debug, Fail)]
+pub enum ArcadeError {
+    #[fail(display = "Arcade error: {}", _0)]
+    Arcade(#[cause] Error),
+    #[fail(display = "ggez error: {}", _0)]
+    Game(#[cause] GameError),
+    #[fail(display = "The environment variable `ARCADE_RESOURCE_PATH` must be set")]
+    ResourcePathNotSet,
+    #[fail(display = "Invalid resource path {}: {}", _0, _1)]
+    ResourcePath(String, #[cause] io::Error),
+    #[fail(display = "Terminal fallback failed: {}", _0)]
+    Terminal(#[cause] arcade_tui::Error),
+}
+
+impl From<Error> for ArcadeError {
+    fn from(e: Error) -> Self {
+        Self::Arcade(e)
+    }
+}
+
+impl From<GameError> for ArcadeError {
+    fn from(e: GameError) -> Self {
+        Self::Game(e)
+    }
+}
+
+struct Transition {
+    to: Box<dyn Stage>,
+}
+
+trait Stage: Debug {
+    fn init(&self, ctx: &mut Context, state: &mut GameState);
+    fn update(&self, ctx: &mut Context, state: &mut GameState) -> GameResult<Option<Transition>>;
+    fn draw(&self, ctx: &mut Context, state: &mut GameState, scale: f32) -> GameResult<Option<Transition>>;
+    fn key_down_event(&self, ctx: &mut Context, state: &mut GameState, keycode: KeyCode, keymod: KeyMods, _repeat: bool) -> Option<Transition>;
+    fn key_up_event(&self, ctx: &mut Context, state: &mut GameState, keycode: KeyCode, keymod: KeyMods) -> Option<Transition>;
+}
+
+#[derive(Clone, Debug, Default)]
+struct StartingScreen {}
+
+impl StartingScreen {
+    /// Seconds without input before the autopilot starts playing in the background.
+    const ATTRACT_DELAY: u64 = 10;
+}
+
+impl Stage for StartingScreen {
+    fn init(&self, _ctx: &mut Context, state: &mut GameState) {
+        state.reset_idle();
+    }
+
+    fn update(&self, ctx: &mut Context, state: &mut GameState) -> GameResult<Option<Transition>> {
+        if state.demo.is_none() && state.idle_since.elapsed() >= Duration::from_secs(Self::ATTRACT_DELAY) {
+            info!("Starting attract mode");
+            state.demo = Some(state.initial_arcade.clone());
+        }
+
+        let frames = state.frames_due();
+        if let Some(demo) = &mut state.demo {
+            for _ in 0 .. frames {
+                if let Err(e) = demo.autopilot() {
+                    state.fail(ctx, e);
+                    return Ok(None);
+                }
+                state.motion = Motion::of(demo);
+                if demo.wait_frame().is_err() {
+                    *demo = state.initial_arcade.clone();
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw(&self, ctx: &mut Context, state: &mut GameState, scale: f32) -> GameResult<Option<Transition>> {
+        if let Some(demo) = &state.demo {
+            state.draw_board(ctx, demo, scale, true)?;
+            state.dim(ctx)?;
+        }
+
+        state.draw_text(ctx, 256., &TextAlign::centered(), &"PRESS SPACE")?;
+        Ok(None)
+    }
+
+    fn key_down_event(&self, _ctx: &mut Context, state: &mut GameState, _keycode: KeyCode, _keymod: KeyMods, _repeat: bool) -> Option<Transition> {
+        state.reset_idle();
+        None
+    }
+
+    fn key_up_event(&self, ctx: &mut Context, state: &mut GameState, keycode: KeyCode, _keymod: KeyMods) -> Option<Transition> {
+        match keycode {
+            KeyCode::Space if state.replay.is_some() => return Some(Transition { to: Box::new(ReplayScreen::default()) }),
+            KeyCode::Space => return Some(Transition { to: Box::new(GameScreen::default()) }),
+            KeyCode::Escape => ggez::event::quit(ctx),
+            _ => {},
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct GameScreen {
+    /// Continue the running game instead of starting a new one.
+    resumed: bool,
+}
+
+impl Stage for GameScreen {
+    fn init(&self, ctx: &mut Context, state: &mut GameState) {
+        if self.resumed {
+            return;
+        }
+        if let Err(e) = state.new_game() {
+            state.fail(ctx, e);
+        }
+        state.motion = Motion::default();
+        state.reset_lives();
+    }
+
+    fn update(&self, ctx: &mut Context, state: &mut GameState) -> GameResult<Option<Transition>> {
+        for _ in 0 .. state.frames_due() {
+            if state.autopilot {
+                debug!("autopilot on");
+                if let Err(Error::Intcode(IntcodeError::Halted)) = state.arcade.autopilot() {
+                    return Ok(Some(Transition { to: Box::new(ScoreScreen { summary: state.summary() }) }));
+                }
+            }
+            else {
+                state.follow_mouse();
+            }
+
+            debug!("waiting for frame event");
+            state.motion = Motion::of(&state.arcade);
+            let result = state.arcade.wait_frame();
+
+            let ball_lost = match &result {
+                Err(Error::Intcode(IntcodeError::Halted)) => !state.won(),
+                Ok(frame) => frame.ball_lost,
+                Err(_) => false,
+            };
+            if ball_lost && state.lose_life() {
+                continue;
+            }
+
+            match result {
+                Err(Error::Intcode(IntcodeError::Halted)) => {
+                    state.sounds.push(Sound::GameOver);
+                    return Ok(Some(Transition { to: Box::new(ScoreScreen { summary: state.summary() }) }));
+                },
+                Err(e) => {
+                    state.fail(ctx, e);
+                    return Ok(None);
+                },
+                Ok(frame) => state.queue_sounds(&frame),
+            }
+            state.update_checkpoint();
+        }
+        Ok(None)
+    }
+
+    fn draw(&self, ctx: &mut Context, state: &mut GameState, scale: f32) -> GameResult<Option<Transition>> {
+        debug!("draw game screen");
+        state.draw_board(ctx, &state.arcade, scale, true)?;
+        if state.show_trajectory {
+            state.draw_trajectory(ctx, scale)?;
+        }
+
+        state.draw_info(ctx, &mut 0, &"SCORE", Some(state.score()))?;
+
+        Ok(None)
+    }
+
+    fn key_down_event(&self, _ctx: &mut Context, state: &mut GameState, keycode: KeyCode, _keymod: KeyMods, _repeat: bool) -> Option<Transition> {
+        match state.keys.action(keycode) {
+            Some(Action::Left) => state.arcade.set_joystick(JoystickPosition::Left),
+            Some(Action::Right) => state.arcade.set_joystick(JoystickPosition::Right),
+            _ => {},
+        }
+        None
+    }
+
+    fn key_up_event(&self, _ctx: &mut Context, state: &mut GameState, keycode: KeyCode, _keymod: KeyMods) -> Option<Transition> {
+        if let Some(Action::Left) | Some(Action::Right) = state.keys.action(keycode) {
+            state.arcade.set_joystick(JoystickPosition::Neutral);
+        }
+
+        match keycode {
+            KeyCode::P | KeyCode::Escape => return Some(Transition { to: Box::new(PauseScreen::default()) }),
+            KeyCode::Key1 => state.save_slot = 0,
+            KeyCode::Key2 => state.save_slot = 1,
+            KeyCode::Key3 => state.save_slot = 2,
+            KeyCode::Key4 => state.save_slot = 3,
+            KeyCode::F5 => state.save_state(),
+            KeyCode::F9 => state.load_state(),
+            _ => {},
+        }
+        None
+    }
+}
+
+/// Plays back the loaded input log. When the log runs out, the player takes over.
+#[derive(Clone, Debug, Default)]
+struct ReplayScreen {}
+
+impl ReplayScreen {
+    const SEEK_FRAMES: usize = 50;
+}
+
+impl Stage for ReplayScreen {
+    fn init(&self, _ctx: &mut Context, state: &mut GameState) {
+        state.replay_paused = false;
+        state.seek_replay(0);
+    }
+
+    fn update(&self, ctx: &mut Context, state: &mut GameState) -> GameResult<Option<Transition>> {
+        if state.replay_paused {
+            return Ok(None);
+        }
+
+        for _ in 0 .. state.frames_due() {
+            state.motion = Motion::of(&state.arcade);
+            match state.arcade.wait_frame() {
+                Err(Error::Intcode(IntcodeError::Halted)) => {
+                    state.sounds.push(Sound::GameOver);
+                    return Ok(Some(Transition { to: Box::new(ScoreScreen { summary: state.summary() }) }));
+                },
+                Err(e) => {
+                    state.fail(ctx, e);
+                    return Ok(None);
+                },
+                Ok(frame) => state.queue_sounds(&frame),
+            }
+
+            if !state.arcade.is_replaying() {
+                return Ok(Some(Transition { to: Box::new(GameScreen { resumed: true }) }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw(&self, ctx: &mut Context, state: &mut GameState, scale: f32) -> GameResult<Option<Transition>> {
+        state.draw_board(ctx, &state.arcade, scale, !state.replay_paused)?;
+        if state.show_trajectory {
+            state.draw_trajectory(ctx, scale)?;
+        }
+        state.draw_info(ctx, &mut 0, &"SCORE", Some(state.score()))?;
+
+        let frame = state.arcade.machine.inputs_read();
+        let (frames, joystick) = match &state.replay {
+            Some(replay) => (replay.inputs(), replay.position_at(frame)),
+            None => (0, JoystickPosition::Neutral),
+        };
+        let joystick = match joystick {
+            JoystickPosition::Left => "<",
+            JoystickPosition::Neutral => "-",
+            JoystickPosition::Right => ">",
+        };
+        let status = if state.replay_paused { "PAUSED" } else { "" };
+        state.draw_text(ctx, GameState::INFO_TEXT_SIZE, &TextAlign {
+            absolute: Vector2::new(0., -GameState::INFO_PADDING),
+            window: Vector2::new(0.5, 1.),
+            text: Vector2::new(-0.5, -1.),
+        }, &format!("FRAME {:05}/{:05}   INPUT {}   {}", frame, frames, joystick, status))?;
+
+        Ok(None)
+    }
+
+    fn key_down_event(&self, _ctx: &mut Context, state: &mut GameState, keycode: KeyCode, _keymod: KeyMods, _repeat: bool) -> Option<Transition> {
+        let frame = state.arcade.machine.inputs_read();
+        match keycode {
+            KeyCode::Left => state.seek_replay(frame.saturating_sub(Self::SEEK_FRAMES)),
+            KeyCode::Right => state.seek_replay(frame + Self::SEEK_FRAMES),
+            KeyCode::Home => state.seek_replay(0),
+            KeyCode::Up => state.speed_up(),
+            KeyCode::Down => state.speed_down(),
+            _ => {},
+        }
+        None
+    }
+
+    fn key_up_event(&self, _ctx: &mut Context, state: &mut GameState, keycode: KeyCode, _keymod: KeyMods) -> Option<Transition> {
+        match keycode {
+            KeyCode::Space => state.replay_paused = !state.replay_paused,
+            KeyCode::Escape => return Some(Transition { to: Box::new(StartingScreen::default()) }),
+            _ => {},
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct PauseScreen {}
+
+impl Stage for PauseScreen {
+    fn init(&self, _ctx: &mut Context, state: &mut GameState) {
+        state.arcade.set_joystick(JoystickPosition::Neutral);
+    }
+
+    fn update(&self, _ctx: &mut Context, _state: &mut GameState) -> GameResult<Option<Transition>> {
+        Ok(None)
+    }
+
+    fn draw(&self, ctx: &mut Context, state: &mut GameState, scale: f32) -> GameResult<Option<Transition>> {
+        state.draw_board(ctx, &state.arcade, scale, false)?;
+        state.dim(ctx)?;
+
+        state.draw_text(ctx, 96., &TextAlign::centered(), &"PAUSED\n\nP: RESUME\nR: RESTART\nQ: QUIT")?;
+        state.draw_info(ctx, &mut 0, &"SCORE", Some(state.score()))?;
+
+        Ok(None)
+    }
+
+    fn key_down_event(&self, _ctx: &mut Context, _state: &mut GameState, _keycode: KeyCode, _keymod: KeyMods, _repeat: bool) -> Option<Transition> {
+        None
+    }
+
+    fn key_up_event(&self, ctx: &mut Context, _state: &mut GameState, keycode: KeyCode, _keymod: KeyMods) -> Option<Transition> {
+        match keycode {
+            KeyCode::P | KeyCode::Escape => return Some(Transition { to: Box::new(GameScreen { resumed: true }) }),
+            KeyCode::R => return Some(Transition { to: Box::new(GameScreen::default()) }),
+            KeyCode::Q => ggez::event::quit(ctx),
+            _ => {},
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ScoreScreen {
+    summary: Summary,
+}
+
+impl Stage for ScoreScreen {
+    fn init(&self, _ctx: &mut Context, _state: &mut GameState) {}
+
+    fn update(&self, _ctx: &mut Context, _state: &mut GameState) -> GameResult<Option<Transition>> {
+        Ok(None)
+    }
+
+    fn draw(&self, ctx: &mut Context, state: &mut GameState, _scale: f32) -> GameResult<Option<Transition>> {
+        let summary = &self.summary;
+        let message = if state.won() { "YOU WON :)" } else { "YOU LOST :(" };
+        let message = format!("{}\n\nYOUR SCORE:\n\n{}", message, summary.score);
+
+        state.draw_text(ctx, 128., &TextAlign {
+            absolute: Vector2::new(0., -GameState::INFO_PADDING),
+            window: Vector2::new(0.5, 0.5),
+            text: Vector2::new(-0.5, -1.),
+        }, &message)?;
+
+        let seconds = summary.elapsed.as_secs();
+        let stats = format!(
+            "TIME         {}:{:02}\nFRAMES       {}\nPADDLE HITS  {}\nINPUTS       {}\nBLOCKS/MIN   {:.1}",
+            seconds / 60, seconds % 60, summary.frames, summary.paddle_hits, summary.inputs, summary.blocks_per_minute(),
+        );
+        state.draw_text(ctx, GameState::INFO_TEXT_SIZE, &TextAlign {
+            absolute: Vector2::new(0., 4. * GameState::INFO_PADDING),
+            window: Vector2::new(0.5, 0.5),
+            text: Vector2::new(-0.5, 0.),
+        }, &stats)?;
+
+        Ok(None)
+    }
+
+    fn key_down_event(&self, _ctx: &mut Context, _state: &mut GameState, _keycode: KeyCode, _keymod: KeyMods, _repeat: bool) -> Option<Transition> {
+        None
+    }
+
+    fn key_up_event(&self, ctx: &mut Context, _state: &mut GameState, keycode: KeyCode, _keymod: KeyMods) -> Option<Transition> {
+        match keycode {
+            KeyCode::Space => return Some(Transition { to: Box::new(GameScreen::default()) }),
+            KeyCode::Escape => ggez::event::quit(ctx),
+            _ => {},
+        }
+        None
+    }
+}
+
+/// How a game went, for the score screen.
+#[derive(Clone, Debug)]
+struct Summary {
+    score: i64,
+    elapsed: Duration,
+    frames: usize,
+    paddle_hits: usize,
+    /// Joystick moves.
+    inputs: usize,
+    blocks_broken: usize,
+}
+
+impl Summary {
+    pub fn blocks_per_minute(&self) -> f64 {
+        let minutes = self.elapsed.as_secs_f64() / 60.;
+        if minutes > 0. { self.blocks_broken as f64 / minutes } else { 0. }
+    }
+}
+
+struct TextAlign {
+    absolute: Vector2<f32>,
+    window: Vector2<f32>,
+    text: Vector2<f32>,
+}
+
+impl TextAlign {
+    pub fn centered() -> Self {
+        Self {
+            absolute: Vector2::zero(),
+            window: Vector2::new(0.5, 0.5),
+            text: Vector2::new(-0.5, -0.5),
+        }
+    }
+
+    pub fn position(&self, window_size: Vector2<f32>, text_size: Vector2<f32>) -> Vector2<f32> {
+        self.absolute
+            + self.window.component_mul(&window_size)
+            + self.text.component_mul(&text_size)
+
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Sound {
+    Bounce,
+    Block,
+    GameOver,
+}
+
+#[derive(Debug)]
+struct Sounds {
+    bounce: Source,
+    block: Source,
+    game_over: Source,
+    music: Source,
+}
+
+impl Sounds {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let mut music = Source::new(ctx, "/music.wav")?;
+        music.set_repeat(true);
+        music.set_volume(0.5);
+
+        Ok(Self {
+            bounce: Source::new(ctx, "/bounce.wav")?,
+            block: Source::new(ctx, "/block.wav")?,
+            game_over: Source::new(ctx, "/game_over.wav")?,
+            music,
+        })
+    }
+
+    pub fn play(&mut self, sound: Sound) -> GameResult<()> {
+        match sound {
+            Sound::Bounce => self.bounce.play(),
+            Sound::Block => self.block.play(),
+            Sound::GameOver => self.game_over.play(),
+        }
+    }
+}
+
+/// Where the ball and paddle were before the last frame, so that their sprites can glide to the current position.
+#[derive(Copy, Clone, Debug, Default)]
+struct Motion {
+    ball: Option<(i64, i64)>,
+    paddle: Option<(i64, i64)>,
+}
+
+impl Motion {
+    pub fn of(arcade: &Arcade) -> Self {
+        let screen = &arcade.screen;
+        let find = |position, tile| Some(position).filter(|position| screen.framebuffer.get(position) == Some(&tile));
+        Self {
+            ball: find((screen.ball_x, screen.ball_y), Tile::Ball),
+            paddle: find((screen.paddle_x, screen.paddle_y), Tile::Paddle),
+        }
+    }
+}
+
+/// A 5x7 pixel font, drawn crisp at multiples of 8 points.
+const FONT: &[u8] = include_bytes!("../../resources/pixel.ttf");
+
+fn load_font(ctx: &mut Context) -> Font {
+    // ggez panics on fonts it can't parse, so check it first.
+    if let Err(e) = rusttype::Font::from_bytes(FONT) {
+        warn!("Can't load font, falling back to the default font: {}", e);
+        return Font::default();
+    }
+    Font::new_glyph_font_bytes(ctx, FONT).unwrap_or_else(|e| {
+        warn!("Can't load font, falling back to the default font: {}", e);
+        Font::default()
+    })
+}
+
+const DEFAULT_THEME: &str = "default";
+
+/// Themes are directories of sprites in `/themes`. The sprites in the resource root are the default theme.
+fn find_themes(ctx: &mut Context) -> Vec<String> {
+    let mut themes = vec![DEFAULT_THEME.to_owned()];
+    if let Ok(dirs) = ggez::filesystem::read_dir(ctx, "/themes") {
+        let mut names = dirs
+            .filter(|dir| ggez::filesystem::is_dir(ctx, dir))
+            .filter_map(|dir| Some(dir.file_name()?.to_str()?.to_owned()))
+            .filter(|name| name != DEFAULT_THEME)
+            .collect::<Vec<String>>();
+        names.sort();
+        names.dedup();
+        themes.extend(names);
+    }
+    themes
+}
+
+fn load_tileset(ctx: &mut Context, theme: &str, palette: Palette) -> GameResult<HashMap<Tile, Image>> {
+    let dir = if theme == DEFAULT_THEME { String::new() } else { format!("/themes/{}", theme) };
+
+    let mut tileset = HashMap::new();
+    tileset.insert(Tile::Wall, Image::new(ctx, format!("{}/wall.64.png", dir))?);
+    tileset.insert(Tile::Block, Image::new(ctx, format!("{}/block.64.png", dir))?);
+    tileset.insert(Tile::Paddle, Image::new(ctx, format!("{}/paddle.64.png", dir))?);
+    tileset.insert(Tile::Ball, Image::new(ctx, format!("{}/ball.64.png", dir))?);
+    tileset.insert(Tile::Empty, Image::new(ctx, format!("{}/empty.64.png", dir))?);
+    if palette.desaturate() {
+        desaturate(ctx, &mut tileset)?;
+    }
+    Ok(tileset)
+}
+
+/// Turns the sprites into grayscale, with the brightest pixel of the tileset becoming white.
+fn desaturate(ctx: &mut Context, tileset: &mut HashMap<Tile, Image>) -> GameResult<()> {
+    let luma = |pixel: &[u8]| 0.299 * f32::from(pixel[0]) + 0.587 * f32::from(pixel[1]) + 0.114 * f32::from(pixel[2]);
+
+    let sprites = tileset.iter()
+        .map(|(tile, image)| Ok((*tile, image.to_rgba8(ctx)?)))
+        .collect::<GameResult<Vec<(Tile, Vec<u8>)>>>()?;
+    let max = sprites.iter()
+        .flat_map(|(_, rgba)| rgba.chunks(4))
+        .map(luma)
+        .fold(1., f32::max);
+
+    for (tile, mut rgba) in sprites {
+        for pixel in rgba.chunks_mut(4) {
+            let value = (luma(pixel) / max * 255.).round() as u8;
+            pixel[.. 3].copy_from_slice(&[value; 3]);
+        }
+        let sprite = &tileset[&tile];
+        let mut gray = Image::from_rgba8(ctx, sprite.width(), sprite.height(), &rgba)?;
+        gray.set_filter(sprite.filter());
+        tileset.insert(tile, gray);
+    }
+
+    Ok(())
+}
+
+/// The board as it was last drawn, so that only cells that changed are drawn again.
+#[derive(Debug)]
+struct BoardCache {
+    canvas: Canvas,
+    scale: f32,
+    theme: usize,
+    origin: (i64, i64),
+    tiles: BTreeMap<(i64, i64), Tile>,
+}
+
+/// Gives every game a new block layout.
+#[derive(Debug)]
+struct Randomizer {
+    /// The board before shuffling.
+    screen: Screen,
+    seed: u64,
+    /// Whether a game was started with this seed already.
+    played: bool,
+}
+
+impl Randomizer {
+    /// Seeds are kept short, so that they're easy to write down from the screen.
+    fn new_seed() -> u64 {
+        rand::thread_rng().gen_range(0, 100_000)
+    }
+}
+
+#[derive(Debug)]
+struct GameState {
+    initial_arcade: Arcade,
+    arcade: Arcade,
+    tile_size: f32,
+    autopilot: bool,
+    show_fps: bool,
+    show_debug: bool,
+    show_trajectory: bool,
+    /// The ball's predicted path and the frame it was predicted at.
+    trajectory: Option<(usize, Vec<(i64, i64)>)>,
+    /// Instructions per second and when and at which instruction count it was last measured.
+    ips: f64,
+    ips_sample: (Instant, u64),
+    tileset: HashMap<Tile, Image>,
+    board_cache: RefCell<Option<BoardCache>>,
+    /// Pixels per screen coordinate the viewport was last set up for.
+    hidpi_factor: Option<f32>,
+    themes: Vec<String>,
+    theme: usize,
+    palette: Palette,
+    font: Font,
+    /// How far the game is into the current frame.
+    frame_time: f32,
+    speed: f32,
+    /// Frames only advance with the step key, and `steps` is how many are requested.
+    stepping: bool,
+    steps: usize,
+    motion: Motion,
+    /// Steer the paddle with the mouse.
+    mouse: bool,
+    cursor_column: Option<i64>,
+    /// Why the game stopped, if the arcade failed.
+    error: Option<Error>,
+    randomizer: Option<Randomizer>,
+    /// When the current game started.
+    started: Instant,
+    /// Lives left, if playing with house rules.
+    lives: Option<usize>,
+    /// Where the game continues after losing a life.
+    checkpoint: Option<Arcade>,
+    replay: Option<InputLog>,
+    stick: JoystickPosition,
+    sounds: Vec<Sound>,
+    muted: bool,
+    save_states: Vec<Option<Arcade>>,
+    save_slot: usize,
+    replay_paused: bool,
+    idle_since: Instant,
+    demo: Option<Arcade>,
+    keys: KeyBindings,
+}
+
+impl GameState {
+    const INFO_PADDING: f32 = 8.;
+    const INFO_TEXT_SIZE: f32 = 32.;
+    const INFO_NUM: usize = 7;
+    const RECORDING_PATH: &'static str = "arcade.log";
+    const SCREENSHOT_DIR: &'static str = "screenshots";
+    const SAVE_SLOTS: usize = 4;
+    const LIVES: usize = 3;
+    /// Empty tiles go first, so that moving sprites are drawn on top of them.
+    const DRAW_ORDER: [Tile; 5] = [Tile::Empty, Tile::Wall, Tile::Block, Tile::Paddle, Tile::Ball];
+    /// Frames per second at 1x speed.
+    const FRAMES_PER_SECOND: f32 = 6.;
+    const SPEEDS: [f32; 11] = [0.1, 0.25, 0.5, 0.75, 1., 1.5, 2., 3., 5., 10., 20.];
+
+    pub fn score(&self) -> i64 {
+        self.arcade.screen.score
+    }
+
+    /// Statistics of the current game, compared to the initial board.
+    pub fn summary(&self) -> Summary {
+        let initial = &self.initial_arcade;
+        Summary {
+            score: self.score(),
+            elapsed: self.started.elapsed(),
+            frames: self.arcade.machine.inputs_read().saturating_sub(initial.machine.inputs_read()),
+            paddle_hits: self.arcade.screen.stats().paddle_hits.saturating_sub(initial.screen.stats().paddle_hits),
+            inputs: self.arcade.record().changes(),
+            blocks_broken: initial.screen.num_blocks.saturating_sub(self.arcade.screen.num_blocks),
+        }
+    }
+
+    pub fn queue_sounds(&mut self, frame: &FrameEvent) {
+        if frame.blocks_broken > 0 {
+            self.sounds.push(Sound::Block);
+        }
+        else if frame.bounced {
+            self.sounds.push(Sound::Bounce);
+        }
+    }
+
+    /// Jumps to the given frame of the replay. Replays are deterministic, so seeking backwards plays the replay again
+    /// from the start.
+    pub fn seek_replay(&mut self, frame: usize) {
+        let replay = match &self.replay {
+            Some(replay) => replay,
+            None => return,
+        };
+        let frame = frame.min(replay.inputs());
+
+        if frame < self.arcade.machine.inputs_read() || !self.arcade.is_replaying() {
+            self.arcade = self.initial_arcade.clone();
+            self.arcade.replay(replay);
+        }
+        self.motion = Motion::default();
+
+        while self.arcade.machine.inputs_read() < frame {
+            if self.arcade.wait_frame().is_err() {
+                break;
+            }
+        }
+    }
+
+    pub fn reset_idle(&mut self) {
+        self.idle_since = Instant::now();
+        self.demo = None;
+    }
+
+    pub fn save_state(&mut self) {
+        info!("Saving state to slot {}", self.save_slot + 1);
+        self.save_states[self.save_slot] = Some(self.arcade.clone());
+    }
+
+    pub fn load_state(&mut self) {
+        match &self.save_states[self.save_slot] {
+            Some(arcade) => {
+                info!("Loading state from slot {}", self.save_slot + 1);
+                self.arcade = arcade.clone();
+                self.motion = Motion::default();
+            },
+            None => info!("Slot {} is empty", self.save_slot + 1),
+        }
+    }
+
+    pub fn save_recording(&self) {
+        let log = self.arcade.record();
+        match log.save(Self::RECORDING_PATH) {
+            Ok(()) => info!("Saved {} inputs to {}", log.inputs(), Self::RECORDING_PATH),
+            Err(e) => error!("Failed to save recording: {}", e),
+        }
+    }
+
+    /// Saves what's currently drawn, which is upside down in OpenGL's framebuffer.
+    pub fn save_screenshot(&self, ctx: &mut Context) -> Result<PathBuf, failure::Error> {
+        let screenshot = graphics::screenshot(ctx)?;
+        let pixels = screenshot.to_rgba8(ctx)?;
+        let image = image::RgbaImage::from_raw(u32::from(screenshot.width()), u32::from(screenshot.height()), pixels)
+            .ok_or_else(|| failure::err_msg("Screenshot has the wrong size"))?;
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let path = Path::new(Self::SCREENSHOT_DIR).join(format!("arcade-{:05}-{}.png", self.score(), timestamp));
+        fs::create_dir_all(Self::SCREENSHOT_DIR)?;
+        image::imageops::flip_vertical(&image).save_with_format(&path, image::ImageFormat::Png)?;
+
+        Ok(path)
+    }
+
+    pub fn won(&self) -> bool {
+        self.arcade.screen.num_blocks == 0
+    }
+
+    /// Size of a tile on the screen, so that the board fits into the window.
+    pub fn scale(&self, ctx: &Context) -> f32 {
+        let window_size = graphics::screen_coordinates(ctx);
+        // 36 x 19
+        let screen_size = self.arcade.screen.screen_size().unwrap();
+        let scale = (window_size.w / (screen_size.0 as f32)).min(window_size.h / (screen_size.1 as f32));
+        debug!("window_size={:?}, screen_size={:?}, scale={}", window_size, screen_size, scale);
+        scale
+    }
+
+    /// Shuffles the blocks of the board with the seed, and of every game after the first one with a new seed.
+    pub fn randomize(&mut self, seed: u64) -> Result<(), Error> {
+        let randomizer = Randomizer {
+            screen: self.initial_arcade.screen.clone(),
+            seed,
+            played: false,
+        };
+        info!("Shuffling the board with seed {}", seed);
+        self.initial_arcade.load_level(&Level::shuffle(&randomizer.screen, seed))?;
+        self.arcade = self.initial_arcade.clone();
+        self.randomizer = Some(randomizer);
+        Ok(())
+    }
+
+    /// Starts over with the initial board, or a freshly shuffled one.
+    pub fn new_game(&mut self) -> Result<(), Error> {
+        if let Some(randomizer) = &mut self.randomizer {
+            if randomizer.played {
+                randomizer.seed = Randomizer::new_seed();
+                info!("Shuffling the board with seed {}", randomizer.seed);
+                self.initial_arcade.load_level(&Level::shuffle(&randomizer.screen, randomizer.seed))?;
+            }
+            randomizer.played = true;
+        }
+        self.arcade = self.initial_arcade.clone();
+        self.started = Instant::now();
+        Ok(())
+    }
+
+    /// Changes how well the autopilot plays, in the running game, the attract mode and every new game.
+    pub fn set_difficulty(&mut self, difficulty: Difficulty, seed: u64) {
+        self.initial_arcade.set_difficulty(difficulty, seed);
+        self.arcade.set_difficulty(difficulty, seed);
+        if let Some(demo) = &mut self.demo {
+            demo.set_difficulty(difficulty, seed);
+        }
+    }
+
+    /// Pixels per screen coordinate, e.g. 2 on most 4K displays.
+    pub fn hidpi_factor(ctx: &Context) -> f32 {
+        graphics::window(ctx).get_hidpi_factor() as f32
+    }
+
+    /// ggez ignores changes of the scale factor, so the viewport keeps its old size in pixels and everything is drawn
+    /// into a corner. This happens when the window moves to another display, and on some platforms right at the start.
+    pub fn check_hidpi_factor(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let factor = Self::hidpi_factor(ctx);
+        if self.hidpi_factor != Some(factor) {
+            debug!("hidpi factor changed: {:?} -> {}", self.hidpi_factor, factor);
+            self.hidpi_factor = Some(factor);
+
+            // Setting the size again makes ggez resize the viewport with the new factor.
+            let (width, height) = graphics::drawable_size(ctx);
+            graphics::set_drawable_size(ctx, width, height)?;
+            graphics::set_screen_coordinates(ctx, Rect::new(0., 0., width, height))?;
+            *self.board_cache.borrow_mut() = None;
+        }
+        Ok(())
+    }
+
+    /// Quits because the arcade failed. `solve` returns the error.
+    pub fn fail(&mut self, ctx: &mut Context, error: Error) {
+        error!("Arcade failed: {}", error);
+        self.error = Some(error);
+        event::quit(ctx);
+    }
+
+    pub fn reset_lives(&mut self) {
+        if self.lives.is_some() {
+            self.lives = Some(Self::LIVES);
+            self.checkpoint = Some(self.arcade.clone());
+        }
+    }
+
+    /// Remembers the game while the ball is flying upwards, so that the last checkpoint before losing the ball is
+    /// where it turned around.
+    pub fn update_checkpoint(&mut self) {
+        if self.lives.is_none() {
+            return;
+        }
+        if let (Some(from), Some(to)) = (self.motion.ball, Motion::of(&self.arcade).ball) {
+            if to.1 < from.1 {
+                self.checkpoint = Some(self.arcade.clone());
+            }
+        }
+    }
+
+    /// Takes a life and goes back to the checkpoint. Returns `false` if the game is over.
+    pub fn lose_life(&mut self) -> bool {
+        match (self.lives, &self.checkpoint) {
+            (Some(lives), Some(checkpoint)) if lives > 0 => {
+                info!("Lost a life, {} left", lives - 1);
+                self.lives = Some(lives - 1);
+                self.arcade = checkpoint.clone();
+                self.motion = Motion::default();
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Moves the paddle towards the column under the mouse cursor.
+    pub fn follow_mouse(&mut self) {
+        let column = match self.cursor_column {
+            Some(column) if self.mouse => column,
+            _ => return,
+        };
+        let paddle = self.arcade.screen.paddle_x;
+        let joystick = if column < paddle {
+            JoystickPosition::Left
+        }
+        else if column > paddle {
+            JoystickPosition::Right
+        }
+        else {
+            JoystickPosition::Neutral
+        };
+        self.arcade.set_joystick(joystick);
+    }
+
+    /// Frames that pass during one tick.
+    fn frames_per_tick(&self) -> f32 {
+        self.speed * Self::FRAMES_PER_SECOND / Game::TICKS_PER_SECOND as f32
+    }
+
+    /// Advances the game time by one tick and returns the number of frames to run.
+    pub fn frames_due(&mut self) -> usize {
+        if self.stepping {
+            // Show whole frames, without interpolation.
+            self.frame_time = 1.;
+            return std::mem::take(&mut self.steps);
+        }
+
+        self.frame_time += self.frames_per_tick();
+        let frames = self.frame_time.floor();
+        self.frame_time -= frames;
+        frames as usize
+    }
+
+    pub fn toggle_stepping(&mut self) {
+        self.stepping = !self.stepping;
+        self.steps = 0;
+        self.frame_time = 0.;
+    }
+
+    pub fn speed_up(&mut self) {
+        if let Some(speed) = Self::SPEEDS.iter().find(|speed| **speed > self.speed) {
+            self.speed = *speed;
+        }
+    }
+
+    pub fn speed_down(&mut self) {
+        if let Some(speed) = Self::SPEEDS.iter().rev().find(|speed| **speed < self.speed) {
+            self.speed = *speed;
+        }
+    }
+
+    /// How far the game is into the current frame, from 0 to 1. This includes the time since the last tick.
+    fn frame_progress(&self, ctx: &mut Context) -> f32 {
+        let tick = ggez::timer::duration_to_f64(ggez::timer::remaining_update_time(ctx)) * f64::from(Game::TICKS_PER_SECOND);
+        (self.frame_time + tick as f32 * self.frames_per_tick()).min(1.)
+    }
+
+    pub fn background(&self) -> Color {
+        self.palette.background()
+    }
+
+    /// Draws the board. With `interpolate` the ball and paddle are drawn between their previous and current cells,
+    /// according to the frame progress. The framebuffer stays authoritative, and jumps of more than one cell aren't
+    /// smoothed.
+    ///
+    /// Tiles are kept on a canvas, and only cells that differ from the last call are drawn again.
+    pub fn draw_board(&self, ctx: &mut Context, arcade: &Arcade, scale: f32, interpolate: bool) -> GameResult<()> {
+        let framebuffer = &arcade.screen.framebuffer;
+
+        let (min, max) = match framebuffer.bounds() {
+            Some(minmax) => minmax,
+            None => return Ok(()),
+        };
+
+        let current = Motion::of(arcade);
+        let moving = if interpolate {
+            vec![(Tile::Ball, self.motion.ball, current.ball), (Tile::Paddle, self.motion.paddle, current.paddle)]
+        }
+        else {
+            vec![]
+        };
+
+        let sprite_params = |tile: Tile, x: f32, y: f32| {
+            let pos = Vector2::new(x - min.0 as f32, y - min.1 as f32) * scale;
+            DrawParam::new()
+                .dest(mint::Point2::from([pos.x, pos.y]))
+                .scale(mint::Vector2::from([scale / self.tile_size, scale / self.tile_size]))
+                .color(self.palette.tint(tile))
+        };
+
+        let mut cache = self.board_cache.borrow_mut();
+        let screen = graphics::screen_coordinates(ctx);
+        // ggez gives us the size in logical units, but the canvas needs one texel per pixel to look sharp.
+        let factor = Self::hidpi_factor(ctx);
+        let (width, height) = graphics::drawable_size(ctx);
+        let pixels = ((width * factor).round() as u16, (height * factor).round() as u16);
+        let valid = match &*cache {
+            Some(cache) => {
+                cache.scale == scale && cache.theme == self.theme && cache.origin == min
+                    && (cache.canvas.image().width(), cache.canvas.image().height()) == pixels
+            },
+            None => false,
+        };
+        if !valid {
+            debug!("redrawing the whole board");
+            let canvas = Canvas::new(ctx, pixels.0, pixels.1, NumSamples::One)?;
+            graphics::set_canvas(ctx, Some(&canvas));
+            graphics::clear(ctx, self.background());
+            graphics::set_canvas(ctx, None);
+            *cache = Some(BoardCache {
+                canvas,
+                scale,
+                theme: self.theme,
+                origin: min,
+                tiles: BTreeMap::new(),
+            });
+        }
+        let cache = cache.as_mut().unwrap();
+
+        // The moving sprites are drawn on top of the canvas, so their cells are empty on it.
+        let dirty = (min.1 ..= max.1)
+            .cartesian_product(min.0 ..= max.0)
+            .map(|(y, x)| {
+                let tile = framebuffer.get(&(x, y)).copied().unwrap_or_default();
+                if moving.iter().any(|(moving, _, to)| *moving == tile && *to == Some((x, y))) {
+                    ((x, y), Tile::Empty)
+                }
+                else {
+                    ((x, y), tile)
+                }
+            })
+            .filter(|(pos, tile)| cache.tiles.get(pos) != Some(tile))
+            .collect::<Vec<((i64, i64), Tile)>>();
+
+        if !dirty.is_empty() {
+            // One batch per tile type, so that the changed cells take a handful of draw calls.
+            let mut batches = Self::DRAW_ORDER.iter()
+                .map(|tile| (*tile, SpriteBatch::new(self.tileset[tile].clone())))
+                .collect::<HashMap<Tile, SpriteBatch>>();
+            let mut background = MeshBuilder::new();
+
+            for ((x, y), tile) in &dirty {
+                let pos = Vector2::new((x - min.0) as f32, (y - min.1) as f32) * scale;
+                background.rectangle(graphics::DrawMode::fill(), Rect::new(pos.x, pos.y, scale, scale), self.background());
+                batches.get_mut(tile).unwrap().add(sprite_params(*tile, *x as f32, *y as f32));
+                cache.tiles.insert((*x, *y), *tile);
+            }
+
+            let background = background.build(ctx)?;
+            graphics::set_canvas(ctx, Some(&cache.canvas));
+            graphics::draw(ctx, &background, DrawParam::new())?;
+            for tile in Self::DRAW_ORDER.iter() {
+                graphics::draw(ctx, &batches[tile], DrawParam::new())?;
+            }
+            graphics::set_canvas(ctx, None);
+        }
+
+        // The canvas has one texel per pixel, which may differ from the screen coordinates.
+        let (width, height) = (cache.canvas.image().width(), cache.canvas.image().height());
+        graphics::draw(ctx, &cache.canvas, DrawParam::new()
+            .dest(mint::Point2::from([screen.x, screen.y]))
+            .scale(mint::Vector2::from([screen.w / f32::from(width), screen.h / f32::from(height)])))?;
+
+        let t = self.frame_progress(ctx);
+        for (tile, from, to) in moving {
+            if let Some(to) = to {
+                let from = from
+                    .filter(|from| (from.0 - to.0).abs() <= 1 && (from.1 - to.1).abs() <= 1)
+                    .unwrap_or(to);
+                let x = from.0 as f32 + (to.0 - from.0) as f32 * t;
+                let y = from.1 as f32 + (to.1 - from.1) as f32 * t;
+                graphics::draw(ctx, &self.tileset[&tile], sprite_params(tile, x, y))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws where the ball goes until it reaches the paddle, as the predicting autopilot sees it. The prediction is
+    /// only simulated again when the game moved on.
+    pub fn draw_trajectory(&mut self, ctx: &mut Context, scale: f32) -> GameResult<()> {
+        let frame = self.arcade.machine.inputs_read();
+        if self.trajectory.as_ref().is_none_or(|(predicted_at, _)| *predicted_at != frame) {
+            match self.arcade.predict_ball() {
+                Ok(path) => self.trajectory = Some((frame, path)),
+                Err(e) => {
+                    warn!("Failed to predict the ball: {}", e);
+                    self.trajectory = None;
+                    return Ok(());
+                },
+            }
+        }
+
+        let min = match self.arcade.screen.framebuffer.bounds() {
+            Some((min, _)) => min,
+            None => return Ok(()),
+        };
+        let center = |(x, y): (i64, i64)| {
+            mint::Point2::from([(x - min.0) as f32 * scale + scale / 2., (y - min.1) as f32 * scale + scale / 2.])
+        };
+
+        let ball = (self.arcade.screen.ball_x, self.arcade.screen.ball_y);
+        let path = &self.trajectory.as_ref().unwrap().1;
+        let points = std::iter::once(ball)
+            .chain(path.iter().copied())
+            .map(center)
+            .collect::<Vec<mint::Point2<f32>>>();
+
+        let color = Color::new(1., 1., 1., 0.3);
+        let mut mesh = MeshBuilder::new();
+        if points.len() >= 2 {
+            mesh.line(&points, scale / 8., color)?;
+        }
+        for point in &points[1 ..] {
+            mesh.circle(graphics::DrawMode::fill(), *point, scale / 4., 0.5, color);
+        }
+        let mesh = mesh.build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::new())
+    }
+
+    /// Darkens everything drawn so far, so that text on top stands out.
+    pub fn dim(&self, ctx: &mut Context) -> GameResult<()> {
+        let dim = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), graphics::screen_coordinates(ctx), Color::new(0., 0., 0., 0.6))?;
+        graphics::draw(ctx, &dim, DrawParam::new())
+    }
+
+    pub fn draw_text<S: AsRef<str>>(&self, ctx: &mut Context, scale: f32, align: &TextAlign, text: &S) -> GameResult<()> {
+        // Rasterize the glyphs in pixels and scale them back down, otherwise they're blurry on HiDPI displays.
+        let factor = Self::hidpi_factor(ctx);
+        let mut text = Text::new(text.as_ref());
+        text.set_font(self.font.clone(), Scale::uniform(scale * factor));
+
+        let window_size = graphics::screen_coordinates(ctx);
+        let window_size = Vector2::new(window_size.w, window_size.h);
+        let text_size =  text.dimensions(ctx);
+        let text_size = Vector2::new(text_size.0 as f32, text_size.1 as f32) / factor;
+
+        let pos = align.position(window_size, text_size);
+        let draw_params = DrawParam::new()
+            .dest(mint::Point2::from([pos.x, pos.y]))
+            .scale(mint::Vector2::from([1. / factor, 1. / factor]));
+
+        graphics::draw(ctx, &text, draw_params)?;
+
+        Ok(())
+    }
+
+    /// Shows what the Intcode machine is doing.
+    pub fn draw_debug(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let machine = &self.arcade.machine;
+        let stats = machine.stats();
+
+        let elapsed = self.ips_sample.0.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            self.ips = stats.instructions.saturating_sub(self.ips_sample.1) as f64 / elapsed.as_secs_f64();
+            self.ips_sample = (Instant::now(), stats.instructions);
+        }
+
+        let last = stats.last_pc
+            .map(|pc| format!("{:05} {}", pc, machine.disassemble(pc)))
+            .unwrap_or_else(|| "-".to_owned());
+        let text = format!(
+            "PC    {:05}\nIPS   {:.0}\nQUEUE {}\nREADS {}\nLAST  {}",
+            machine.pc(), self.ips, machine.input_len(), machine.inputs_read(), last,
+        );
+
+        self.draw_text(ctx, Self::INFO_TEXT_SIZE * 0.75, &TextAlign {
+            absolute: Vector2::new(Self::INFO_PADDING, -Self::INFO_PADDING),
+            window: Vector2::new(0., 1.),
+            text: Vector2::new(0., -1.),
+        }, &text)
+    }
+
+    pub fn draw_info<T: Display>(&self, ctx: &mut Context, index: &mut usize, text: &T, number: Option<i64>) -> GameResult<()> {
+        let info = match number {
+            Some(number) => format!("{} {:04}", text, number),
+            None => format!("{}", text),
+        };
+
+        self.draw_text(ctx, Self::INFO_TEXT_SIZE, &TextAlign {
+            absolute: Vector2::new(0., Self::INFO_PADDING),
+            window: Vector2::new((*index as f32 + 1.) / (Self::INFO_NUM as f32 + 1.), 0.),
+            text: Vector2::new(-0.5, 0.0),
+        }, &info)?;
+
+        *index += 1;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Game {
+    state: GameState,
+    stage: Box<dyn Stage>,
+    sounds: Option<Sounds>,
+    fullscreen: bool,
+    /// Take a screenshot after the next frame is drawn.
+    screenshot: bool,
+}
+
+impl Game {
+    pub fn new(ctx: &mut Context, program: Program, level: Option<&Level>, theme: Option<&str>, palette: Palette) -> Result<Self, ArcadeError> {
+        let themes = find_themes(ctx);
+        debug!("themes: {:?}", themes);
+        let theme = match theme {
+            Some(name) => themes.iter().position(|theme| theme == name).unwrap_or_else(|| {
+                warn!("Unknown theme: {}", name);
+                0
+            }),
+            None => 0,
+        };
+        let tileset = load_tileset(ctx, &themes[theme], palette)?;
+
+        let mut arcade = Arcade::new(program);
+
+        arcade.load_screen()?;
+        if let Some(level) = level {
+            arcade.load_level(level)?;
+        }
+        info!("Game hot-loaded");
+
+        let font = load_font(ctx);
+        debug!("screen loaded");
+        debug!("window size: {:?}", graphics::drawable_size(ctx));
+
+        arcade.machine.set_contant_input(JoystickPosition::default().into());
+
+        let sounds = match Sounds::new(ctx) {
+            Ok(mut sounds) => {
+                sounds.music.play()?;
+                Some(sounds)
+            },
+            Err(e) => {
+                warn!("Sound disabled: {}", e);
+                None
+            },
+        };
+
+        Ok(Game {
+            state: GameState {
+                initial_arcade: arcade.clone(),
+                arcade,
+                tileset,
+                board_cache: RefCell::new(None),
+                hidpi_factor: None,
+                themes,
+                theme,
+                palette,
+                tile_size: 64.,
+                autopilot: false,
+                font,
+                show_fps: true,
+                show_debug: false,
+                show_trajectory: false,
+                trajectory: None,
+                ips: 0.,
+                ips_sample: (Instant::now(), 0),
+                frame_time: 0.,
+                speed: 1.,
+                stepping: false,
+                steps: 0,
+                motion: Motion::default(),
+                mouse: false,
+                cursor_column: None,
+                error: None,
+                randomizer: None,
+                started: Instant::now(),
+                lives: None,
+                checkpoint: None,
+                replay: None,
+                stick: JoystickPosition::Neutral,
+                sounds: Vec::new(),
+                muted: false,
+                save_states: vec![None; GameState::SAVE_SLOTS],
+                save_slot: 0,
+                replay_paused: false,
+                idle_since: Instant::now(),
+                demo: None,
+                keys: KeyBindings::default(),
+            },
+            stage: Box::new(StartingScreen::default()),
+            sounds,
+            fullscreen: false,
+            screenshot: false,
+        })
+    }
+
+    const STICK_DEADZONE: f32 = 0.5;
+    const TICKS_PER_SECOND: u32 = 60;
+
+    /// Gamepad buttons act like their keyboard counterparts.
+    fn button_key(&self, button: Button) -> Option<KeyCode> {
+        match button {
+            Button::DPadLeft => self.state.keys.key(Action::Left),
+            Button::DPadRight => self.state.keys.key(Action::Right),
+            Button::South => Some(KeyCode::Space),
+            _ => None,
+        }
+    }
+
+    fn stick_key(&self, stick: JoystickPosition) -> Option<KeyCode> {
+        match stick {
+            JoystickPosition::Left => self.state.keys.key(Action::Left),
+            JoystickPosition::Right => self.state.keys.key(Action::Right),
+            JoystickPosition::Neutral => None,
+        }
+    }
+
+    fn play_sounds(&mut self) -> GameResult<()> {
+        let queued = std::mem::take(&mut self.state.sounds);
+        if let Some(sounds) = &mut self.sounds {
+            if !self.state.muted {
+                for sound in queued {
+                    sounds.play(sound)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn toggle_fullscreen(&mut self, ctx: &mut Context) -> GameResult<()> {
+        self.fullscreen = !self.fullscreen;
+        graphics::set_fullscreen(ctx, if self.fullscreen { FullscreenType::Desktop } else { FullscreenType::Windowed })
+    }
+
+    fn next_theme(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let theme = (self.state.theme + 1) % self.state.themes.len();
+        self.state.tileset = load_tileset(ctx, &self.state.themes[theme], self.state.palette)?;
+        self.state.theme = theme;
+        info!("Theme: {}", self.state.themes[theme]);
+        Ok(())
+    }
+
+    fn toggle_mute(&mut self) {
+        self.state.muted = !self.state.muted;
+        if let Some(sounds) = &self.sounds {
+            if self.state.muted {
+                sounds.music.pause();
+            }
+            else {
+                sounds.music.resume();
+            }
+        }
+    }
+
+    fn transition_maybe(&mut self, ctx: &mut Context, transition: Option<Transition>) {
+        if let Some(transition) = transition {
+            info!("Transition to: {:?}", transition.to);
+            transition.to.init(ctx, &mut self.state);
+            self.stage = transition.to;
+        }
+    }
+
+}
+
+impl EventHandler for Game {
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        debug!("update");
+        self.state.check_hidpi_factor(ctx)?;
+
+        // The game runs at a fixed number of ticks per second, no matter how fast we can draw.
+        while ggez::timer::check_update_time(ctx, Self::TICKS_PER_SECOND) {
+            let transition = self.stage.update(ctx, &mut self.state)?;
+            self.transition_maybe(ctx, transition);
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        graphics::clear(ctx, self.state.background());
+
+        let scale = self.state.scale(ctx);
+
+        let transition = self.stage.draw(ctx, &mut self.state, scale)?;
+        self.transition_maybe(ctx, transition);
+        self.play_sounds()?;
+
+        let mut menu_index = 1;
+
+        if self.state.stepping {
+            self.state.draw_info(ctx, &mut menu_index, &"STEP", Some(self.state.arcade.machine.inputs_read() as i64))?;
+        }
+        else {
+            self.state.draw_info(ctx, &mut menu_index, &format!("SPEED {}X", self.state.speed), None)?;
+        }
+        self.state.draw_info(ctx, &mut menu_index, &"SLOT", Some(self.state.save_slot as i64 + 1))?;
+
+        if let Some(lives) = self.state.lives {
+            self.state.draw_info(ctx, &mut menu_index, &"LIVES", Some(lives as i64))?;
+        }
+
+        if let Some(randomizer) = &self.state.randomizer {
+            self.state.draw_info(ctx, &mut menu_index, &format!("SEED {}", randomizer.seed), None)?;
+        }
+
+        if self.state.show_fps {
+            self.state.draw_info(ctx, &mut menu_index, &"FPS", Some(ggez::timer::fps(ctx) as i64))?;
+        }
+
+        if self.state.arcade.is_replaying() {
+            self.state.draw_info(ctx, &mut menu_index, &"REPLAY", None)?;
+        }
+        else if self.state.autopilot {
+            let difficulty = self.state.arcade.difficulty();
+            self.state.draw_info(ctx, &mut menu_index, &format!("AUTO {}", difficulty.to_string().to_uppercase()), None)?;
+            // The perfect autopilot doesn't use its seed.
+            if difficulty != Difficulty::Perfect {
+                let seed = self.state.arcade.pilot_seed();
+                self.state.draw_info(ctx, &mut menu_index, &format!("PILOT SEED {}", seed), None)?;
+            }
+        }
+        else if self.state.mouse {
+            self.state.draw_info(ctx, &mut menu_index, &"MOUSE", None)?;
+        }
+
+        if self.state.show_debug {
+            self.state.draw_debug(ctx)?;
+        }
+
+        if self.screenshot {
+            self.screenshot = false;
+            match self.state.save_screenshot(ctx) {
+                Ok(path) => info!("Saved screenshot to {}", path.display()),
+                Err(e) => error!("Failed to save screenshot: {}", e),
+            }
+        }
+
+        graphics::present(ctx)
+    }
+
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, keymod: KeyMods, repeat: bool) {
+        let transition = self.stage.key_down_event(ctx, &mut self.state, keycode, keymod, repeat);
+        self.transition_maybe(ctx, transition);
+    }
+
+    fn key_up_event(&mut self, ctx: &mut Context, keycode: KeyCode, keymod: KeyMods) {
+        debug!("key up: {:?}", keycode);
+
+        match self.state.keys.action(keycode) {
+            Some(Action::Autopilot) => {
+                self.state.autopilot = !self.state.autopilot;
+                self.state.arcade.set_joystick(JoystickPosition::Left);
+            },
+            Some(Action::ToggleFps) => self.state.show_fps = !self.state.show_fps,
+            Some(Action::DebugOverlay) => self.state.show_debug = !self.state.show_debug,
+            Some(Action::Trajectory) => self.state.show_trajectory = !self.state.show_trajectory,
+            Some(Action::ToggleStepping) => self.state.toggle_stepping(),
+            Some(Action::StepFrame) if self.state.stepping => self.state.steps += 1,
+            Some(Action::Screenshot) => self.screenshot = true,
+            Some(Action::MouseControl) => {
+                self.state.mouse = !self.state.mouse;
+                if !self.state.mouse {
+                    self.state.arcade.set_joystick(JoystickPosition::Neutral);
+                }
+            },
+            Some(Action::SaveRecording) => self.state.save_recording(),
+            Some(Action::Mute) => self.toggle_mute(),
+            Some(Action::Fullscreen) => {
+                if let Err(e) = self.toggle_fullscreen(ctx) {
+                    error!("Failed to toggle fullscreen: {}", e);
+                }
+            },
+            Some(Action::NextTheme) => {
+                if let Err(e) = self.next_theme(ctx) {
+                    error!("Failed to load theme: {}", e);
+                }
+            },
+            Some(Action::SpeedDown) => self.state.speed_down(),
+            Some(Action::SpeedUp) => self.state.speed_up(),
+            Some(Action::NextDifficulty) => {
+                let difficulty = self.state.arcade.difficulty().next();
+                info!("Autopilot difficulty: {}", difficulty);
+                let seed = self.state.arcade.pilot_seed();
+                self.state.set_difficulty(difficulty, seed);
+            },
+            _ => {},
+        }
+
+        let transition = self.stage.key_up_event(ctx, &mut self.state, keycode, keymod);
+        self.transition_maybe(ctx, transition);
+    }
+
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        debug!("resized to {} x {}", width, height);
+
+        // Keep one unit per pixel, otherwise everything is stretched to the new window size.
+        if let Err(e) = graphics::set_screen_coordinates(ctx, graphics::Rect::new(0., 0., width, height)) {
+            error!("Failed to set screen coordinates: {}", e);
+        }
+    }
+
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, _y: f32, _dx: f32, _dy: f32) {
+        let scale = self.state.scale(ctx);
+        // The board is drawn from its leftmost column, which isn't always x = 0.
+        let min_x = self.state.arcade.screen.framebuffer.bounds().map_or(0, |(min, _)| min.0);
+        self.state.cursor_column = Some((x / scale).floor() as i64 + min_x);
+    }
+
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, button: Button, _id: GamepadId) {
+        if let Some(keycode) = self.button_key(button) {
+            self.key_down_event(ctx, keycode, KeyMods::NONE, false);
+        }
+    }
+
+    fn gamepad_button_up_event(&mut self, ctx: &mut Context, button: Button, _id: GamepadId) {
+        debug!("gamepad button up: {:?}", button);
+
+        if button == Button::Start {
+            self.transition_maybe(ctx, Some(Transition { to: Box::new(GameScreen::default()) }));
+        }
+        else if let Some(keycode) = self.button_key(button) {
+            self.key_up_event(ctx, keycode, KeyMods::NONE);
+        }
+    }
+
+    fn gamepad_axis_event(&mut self, ctx: &mut Context, axis: Axis, value: f32, _id: GamepadId) {
+        if axis != Axis::LeftStickX {
+            return;
+        }
+
+        let stick = if value < -Self::STICK_DEADZONE {
+            JoystickPosition::Left
+        }
+        else if value > Self::STICK_DEADZONE {
+            JoystickPosition::Right
+        }
+        else {
+            JoystickPosition::Neutral
+        };
+
+        if stick != self.state.stick {
+            if let Some(keycode) = self.stick_key(self.state.stick) {
+                self.key_up_event(ctx, keycode, KeyMods::NONE);
+            }
+            if let Some(keycode) = self.stick_key(stick) {
+                self.key_down_event(ctx, keycode, KeyMods::NONE, false);
+            }
+            self.state.stick = stick;
+        }
+    }
+}
+
+/// Sets up and runs the arcade window.
+#[derive(Clone, Debug)]
+pub struct ArcadeBuilder {
+    program: Program,
+    autopilot: bool,
+    replay: Option<InputLog>,
+    level: Option<Level>,
+    theme: Option<String>,
+    lives: bool,
+    difficulty: Option<Difficulty>,
+    pilot_seed: Option<u64>,
+    randomize: Option<Option<u64>>,
+    palette: Option<Palette>,
+    title: String,
+    icon: Option<String>,
+    size: (f32, f32),
+    vsync: bool,
+}
+
+impl ArcadeBuilder {
+    pub fn new(program: Program) -> Self {
+        Self {
+            program,
+            autopilot: false,
+            replay: None,
+            level: None,
+            theme: None,
+            lives: false,
+            difficulty: None,
+            pilot_seed: None,
+            randomize: None,
+            palette: None,
+            title: "Advent of Code 2019 Arcade".to_owned(),
+            icon: None,
+            size: (1920., 1080.),
+            vsync: true,
+        }
+    }
+
+    pub fn autopilot(mut self, autopilot: bool) -> Self {
+        self.autopilot = autopilot;
+        self
+    }
+
+    /// Input log to play back when the game starts.
+    pub fn replay(mut self, replay: InputLog) -> Self {
+        self.replay = Some(replay);
+        self
+    }
+
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Tileset theme to start with, instead of the one from the config file.
+    pub fn theme<S: Into<String>>(mut self, theme: S) -> Self {
+        self.theme = Some(theme.into());
+        self
+    }
+
+    /// The player gets a few more tries after losing the ball.
+    pub fn lives(mut self, lives: bool) -> Self {
+        self.lives = lives;
+        self
+    }
+
+    /// How well the autopilot plays, instead of the difficulty from the config file.
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    /// Seed of the autopilot's mistakes, instead of the one from the config file.
+    pub fn pilot_seed(mut self, seed: u64) -> Self {
+        self.pilot_seed = Some(seed);
+        self
+    }
+
+    /// Shuffles the blocks for every game. The first layout comes from `seed`, or a random one.
+    pub fn randomize(mut self, seed: Option<u64>) -> Self {
+        self.randomize = Some(seed);
+        self
+    }
+
+    /// Colors of the board, instead of the palette from the config file.
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Path of the window icon, relative to the resource directory, e.g. `/ball.64.png`.
+    pub fn icon<S: Into<String>>(mut self, icon: S) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Initial window size.
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    /// Opens the window and returns the score when it's closed.
+    /// Without a display, e.g. over SSH, the game is played in the terminal instead.
+    pub fn run(self) -> Result<i64, ArcadeError> {
+        if !has_display() {
+            warn!("Neither DISPLAY nor WAYLAND_DISPLAY is set");
+            return self.run_in_terminal();
+        }
+
+        let mut cb = ContextBuilder::new("Advent of Code 2019 Arcade", "Janosch Gräf");
+
+        let path = env::var("ARCADE_RESOURCE_PATH")
+            .map_err(|_| ArcadeError::ResourcePathNotSet)?;
+        let path = Path::new(&path).canonicalize()
+            .map_err(|e| ArcadeError::ResourcePath(path, e))?;
+        debug!("set path to: {}", path.display());
+        cb = cb.add_resource_path(path);
+
+        let mut window_setup = WindowSetup::default()
+            .title(&self.title)
+            .vsync(self.vsync);
+        if let Some(icon) = &self.icon {
+            window_setup = window_setup.icon(icon);
+        }
+        cb = cb.window_setup(window_setup);
+
+        let window_mode = WindowMode::default()
+            .dimensions(self.size.0, self.size.1)
+            //.maximized(true)
+            .resizable(true);
+        cb = cb.window_mode(window_mode);
+
+        let (mut ctx, mut event_loop) = match cb.build() {
+            Ok(context) => context,
+            Err(e) => {
+                warn!("Can't open a window: {}", e);
+                return self.run_in_terminal();
+            },
+        };
+
+        let config_path = env::var("ARCADE_CONFIG").unwrap_or_else(|_| "arcade.toml".to_owned());
+        let mut config = Config::default();
+        if Path::new(&config_path).exists() {
+            match Config::load(&config_path) {
+                Ok(loaded) => config = loaded,
+                Err(e) => error!("Failed to load {}: {}", config_path, e),
+            }
+        }
+
+        let theme = self.theme.as_deref().or(config.theme.as_deref());
+        let palette = self.palette.unwrap_or(config.palette);
+        let mut game = Game::new(&mut ctx, self.program, self.level.as_ref(), theme, palette)?;
+        game.state.autopilot = self.autopilot;
+        game.state.replay = self.replay;
+        game.state.keys = config.keys;
+        if self.lives || config.lives {
+            game.state.lives = Some(GameState::LIVES);
+        }
+        game.state.set_difficulty(
+            self.difficulty.unwrap_or(config.difficulty),
+            self.pilot_seed.or(config.pilot_seed).unwrap_or(Arcade::PILOT_SEED),
+        );
+        if let Some(seed) = self.randomize {
+            game.state.randomize(seed.unwrap_or_else(Randomizer::new_seed))?;
+        }
+
+        // Run!
+        event::run(&mut ctx, &mut event_loop, &mut game)?;
+        debug!("Exited cleanly.");
+
+        match game.state.error {
+            Some(e) => Err(e.into()),
+            None => Ok(game.state.score()),
+        }
+    }
+
+    /// The terminal renderer only knows the program, the level and the autopilot. Everything else is ignored.
+    fn run_in_terminal(self) -> Result<i64, ArcadeError> {
+        info!("Playing in the terminal");
+        arcade_tui::solve(self.program, self.autopilot, self.level.as_ref())
+            .map_err(ArcadeError::Terminal)
+    }
+}
+
+/// Whether a window can be opened. Only X11 and Wayland need to be told where the display is.
+fn has_display() -> bool {
+    if cfg!(all(unix, not(target_os = "macos"))) {
+        env::var_os("DISPLAY").is_some() || env::var_os("WAYLAND_DISPLAY").is_some()
+    }
+    else {
+        true
+    }
+}
+
+pub fn solve(program: Program, autopilot: bool) -> Result<i64, ArcadeError> {
+    ArcadeBuilder::new(program)
+        .autopilot(autopilot)
+        .run()
+}
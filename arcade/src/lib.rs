@@ -0,0 +1,6 @@
+#[macro_use]
+extern crate log;
+
+pub mod game;
+pub mod config;
+pub mod palette;
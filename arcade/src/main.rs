@@ -0,0 +1,72 @@
+extern crate aoc_2019;
+
+use std::fs::read_to_string;
+use std::env;
+
+use itertools::Itertools;
+
+use aoc_2019::y2019::day13::{InputLog, Level, Difficulty};
+use aoc2019_arcade::game::ArcadeBuilder;
+use aoc2019_arcade::palette::Palette;
+
+
+fn arg(name: &str) -> Option<String> {
+    env::args()
+        .skip_while(|arg| arg != name)
+        .nth(1)
+}
+
+pub fn main() {
+    aoc_2019::util::init();
+    let program = aoc_2019::inputs::load(aoc_2019::y2019::YEAR, 13).expect("Failed to load input").parse().unwrap();
+
+    let mut builder = ArcadeBuilder::new(program)
+        .lives(env::args().any(|arg| arg == "--lives"))
+        .vsync(!env::args().any(|arg| arg == "--no-vsync"));
+
+    if let Some(path) = arg("--replay") {
+        builder = builder.replay(InputLog::load(path).expect("Failed to load replay"));
+    }
+    if let Some(path) = arg("--level") {
+        let level: Level = read_to_string(path).unwrap().parse().expect("Failed to parse level");
+        builder = builder.level(level);
+    }
+    if let Some(theme) = arg("--theme") {
+        builder = builder.theme(theme);
+    }
+    if let Some(difficulty) = arg("--difficulty") {
+        let difficulty: Difficulty = difficulty.parse().expect("Invalid difficulty");
+        builder = builder.difficulty(difficulty);
+    }
+    if let Some(seed) = arg("--pilot-seed") {
+        builder = builder.pilot_seed(seed.parse().expect("Invalid seed"));
+    }
+    if let Some(seed) = arg("--seed") {
+        builder = builder.randomize(Some(seed.parse().expect("Invalid seed")));
+    }
+    else if env::args().any(|arg| arg == "--random") {
+        builder = builder.randomize(None);
+    }
+    if let Some(palette) = arg("--palette") {
+        let palette: Palette = palette.parse().expect("Unknown palette");
+        builder = builder.palette(palette);
+    }
+    if let Some(title) = arg("--title") {
+        builder = builder.title(title);
+    }
+    if let Some(icon) = arg("--icon") {
+        builder = builder.icon(icon);
+    }
+    if let Some(size) = arg("--size") {
+        let (width, height) = size.split('x')
+            .map(|n| n.parse::<f32>().expect("Invalid window size"))
+            .collect_tuple()
+            .expect("Window size must look like 1280x720");
+        builder = builder.size(width, height);
+    }
+
+    if let Err(e) = builder.run() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+use std::str::FromStr;
+
+use failure::Fail;
+use ggez::graphics::{self, Color};
+
+use aoc_2019::y2019::day13::Tile;
+
+
+#[derive(Debug, Fail)]
+#[fail(display = "Unknown palette: {}", _0)]
+pub struct UnknownPalette(pub String);
+
+/// Colors of the board. Except for `GameBoy`, the sprites are turned into grayscale when they're loaded, and then
+/// tinted with the tile's color when they're drawn.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Palette {
+    /// The colors of the sprites.
+    #[default]
+    GameBoy,
+    Grayscale,
+    /// Bright tiles on black, in colors that stay apart for most color-vision deficiencies.
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [Palette::GameBoy, Palette::Grayscale, Palette::HighContrast];
+
+    /// The clear color, which also shows behind tiles that are redrawn.
+    pub fn background(self) -> Color {
+        match self {
+            Self::GameBoy => Color::from_rgb(0x0f, 0x38, 0x0f),
+            Self::Grayscale => Color::from_rgb(0x10, 0x10, 0x10),
+            Self::HighContrast => Color::from_rgb(0, 0, 0),
+        }
+    }
+
+    /// Whether the sprites need to be turned into grayscale, so that tinting gives the tile's color.
+    pub fn desaturate(self) -> bool {
+        self != Self::GameBoy
+    }
+
+    /// Color the sprite of a tile is multiplied with.
+    pub fn tint(self, tile: Tile) -> Color {
+        match (self, tile) {
+            (Self::GameBoy, _) => graphics::WHITE,
+            (Self::Grayscale, Tile::Empty) => Color::new(0.15, 0.15, 0.15, 1.),
+            (Self::Grayscale, Tile::Wall) => Color::new(0.6, 0.6, 0.6, 1.),
+            (Self::Grayscale, _) => graphics::WHITE,
+            (Self::HighContrast, Tile::Empty) => Color::new(0., 0., 0., 1.),
+            (Self::HighContrast, Tile::Wall) => Color::new(0.5, 0.5, 0.5, 1.),
+            (Self::HighContrast, Tile::Block) => Color::new(1., 0.8, 0., 1.),
+            (Self::HighContrast, Tile::Paddle) => Color::new(0.2, 0.6, 1., 1.),
+            (Self::HighContrast, Tile::Ball) => graphics::WHITE,
+        }
+    }
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::GameBoy => "gameboy",
+            Self::Grayscale => "grayscale",
+            Self::HighContrast => "high-contrast",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Palette {
+    type Err = UnknownPalette;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.iter()
+            .find(|palette| palette.to_string() == s)
+            .copied()
+            .ok_or_else(|| UnknownPalette(s.to_owned()))
+    }
+}
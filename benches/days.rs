@@ -0,0 +1,68 @@
+//! One benchmark group per day, with the generator and each part benchmarked separately. Days without an input in
+//! the input directory are skipped, since benchmarks shouldn't download anything. So are days with their feature
+//! disabled.
+
+use std::fs::read_to_string;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use aoc_2019::{cache, inputs, y2019};
+use aoc_2019::progress::{self, Mode};
+
+
+macro_rules! bench_day {
+    ($c:expr, $n:expr, $day:ident, [$($part:ident),*]) => {
+        match read_to_string(inputs::path(y2019::YEAR, $n)) {
+            Ok(input) => {
+                let parsed = aoc_2019::y2019::$day::input_generator(&input).expect("Failed to parse input");
+                let mut group = $c.benchmark_group(concat!("day ", $n));
+                // Some parts take seconds, so the minimum number of samples has to do.
+                group.sample_size(10);
+                group.bench_function("generator", |b| b.iter(|| aoc_2019::y2019::$day::input_generator(black_box(&input))));
+                let mut part = 0;
+                $(
+                    part += 1;
+                    group.bench_function(format!("part {}", part), |b| b.iter(|| aoc_2019::y2019::$day::$part(black_box(&parsed))));
+                )*
+                group.finish();
+            },
+            Err(_) => eprintln!("Skipping day {}: no input at {}", $n, inputs::path(y2019::YEAR, $n).display()),
+        }
+    };
+}
+
+fn days(c: &mut Criterion) {
+    // Progress bars would mess up criterion's output.
+    progress::set_mode(Mode::Silent);
+    cache::set_enabled(false);
+
+    #[cfg(feature="day1")]
+    bench_day!(c, 1, day1, [solve_part1, solve_part2]);
+    #[cfg(feature="day2")]
+    bench_day!(c, 2, day2, [solve_part1, solve_part2]);
+    #[cfg(feature="day3")]
+    bench_day!(c, 3, day3, [solve_part1, solve_part2]);
+    #[cfg(feature="day4")]
+    bench_day!(c, 4, day4, [solve_part1]);
+    #[cfg(feature="day5")]
+    bench_day!(c, 5, day5, [solve_part1, solve_part2]);
+    #[cfg(feature="day6")]
+    bench_day!(c, 6, day6, [solve_part1, solve_part2]);
+    #[cfg(feature="day7")]
+    bench_day!(c, 7, day7, [solve_part1, solve_part2]);
+    #[cfg(feature="day8")]
+    bench_day!(c, 8, day8, [solve_part1, solve_part2]);
+    #[cfg(feature="day9")]
+    bench_day!(c, 9, day9, [solve_part1, solve_part2]);
+    #[cfg(feature="day10")]
+    bench_day!(c, 10, day10, [solve_part1, solve_part2]);
+    #[cfg(feature="day11")]
+    bench_day!(c, 11, day11, [solve_part1, solve_part2]);
+    #[cfg(feature="day12")]
+    bench_day!(c, 12, day12, [solve_part1, solve_part2]);
+    #[cfg(feature="day13")]
+    bench_day!(c, 13, day13, [solve_part1, solve_part2]);
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
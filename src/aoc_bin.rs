@@ -0,0 +1,229 @@
+extern crate aoc_2019;
+
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+use aoc_2019::{cache, inputs, registry, report, runner, y2019};
+use aoc_2019::prelude::*;
+use aoc_2019::util::{self, Verbosity};
+#[cfg(feature="profiling")]
+use aoc_2019::profiling::{self, CountingAlloc};
+
+#[cfg(feature="profiling")]
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+fn usage() -> ! {
+    eprintln!("Usage: aoc run [--no-cache] [<options>] [<day>...]");
+    eprintln!("       aoc bench [--runs <n>] [--warmup <n>] [<options>] [<day>...]");
+    eprintln!("       aoc profile [--flamegraph <dir>] [<options>] [<day>...]");
+    eprintln!("Options: --year <year> (default {}), --timeout <s>, --format text|pretty|json, --quiet, --verbose", y2019::YEAR);
+    process::exit(1);
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Format {
+    Text,
+    /// Checked against the known answers, and in color in a terminal.
+    Pretty,
+    Json,
+}
+
+struct Options {
+    year: u32,
+    runs: usize,
+    /// Untimed runs before the timed ones.
+    warmup: usize,
+    /// The time budget of a day, so that one hanging day doesn't block the others.
+    timeout: Duration,
+    format: Format,
+    /// Where `profile` puts a flamegraph per day.
+    flamegraph: Option<PathBuf>,
+    days: Vec<u32>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Self {
+        let mut options = Options {
+            year: y2019::YEAR,
+            runs: 10,
+            warmup: 1,
+            timeout: Duration::from_secs(60),
+            format: Format::Text,
+            flamegraph: None,
+            days: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--year" => options.year = args.next().and_then(|year| year.parse().ok()).unwrap_or_else(|| usage()),
+                "--runs" => options.runs = args.next().and_then(|runs| runs.parse().ok()).unwrap_or_else(|| usage()),
+                "--warmup" => options.warmup = args.next()
+                    .and_then(|warmup| warmup.parse().ok())
+                    .unwrap_or_else(|| usage()),
+                "--timeout" => options.timeout = args.next()
+                    .and_then(|timeout| timeout.parse().ok())
+                    .map(Duration::from_secs_f64)
+                    .unwrap_or_else(|| usage()),
+                "--format" => options.format = match args.next().map(String::as_str) {
+                    Some("text") => Format::Text,
+                    Some("pretty") => Format::Pretty,
+                    Some("json") => Format::Json,
+                    _ => usage(),
+                },
+                "--flamegraph" => options.flamegraph = Some(args.next().unwrap_or_else(|| usage()).into()),
+                "--no-cache" => cache::set_enabled(false),
+                "--quiet" => util::set_verbosity(Verbosity::Quiet),
+                "--verbose" => util::set_verbosity(Verbosity::Verbose),
+                day => options.days.push(day.parse().unwrap_or_else(|_| usage())),
+            }
+        }
+        options
+    }
+
+    /// Calls `f` with the selected days and their inputs on a worker thread, and collects the results of the days that
+    /// finish within the timeout. Days without an input are skipped.
+    fn for_each_day<T, F>(&self, f: F) -> Vec<T>
+        where T: Send + 'static,
+              F: Fn(&Day, &str) -> Result<Vec<T>, Error> + Clone + Send + 'static,
+    {
+        let days = registry::days_of(self.year);
+        if days.is_empty() {
+            eprintln!("No days of {}", self.year);
+        }
+
+        let mut results = Vec::new();
+        for day in days {
+            if !self.days.is_empty() && !self.days.contains(&day.day) {
+                continue;
+            }
+            let input = match inputs::load(day.year, day.day) {
+                Ok(input) => input,
+                Err(e) => {
+                    eprintln!("Skipping day {}: {}", day.day, e);
+                    continue;
+                },
+            };
+            let n = day.day;
+            let f = f.clone();
+            match runner::run_with_timeout(move || f(&day, &input), self.timeout) {
+                Ok(day_results) => results.extend(day_results),
+                Err(e) => eprintln!("Day {} failed: {}", n, e),
+            }
+        }
+        results
+    }
+}
+
+fn run(options: &Options) {
+    let solutions = options.for_each_day(|day, input| {
+        #[cfg(feature="spans")]
+        aoc_2019::spans::reset();
+        let solutions = runner::run(day, input);
+        // On stderr, so that it doesn't get into the way of `--format json`.
+        #[cfg(feature="spans")]
+        eprintln!("Spans of day {}:\n{}", day.day, aoc_2019::spans::report());
+        solutions
+    });
+
+    if options.format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(&solutions).expect("Failed to serialize solutions"));
+        return;
+    }
+
+    if options.format == Format::Pretty {
+        let answers = inputs::answers(options.year).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            Default::default()
+        });
+        let colored = io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+        println!("{}", report::pretty(&solutions, &answers, colored));
+        return;
+    }
+
+    for solution in &solutions {
+        let peak = solution.peak_bytes.map(|bytes| format!(", {} peak", util::format_bytes(bytes))).unwrap_or_default();
+        println!(
+            "Day {} part {} ({:.3} ms{}): {}", solution.day, solution.part, solution.duration_ms, peak, solution.answer
+        );
+    }
+}
+
+fn bench(options: &Options) {
+    // Otherwise we'd measure cache hits.
+    cache::set_enabled(false);
+    let (runs, warmup) = (options.runs, options.warmup);
+    let benchmarks = options.for_each_day(move |day, input| runner::bench(day, input, runs, warmup));
+
+    if options.format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(&benchmarks).expect("Failed to serialize benchmarks"));
+        return;
+    }
+
+    println!(
+        "{:>3}  {:<9}  {:>23}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "day", "step", "mean ms ± stddev", "min ms", "median ms", "max ms", "total ms"
+    );
+    for benchmark in &benchmarks {
+        println!(
+            "{:>3}  {:<9}  {:>10.3} ± {:<10.3}  {:>10.3}  {:>10.3}  {:>10.3}  {:>10.3}",
+            benchmark.day, benchmark.step.to_string(), benchmark.mean_ms, benchmark.stddev_ms, benchmark.min_ms,
+            benchmark.median_ms, benchmark.max_ms, benchmark.total_ms
+        );
+    }
+    let median: f64 = benchmarks.iter().map(|benchmark| benchmark.median_ms).sum();
+    println!(
+        "Sum of medians: {:.3} ms over {} runs each, after {} warmup runs", median, options.runs, options.warmup
+    );
+}
+
+#[cfg(feature="profiling")]
+fn profile(options: &Options) {
+    // Like `bench`, we want to know how long the solvers take, not the cache.
+    cache::set_enabled(false);
+    let flamegraph = options.flamegraph.clone();
+    let profiles = options.for_each_day(move |day, input| match &flamegraph {
+        #[cfg(feature="flamegraph")]
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            let path = dir.join(format!("{}-day{}.svg", day.year, day.day));
+            let profiles = profiling::profile_with_flamegraph(day, input, &path)?;
+            eprintln!("Wrote {}", path.display());
+            Ok(profiles)
+        },
+        #[cfg(not(feature="flamegraph"))]
+        Some(_) => {
+            eprintln!("--flamegraph needs --features flamegraph");
+            process::exit(1);
+        },
+        None => profiling::profile(day, input),
+    });
+
+    if options.format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(&profiles).expect("Failed to serialize profiles"));
+        return;
+    }
+    println!("{}", profiling::report(&profiles));
+}
+
+#[cfg(not(feature="profiling"))]
+fn profile(_options: &Options) {
+    eprintln!("Profiling needs --features profiling");
+    process::exit(1);
+}
+
+pub fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = Options::parse(args.get(1 ..).unwrap_or_default());
+    util::init();
+
+    match args.first().map(String::as_str) {
+        Some("run") => run(&options),
+        Some("bench") => bench(&options),
+        Some("profile") => profile(&options),
+        _ => usage(),
+    }
+}
@@ -0,0 +1,14 @@
+extern crate aoc_2019;
+
+use std::env;
+
+use aoc_2019::y2019;
+
+
+pub fn main() {
+    let year = env::args()
+        .skip_while(|arg| arg != "--year")
+        .nth(1)
+        .map_or(y2019::YEAR, |year| year.parse().expect("Invalid year"));
+    aoc_2019::dashboard::run(year).expect("Terminal error");
+}
@@ -0,0 +1,41 @@
+extern crate aoc_2019;
+
+use std::fs::read_to_string;
+use std::env;
+
+use aoc_2019::{inputs, y2019};
+use aoc_2019::y2019::day13::{Arcade, Autopilot, Level, Difficulty};
+
+
+pub fn main() {
+    aoc_2019::util::init();
+    let input = match env::args().skip_while(|arg| arg != "--input").nth(1) {
+        Some(path) => read_to_string(path).unwrap(),
+        None => inputs::load(y2019::YEAR, 13).expect("Failed to load input"),
+    };
+    let program = input.parse().unwrap();
+    let strategy = if env::args().any(|arg| arg == "--chase") { Autopilot::Chase } else { Autopilot::Predict };
+    let level: Option<Level> = env::args()
+        .skip_while(|arg| arg != "--level")
+        .nth(1)
+        .map(|path| read_to_string(path).unwrap().parse().expect("Failed to parse level"));
+    let difficulty: Difficulty = env::args()
+        .skip_while(|arg| arg != "--difficulty")
+        .nth(1)
+        .map(|difficulty| difficulty.parse().expect("Invalid difficulty"))
+        .unwrap_or_default();
+    let seed = env::args()
+        .skip_while(|arg| arg != "--pilot-seed")
+        .nth(1)
+        .map_or(Arcade::PILOT_SEED, |seed| seed.parse().expect("Invalid seed"));
+
+    let mut arcade = Arcade::new(program);
+    arcade.set_difficulty(difficulty, seed);
+    arcade.load_screen().expect("Arcade failed to load screen");
+    if let Some(level) = &level {
+        arcade.load_level(level).expect("Failed to load level");
+    }
+
+    let benchmark = arcade.benchmark(strategy).expect("Arcade failed");
+    println!("{}", benchmark);
+}
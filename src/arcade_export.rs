@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+use failure::Fail;
+use image::{Delay, Frame, RgbaImage};
+use image::codecs::gif::{GifEncoder, Repeat};
+use itertools::Itertools;
+
+use crate::intcode::{Program, Error as IntcodeError};
+use crate::y2019::day13::{Arcade, Error as ArcadeError, Tile, Screen, InputLog, Level};
+
+
+#[derive(Debug, Fail)]
+pub enum ExportError {
+    #[fail(display = "Arcade error: {}", _0)]
+    Arcade(#[cause] ArcadeError),
+    #[fail(display = "Image error: {}", _0)]
+    Image(#[cause] image::ImageError),
+    #[fail(display = "IO error: {}", _0)]
+    Io(#[cause] io::Error),
+    #[fail(display = "Sprite {:?} is {}x{}, but the tile size is {}", _0, _1, _2, _3)]
+    SpriteSize(Tile, u32, u32, u32),
+}
+
+impl From<ArcadeError> for ExportError {
+    fn from(e: ArcadeError) -> Self {
+        Self::Arcade(e)
+    }
+}
+
+impl From<image::ImageError> for ExportError {
+    fn from(e: image::ImageError) -> Self {
+        Self::Image(e)
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// The game's sprites, loaded from the same files as the window frontend uses.
+#[derive(Clone, Debug)]
+pub struct Tileset {
+    tiles: HashMap<Tile, RgbaImage>,
+    size: u32,
+}
+
+impl Tileset {
+    /// Loads `<name>.<size>.png` for every tile from a directory, e.g. `resources/ball.8.png`.
+    pub fn load<P: AsRef<Path>>(dir: P, size: u32) -> Result<Self, ExportError> {
+        let names = [
+            (Tile::Empty, "empty"),
+            (Tile::Wall, "wall"),
+            (Tile::Block, "block"),
+            (Tile::Paddle, "paddle"),
+            (Tile::Ball, "ball"),
+        ];
+
+        let mut tiles = HashMap::new();
+        for (tile, name) in names.iter() {
+            let sprite = image::open(dir.as_ref().join(format!("{}.{}.png", name, size)))?.to_rgba8();
+            if sprite.dimensions() != (size, size) {
+                return Err(ExportError::SpriteSize(*tile, sprite.width(), sprite.height(), size));
+            }
+            tiles.insert(*tile, sprite);
+        }
+
+        Ok(Self { tiles, size })
+    }
+
+    pub fn render(&self, screen: &Screen) -> RgbaImage {
+        let (width, height) = screen.screen_size().unwrap_or((0, 0));
+        let mut image = RgbaImage::new(width as u32 * self.size, height as u32 * self.size);
+
+        for (y, x) in (0 .. height).cartesian_product(0 .. width) {
+            let tile = screen.framebuffer.get(&(x, y)).copied().unwrap_or_default();
+            image::imageops::replace(&mut image, &self.tiles[&tile], x * i64::from(self.size), y * i64::from(self.size));
+        }
+
+        image
+    }
+}
+
+/// Plays a replay from the start and calls `f` with every frame, until the log runs out or the game is over.
+/// Returns the number of frames.
+pub fn replay_frames<F>(program: Program, replay: &InputLog, level: Option<&Level>, mut f: F) -> Result<usize, ExportError>
+    where F: FnMut(&Screen) -> Result<(), ExportError>
+{
+    let mut arcade = Arcade::new(program);
+    arcade.load_screen()?;
+    if let Some(level) = level {
+        arcade.load_level(level)?;
+    }
+    arcade.replay(replay);
+
+    let mut frames = 1;
+    f(&arcade.screen)?;
+
+    while arcade.is_replaying() {
+        match arcade.wait_frame() {
+            Ok(_) => {},
+            Err(ArcadeError::Intcode(IntcodeError::Halted)) => break,
+            Err(e) => return Err(e.into()),
+        }
+        f(&arcade.screen)?;
+        frames += 1;
+    }
+
+    Ok(frames)
+}
+
+/// Renders a replay into an animated GIF that loops forever. An `fps` of 0 is taken as 1.
+pub fn export_gif<P: AsRef<Path>>(program: Program, replay: &InputLog, level: Option<&Level>, tileset: &Tileset, fps: u32, path: P) -> Result<usize, ExportError> {
+    let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+    encoder.set_repeat(Repeat::Infinite)?;
+    let delay = Delay::from_numer_denom_ms(1000, fps.max(1));
+
+    replay_frames(program, replay, level, |screen| {
+        encoder.encode_frame(Frame::from_parts(tileset.render(screen), 0, 0, delay))?;
+        Ok(())
+    })
+}
+
+/// Renders a replay into numbered PNGs, e.g. `frame-00042.png`.
+pub fn export_png_sequence<P: AsRef<Path>>(program: Program, replay: &InputLog, level: Option<&Level>, tileset: &Tileset, dir: P) -> Result<usize, ExportError> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    let mut index = 0;
+    replay_frames(program, replay, level, |screen| {
+        tileset.render(screen).save_with_format(dir.join(format!("frame-{:05}.png", index)), image::ImageFormat::Png)?;
+        index += 1;
+        Ok(())
+    })
+}
@@ -0,0 +1,40 @@
+extern crate aoc_2019;
+
+use std::fs::read_to_string;
+use std::env;
+
+use aoc_2019::{inputs, y2019};
+use aoc_2019::y2019::day13::{InputLog, Level};
+use aoc_2019::arcade_export::{self, Tileset};
+
+
+fn arg(name: &str) -> Option<String> {
+    env::args()
+        .skip_while(|arg| arg != name)
+        .nth(1)
+}
+
+pub fn main() {
+    aoc_2019::util::init();
+    let program = inputs::load(y2019::YEAR, 13).expect("Failed to load input").parse().unwrap();
+
+    let replay = InputLog::load(arg("--replay").unwrap_or_else(|| "arcade.log".to_owned()))
+        .expect("Failed to load replay");
+    let output = arg("--output").unwrap_or_else(|| "arcade.gif".to_owned());
+    let level: Option<Level> = arg("--level")
+        .map(|path| read_to_string(path).unwrap().parse().expect("Failed to parse level"));
+    let size = arg("--size").map(|size| size.parse().expect("Invalid tile size")).unwrap_or(8);
+    let fps = arg("--fps").map(|fps| fps.parse().expect("Invalid frame rate")).unwrap_or(10);
+
+    let resources = aoc_2019::config::get().resources();
+    let tileset = Tileset::load(resources, size).expect("Failed to load tileset");
+
+    let frames = if output.ends_with(".gif") {
+        arcade_export::export_gif(program, &replay, level.as_ref(), &tileset, fps, &output)
+    }
+    else {
+        arcade_export::export_png_sequence(program, &replay, level.as_ref(), &tileset, &output)
+    }.expect("Export failed");
+
+    println!("Wrote {} frames to {}", frames, output);
+}
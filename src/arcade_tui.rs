@@ -0,0 +1,118 @@
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::{cursor, execute, queue, terminal};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::Print;
+use failure::Fail;
+
+use crate::intcode::{Program, Error as IntcodeError};
+use crate::terminal::TerminalGuard;
+use crate::y2019::day13::{Arcade, Error as ArcadeError, JoystickPosition, Level};
+
+
+#[derive(Debug, Fail)]
+pub enum Error {
+    #[fail(display = "Arcade error: {}", _0)]
+    Arcade(#[cause] ArcadeError),
+    #[fail(display = "Terminal error: {}", _0)]
+    Terminal(#[cause] io::Error),
+}
+
+impl From<ArcadeError> for Error {
+    fn from(e: ArcadeError) -> Self {
+        Self::Arcade(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Terminal(e)
+    }
+}
+
+enum Input {
+    Joystick(JoystickPosition),
+    ToggleAutopilot,
+    Quit,
+}
+
+fn read_input(timeout: Duration) -> Result<Vec<Input>, Error> {
+    let deadline = Instant::now() + timeout;
+    let mut inputs = Vec::new();
+
+    loop {
+        let now = Instant::now();
+        if now >= deadline || !event::poll(deadline - now)? {
+            break;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('a') | KeyCode::Left => inputs.push(Input::Joystick(JoystickPosition::Left)),
+                KeyCode::Char('d') | KeyCode::Right => inputs.push(Input::Joystick(JoystickPosition::Right)),
+                KeyCode::F(7) => inputs.push(Input::ToggleAutopilot),
+                KeyCode::Esc | KeyCode::Char('q') => inputs.push(Input::Quit),
+                _ => {},
+            }
+        }
+    }
+
+    Ok(inputs)
+}
+
+fn render(stdout: &mut Stdout, arcade: &Arcade, autopilot: bool) -> Result<(), Error> {
+    // Raw mode doesn't return the cursor to the start of the line.
+    let screen = arcade.screen.to_string().replace('\n', "\r\n");
+    let status = if autopilot { "AUTO" } else { "A/D or arrows: move, F7: auto pilot, ESC: exit" };
+
+    queue!(stdout, cursor::MoveTo(0, 0), Print(screen), Print("\r\n"), Print(status), terminal::Clear(terminal::ClearType::UntilNewLine))?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Plays the arcade game in the terminal and returns the final score.
+pub fn solve(program: Program, mut autopilot: bool, level: Option<&Level>) -> Result<i64, Error> {
+    let frame_time = Duration::from_millis(50);
+
+    let mut arcade = Arcade::new(program);
+    arcade.load_screen()?;
+    if let Some(level) = level {
+        arcade.load_level(level)?;
+    }
+
+    let mut guard = TerminalGuard::new()?;
+    execute!(guard.stdout, terminal::Clear(terminal::ClearType::All))?;
+
+    'game: loop {
+        render(&mut guard.stdout, &arcade, autopilot)?;
+
+        // Terminals don't report key releases, so the paddle only moves in frames in which a key was pressed.
+        let mut joystick = JoystickPosition::Neutral;
+        for input in read_input(frame_time)? {
+            match input {
+                Input::Joystick(position) => joystick = position,
+                Input::ToggleAutopilot => autopilot = !autopilot,
+                Input::Quit => break 'game,
+            }
+        }
+
+        if autopilot {
+            arcade.autopilot()?;
+        }
+        else {
+            arcade.set_joystick(joystick);
+        }
+
+        match arcade.wait_frame() {
+            Err(ArcadeError::Intcode(IntcodeError::Halted)) => break,
+            result => { result?; },
+        }
+    }
+
+    Ok(arcade.screen.score)
+}
@@ -0,0 +1,20 @@
+extern crate aoc_2019;
+
+use std::fs::read_to_string;
+use std::env;
+
+use aoc_2019::{inputs, y2019};
+use aoc_2019::y2019::day13::Level;
+
+
+pub fn main() {
+    aoc_2019::util::init();
+    let program = inputs::load(y2019::YEAR, 13).expect("Failed to load input").parse().unwrap();
+    let autopilot = aoc_2019::config::get().visualization.autopilot || env::args().any(|arg| arg == "--autopilot");
+    let level: Option<Level> = env::args()
+        .skip_while(|arg| arg != "--level")
+        .nth(1)
+        .map(|path| read_to_string(path).unwrap().parse().expect("Failed to parse level"));
+    let score = aoc_2019::arcade_tui::solve(program, autopilot, level.as_ref()).unwrap();
+    println!("Score: {}", score);
+}
@@ -0,0 +1,170 @@
+//! Keeps slow intermediate results on disk, so that they survive between runs. Entries are keyed by day, part, the
+//! version of the code that computes them and a hash of the input, and stored as TOML in the cache directory
+//! (`cache_dir` in `aoc.toml`).
+//!
+//! The cache is only an optimization: if it can't be read or written, we log a warning and compute the value. Unit
+//! tests never use it, so that they test the computation and not an old entry.
+
+use std::convert::Infallible;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::config;
+
+
+static ENABLED: AtomicU8 = AtomicU8::new(UNSET);
+
+const UNSET: u8 = u8::MAX;
+
+
+/// TOML files need a table at the top.
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    value: T,
+}
+
+/// Overrides the configuration, e.g. for `--no-cache`.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled as u8, Ordering::Relaxed);
+}
+
+/// Whether the cache is used. Defaults to `cache` from the configuration, which defaults to yes, except in unit tests.
+pub fn enabled() -> bool {
+    match ENABLED.load(Ordering::Relaxed) {
+        UNSET => !cfg!(test) && config::get().cache.unwrap_or(true),
+        enabled => enabled != 0,
+    }
+}
+
+/// FNV-1a. Unlike `DefaultHasher`, it hashes the same with every Rust release, so entries stay valid.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x100_0000_01b3);
+        }
+    }
+}
+
+fn path<K: Hash + ?Sized>(day: u32, part: usize, version: u32, key: &K) -> PathBuf {
+    let mut hasher = Fnv::default();
+    key.hash(&mut hasher);
+    config::get().cache_dir().join(format!("day{}-part{}-v{}-{:016x}.toml", day, part, version, hasher.finish()))
+}
+
+fn load<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let data = fs::read_to_string(path).ok()?;
+    match toml::from_str::<Entry<T>>(&data) {
+        Ok(entry) => Some(entry.value),
+        Err(e) => {
+            warn!("Ignoring invalid cache entry {}: {}", path.display(), e);
+            None
+        },
+    }
+}
+
+fn store<T: Serialize>(path: &Path, value: T) {
+    let result = toml::to_string(&Entry { value })
+        .map_err(|e| e.to_string())
+        .and_then(|data| {
+            let dir = path.parent().expect("Cache entry without directory");
+            // Write to a temporary file first, so that other runs never see half an entry.
+            let tmp = path.with_extension("tmp");
+            fs::create_dir_all(dir)
+                .and_then(|_| fs::write(&tmp, data))
+                .and_then(|_| fs::rename(&tmp, path))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("Failed to write cache entry {}: {}", path.display(), e);
+    }
+}
+
+/// Returns the cached value for `key`, or computes it with `f` and caches it. `key` should be the parsed input, or
+/// whatever else the value depends on. Bump `version` whenever `f` changes its results, e.g. with a bug fix, so that
+/// old entries aren't used anymore.
+pub fn get_or_insert_with<K, T, F>(day: u32, part: usize, version: u32, key: &K, f: F) -> T
+where
+    K: Hash + ?Sized,
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> T,
+{
+    match try_get_or_insert_with(day, part, version, key, || Ok::<_, Infallible>(f())) {
+        Ok(value) => value,
+        Err(never) => match never {},
+    }
+}
+
+/// Like `get_or_insert_with`, but `f` may fail. Errors are returned and not cached.
+pub fn try_get_or_insert_with<K, T, E, F>(day: u32, part: usize, version: u32, key: &K, f: F) -> Result<T, E>
+where
+    K: Hash + ?Sized,
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, E>,
+{
+    if !enabled() {
+        return f();
+    }
+    cached(&path(day, part, version, key), f)
+}
+
+fn cached<T, E, F>(path: &Path, f: F) -> Result<T, E>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, E>,
+{
+    if let Some(value) = load(path) {
+        debug!("Using cached value from {}", path.display());
+        return Ok(value);
+    }
+
+    let value = f()?;
+    store(path, &value);
+    Ok(value)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reuses_values() {
+        let path = std::env::temp_dir().join(format!("aoc-cache-test-{}.toml", std::process::id()));
+
+        assert_eq!(cached(&path, || Ok::<_, ()>(vec![1usize, 2, 3])), Ok(vec![1, 2, 3]));
+        assert_eq!(cached(&path, || -> Result<Vec<usize>, ()> { unreachable!() }), Ok(vec![1, 2, 3]));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(cached(&path, || Err::<usize, _>("failed")), Err("failed"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn unit_tests_dont_use_the_cache() {
+        assert!(!enabled());
+    }
+
+    #[test]
+    fn keys_hash_the_same_everywhere() {
+        // The FNV-1a test vector for "a".
+        let mut hasher = Fnv::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+}
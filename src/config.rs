@@ -0,0 +1,180 @@
+//! Runtime configuration from `aoc.toml` in the crate root (or the file `AOC_CONFIG` points to). Environment
+//! variables, also from `.env`, override the file:
+//!
+//! ```toml
+//! verbosity = "verbose"       # AOC_VERBOSITY
+//! input_dir = "input"         # AOC_INPUT_DIR, with a directory per year
+//! session_file = "session"    # AOC_SESSION_FILE, or pass the token itself in AOC_SESSION
+//! threads = 2                 # AOC_THREADS
+//! cache = true                # AOC_CACHE, keep slow intermediate results on disk
+//! cache_dir = "target/cache"  # AOC_CACHE_DIR
+//!
+//! [visualization]
+//! progress = "bar"            # AOC_PROGRESS
+//! autopilot = true            # Start arcade_tui on auto pilot
+//! resources = "resources"     # ARCADE_RESOURCE_PATH
+//! ```
+//!
+//! Relative paths in the file are relative to the file.
+
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::thread;
+
+use failure::Fail;
+use serde::{Deserialize, Deserializer};
+use serde::de::Error as _;
+
+use crate::progress::Mode;
+use crate::util::Verbosity;
+
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Fail)]
+pub enum ConfigError {
+    #[fail(display = "Failed to read {}: {}", _0, _1)]
+    Io(String, #[cause] io::Error),
+    #[fail(display = "Invalid {}: {}", _0, _1)]
+    Toml(String, #[cause] toml::de::Error),
+    #[fail(display = "Invalid value for {}: {}", _0, _1)]
+    Env(&'static str, String),
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(deserialize_with = "parse")]
+    pub verbosity: Option<Verbosity>,
+    pub input_dir: Option<PathBuf>,
+    pub session_file: Option<PathBuf>,
+    /// How many threads a solver may use. Defaults to the number of CPUs.
+    pub threads: Option<usize>,
+    /// Whether to use the on-disk cache. Defaults to yes.
+    pub cache: Option<bool>,
+    pub cache_dir: Option<PathBuf>,
+    pub visualization: Visualization,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Visualization {
+    #[serde(deserialize_with = "parse")]
+    pub progress: Option<Mode>,
+    pub autopilot: bool,
+    /// Tile sets for `arcade_export`.
+    pub resources: Option<PathBuf>,
+}
+
+/// Deserializes anything with `FromStr` from a string, e.g. `Verbosity`.
+fn parse<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where D: Deserializer<'de>,
+          T: FromStr,
+          T::Err: Display,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map(Some).map_err(D::Error::custom)
+}
+
+fn env_var<T>(name: &'static str) -> Result<Option<T>, ConfigError>
+    where T: FromStr,
+          T::Err: Display,
+{
+    match env::var(name) {
+        Ok(value) => value.parse().map(Some).map_err(|e: T::Err| ConfigError::Env(name, e.to_string())),
+        Err(_) => Ok(None),
+    }
+}
+
+impl Config {
+    /// The default location of `aoc.toml`.
+    pub fn path() -> PathBuf {
+        env::var_os("AOC_CONFIG")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("aoc.toml"))
+    }
+
+    /// Reads a config file. A missing file is an empty config.
+    pub fn load_file(path: &Path) -> Result<Self, ConfigError> {
+        let s = match fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(ConfigError::Io(path.display().to_string(), e)),
+        };
+        let mut config: Self = toml::from_str(&s)
+            .map_err(|e| ConfigError::Toml(path.display().to_string(), e))?;
+
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let paths = [
+            &mut config.input_dir, &mut config.session_file, &mut config.cache_dir, &mut config.visualization.resources,
+        ];
+        for path in IntoIterator::into_iter(paths).flatten() {
+            *path = dir.join(&*path);
+        }
+        Ok(config)
+    }
+
+    /// Overrides the settings that have an environment variable set.
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        self.verbosity = env_var("AOC_VERBOSITY")?.or(self.verbosity);
+        self.input_dir = env_var("AOC_INPUT_DIR")?.or_else(|| self.input_dir.take());
+        self.session_file = env_var("AOC_SESSION_FILE")?.or_else(|| self.session_file.take());
+        self.threads = env_var("AOC_THREADS")?.or(self.threads);
+        self.cache = env_var("AOC_CACHE")?.or(self.cache);
+        self.cache_dir = env_var("AOC_CACHE_DIR")?.or_else(|| self.cache_dir.take());
+        self.visualization.progress = env_var("AOC_PROGRESS")?.or(self.visualization.progress);
+        self.visualization.resources = env_var("ARCADE_RESOURCE_PATH")?
+            .or_else(|| self.visualization.resources.take());
+        Ok(())
+    }
+
+    pub fn input_dir(&self) -> PathBuf {
+        self.input_dir.clone()
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("input"))
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+            .or_else(|| thread::available_parallelism().ok().map(usize::from))
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    pub fn cache_dir(&self) -> PathBuf {
+        self.cache_dir.clone()
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("target/cache"))
+    }
+
+    pub fn resources(&self) -> PathBuf {
+        self.visualization.resources.clone()
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("resources"))
+    }
+}
+
+/// Uses `config` instead of loading one. Fails if the configuration is in use already.
+// Returning the config like `OnceLock::set` does is fine, since this is called once.
+#[allow(clippy::result_large_err)]
+pub fn set(config: Config) -> Result<(), Config> {
+    CONFIG.set(config)
+}
+
+/// The configuration, loaded with `.env` on first use. Since this happens before logging is set up, errors are printed
+/// and the defaults are used instead.
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| {
+        dotenv::dotenv().ok();
+        let mut config = Config::load_file(&Config::path()).unwrap_or_else(|e| {
+            eprintln!("Ignoring configuration: {}", e);
+            Config::default()
+        });
+        if let Err(e) = config.apply_env() {
+            eprintln!("Ignoring environment: {}", e);
+        }
+        config
+    })
+}
@@ -0,0 +1,325 @@
+//! A terminal dashboard that lists the days of a year, runs them on demand and shows their answers, timings and the log.
+
+use std::collections::VecDeque;
+use std::io::{self, Stdout, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossterm::{cursor, queue, terminal};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::Print;
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::inputs;
+use crate::progress;
+use crate::registry;
+use crate::runner::{self, Solution};
+use crate::terminal::TerminalGuard;
+use crate::util::{self, Verbosity};
+
+
+const LIST_WIDTH: usize = 26;
+const MAX_LOG_LINES: usize = 1000;
+
+type LogLines = Arc<Mutex<VecDeque<String>>>;
+
+/// Keeps log messages for the log pane, since writing to the terminal would mess up the screen.
+struct Logger {
+    level: LevelFilter,
+    lines: LogLines,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let mut lines = self.lines.lock().unwrap();
+        for line in record.args().to_string().lines() {
+            if lines.len() == MAX_LOG_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(format!("{:<5} {} > {}", record.level(), record.target(), line));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the logger before anything calls `util::init`. We log solver progress by default, since that's what the
+/// log pane is for.
+fn init_logger() -> LogLines {
+    let level = match util::verbosity() {
+        Verbosity::Normal => LevelFilter::Info,
+        verbosity => verbosity.level_filter(),
+    };
+    let lines = LogLines::default();
+    if log::set_boxed_logger(Box::new(Logger { level, lines: Arc::clone(&lines) })).is_ok() {
+        log::set_max_level(level);
+    }
+    lines
+}
+
+enum Message {
+    Started(u32),
+    Finished(u32, Result<Vec<Solution>, String>, Duration),
+}
+
+/// Runs the requested days one after another. The days are created here, because their solvers can't be sent to
+/// another thread.
+fn worker(year: u32, requests: Receiver<u32>, messages: Sender<Message>) {
+    let days = registry::days_of(year);
+    for request in requests {
+        let day = match days.iter().find(|day| day.day == request) {
+            Some(day) => day,
+            None => continue,
+        };
+        if messages.send(Message::Started(day.day)).is_err() {
+            break;
+        }
+
+        let start = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let input = inputs::load(day.year, day.day).map_err(|e| e.to_string())?;
+            runner::run(day, &input).map_err(|e| e.to_string())
+        }));
+        let result = result.unwrap_or_else(|_| Err("Solver panicked".to_owned()));
+        if result.is_err() {
+            error!("Day {} failed", day.day);
+        }
+
+        if messages.send(Message::Finished(day.day, result, start.elapsed())).is_err() {
+            break;
+        }
+    }
+}
+
+enum Status {
+    Idle,
+    Queued,
+    Running(Instant),
+    Solved(Vec<Solution>, Duration),
+    Failed(String, Duration),
+}
+
+impl Status {
+    fn summary(&self) -> String {
+        match self {
+            Self::Idle => String::new(),
+            Self::Queued => "queued".to_owned(),
+            Self::Running(start) => format!("{:.1} s", start.elapsed().as_secs_f64()),
+            Self::Solved(_, elapsed) => format!("{:.1} ms", elapsed.as_secs_f64() * 1000.),
+            Self::Failed(..) => "failed".to_owned(),
+        }
+    }
+
+    fn is_pending(&self) -> bool {
+        matches!(self, Self::Queued | Self::Running(_))
+    }
+}
+
+struct Dashboard {
+    year: u32,
+    days: Vec<(u32, Status)>,
+    selected: usize,
+    /// How many lines the log is scrolled up from its end.
+    scroll: usize,
+    log: LogLines,
+    requests: Sender<u32>,
+}
+
+impl Dashboard {
+    fn request(&mut self, index: usize) {
+        let (day, status) = match self.days.get_mut(index) {
+            Some(entry) => entry,
+            None => return,
+        };
+        if !status.is_pending() && self.requests.send(*day).is_ok() {
+            *status = Status::Queued;
+        }
+    }
+
+    fn update(&mut self, message: Message) {
+        let (day, status) = match message {
+            Message::Started(day) => (day, Status::Running(Instant::now())),
+            Message::Finished(day, Ok(solutions), elapsed) => (day, Status::Solved(solutions, elapsed)),
+            Message::Finished(day, Err(e), elapsed) => (day, Status::Failed(e, elapsed)),
+        };
+        if let Some(entry) = self.days.iter_mut().find(|(n, _)| *n == day) {
+            entry.1 = status;
+        }
+    }
+
+    fn list_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("Days of {}", self.year)];
+        lines.extend(self.days.iter().enumerate().map(|(i, (day, status))| {
+            let marker = if i == self.selected { '>' } else { ' ' };
+            format!("{} Day {:>2}  {:>12}", marker, day, status.summary())
+        }));
+        lines
+    }
+
+    fn detail_lines(&self) -> Vec<String> {
+        let (day, status) = match self.days.get(self.selected) {
+            Some(entry) => entry,
+            // Another year, or a build without day features.
+            None => return vec![format!("No days for {}", self.year)],
+        };
+        let mut lines = vec![format!("Day {}", day)];
+        match status {
+            Status::Idle => lines.push("Press ENTER to run".to_owned()),
+            Status::Queued => lines.push("Waiting for other days".to_owned()),
+            Status::Running(start) => lines.push(format!("Running for {:.1} s", start.elapsed().as_secs_f64())),
+            Status::Solved(solutions, elapsed) => {
+                lines.push(format!("Solved in {:.3} ms", elapsed.as_secs_f64() * 1000.));
+                for solution in solutions {
+                    let header = format!("Part {} ({:.3} ms):", solution.part, solution.duration_ms);
+                    // Some answers are pictures over several lines.
+                    if solution.answer.contains('\n') {
+                        lines.push(header);
+                        lines.extend(solution.answer.lines().map(|line| format!("  {}", line)));
+                    }
+                    else {
+                        lines.push(format!("{} {}", header, solution.answer));
+                    }
+                }
+            },
+            Status::Failed(e, elapsed) => {
+                lines.push(format!("Failed after {:.3} ms:", elapsed.as_secs_f64() * 1000.));
+                lines.extend(e.lines().map(|line| format!("  {}", line)));
+            },
+        }
+        lines
+    }
+
+    fn log_lines(&self, height: usize) -> Vec<String> {
+        let log = self.log.lock().unwrap();
+        let end = log.len().saturating_sub(self.scroll);
+        log.iter().skip(end.saturating_sub(height)).take(end.min(height)).cloned().collect()
+    }
+
+    fn render(&self, stdout: &mut Stdout) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (width as usize, height as usize);
+        let log_height = (height / 3).max(3);
+        let panes_height = height.saturating_sub(log_height + 2);
+
+        // Every row is written in full, which also overwrites anything that went to stderr directly.
+        let mut rows = Vec::with_capacity(height);
+        let list = self.list_lines();
+        let details = self.detail_lines();
+        for i in 0 .. panes_height {
+            let left = list.get(i).map_or("", String::as_str);
+            let right = details.get(i).map_or("", String::as_str);
+            rows.push(format!("{} │ {}", fit(left, LIST_WIDTH), right));
+        }
+
+        let title = if self.scroll > 0 { format!("─ Log (scrolled up {}) ", self.scroll) } else { "─ Log ".to_owned() };
+        rows.push(format!("{:─<width$}", title, width = width));
+        let mut log = self.log_lines(log_height);
+        log.resize(log_height, String::new());
+        rows.extend(log);
+
+        rows.push("UP/DOWN: select, ENTER: run, A: run all, PGUP/PGDN/END: scroll log, Q: quit".to_owned());
+
+        queue!(stdout, cursor::MoveTo(0, 0))?;
+        for (i, row) in rows.iter().take(height).enumerate() {
+            if i > 0 {
+                queue!(stdout, Print("\r\n"))?;
+            }
+            queue!(stdout, Print(fit(row, width)))?;
+        }
+        stdout.flush()
+    }
+}
+
+/// Cuts or pads a line to exactly `width` characters.
+fn fit(line: &str, width: usize) -> String {
+    let line: String = line.chars().take(width).collect();
+    format!("{:<width$}", line, width = width)
+}
+
+/// Runs the dashboard until the user quits.
+pub fn run(year: u32) -> io::Result<()> {
+    let log = init_logger();
+    // Progress bars would draw over the dashboard.
+    if progress::Mode::from_env() == progress::Mode::Bar {
+        progress::set_mode(progress::Mode::Log);
+    }
+
+    let (requests, worker_requests) = mpsc::channel();
+    let (worker_messages, messages) = mpsc::channel();
+    thread::spawn(move || worker(year, worker_requests, worker_messages));
+
+    let mut dashboard = Dashboard {
+        year,
+        days: registry::days_of(year).into_iter().map(|day| (day.day, Status::Idle)).collect(),
+        selected: 0,
+        scroll: 0,
+        log,
+        requests,
+    };
+
+    let mut guard = TerminalGuard::new()?;
+    loop {
+        for message in messages.try_iter() {
+            dashboard.update(message);
+        }
+        dashboard.render(&mut guard.stdout)?;
+
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        let page = (terminal::size()?.1 as usize / 3).max(1);
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => dashboard.selected = dashboard.selected.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    dashboard.selected = (dashboard.selected + 1).min(dashboard.days.len().saturating_sub(1))
+                },
+                KeyCode::Enter => dashboard.request(dashboard.selected),
+                KeyCode::Char('a') => (0 .. dashboard.days.len()).for_each(|i| dashboard.request(i)),
+                KeyCode::PageUp => dashboard.scroll += page,
+                KeyCode::PageDown => dashboard.scroll = dashboard.scroll.saturating_sub(page),
+                KeyCode::End => dashboard.scroll = 0,
+                KeyCode::Esc | KeyCode::Char('q') => break,
+                _ => {},
+            }
+            let len = dashboard.log.lock().unwrap().len();
+            dashboard.scroll = dashboard.scroll.min(len);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn year_without_days() {
+        let (requests, _) = mpsc::channel();
+        let mut dashboard = Dashboard {
+            year: 2020,
+            days: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            log: LogLines::default(),
+            requests,
+        };
+        dashboard.request(dashboard.selected);
+        assert_eq!(dashboard.list_lines(), vec!["Days of 2020".to_owned()]);
+        assert_eq!(dashboard.detail_lines(), vec!["No days for 2020".to_owned()]);
+    }
+}
@@ -0,0 +1,225 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+use std::time::Duration;
+
+use crate::{inputs, intcode};
+#[cfg(feature="day3")]
+use crate::y2019::day3;
+#[cfg(feature="day6")]
+use crate::y2019::day6;
+#[cfg(feature="day8")]
+use crate::y2019::day8;
+#[cfg(feature="day10")]
+use crate::y2019::day10;
+#[cfg(feature="day11")]
+use crate::y2019::day11;
+#[cfg(feature="day12")]
+use crate::y2019::day12;
+#[cfg(feature="day13")]
+use crate::y2019::day13;
+
+
+/// Any error of this crate.
+///
+/// This implements `std::error::Error` instead of deriving `Fail`, so that cargo-aoc accepts it from generators and
+/// solvers. It's still a `Fail` through failure's blanket impl.
+#[derive(Debug)]
+pub enum Error {
+    Intcode(intcode::Error),
+    Input(inputs::InputError),
+    Io(io::Error),
+    ParseInt(ParseIntError),
+    #[cfg(feature="day3")]
+    Wire(day3::WireError),
+    #[cfg(feature="day6")]
+    Orbit(day6::Error),
+    #[cfg(feature="day8")]
+    Image(day8::ParseError),
+    #[cfg(feature="day10")]
+    AsteroidMap(day10::ParseError),
+    #[cfg(feature="day11")]
+    Robot(day11::Error),
+    #[cfg(feature="day12")]
+    Moons(day12::Error),
+    #[cfg(feature="day13")]
+    Arcade(day13::Error),
+    #[cfg(feature="day13")]
+    InputLog(day13::InputLogError),
+    #[cfg(feature="flamegraph")]
+    Profiler(pprof::Error),
+    Parse(ParseError),
+    /// The input parsed, but has no answer.
+    NoSolution(&'static str),
+    /// A solver didn't finish within its time budget, see `runner::run_with_timeout`.
+    TimedOut(Duration),
+    /// The day and the part that was asked for, e.g. part 2 of the last day.
+    NoSuchPart(u32, usize),
+    /// `Day::solve` got input that another day's generator parsed.
+    WrongInput(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Intcode(e) => write!(f, "Intcode error: {}", e),
+            Self::Input(e) => write!(f, "Input error: {}", e),
+            Self::Io(e) => write!(f, "IO error: {}", e),
+            Self::ParseInt(e) => write!(f, "Invalid number: {}", e),
+            #[cfg(feature="day3")]
+            Self::Wire(e) => write!(f, "Day 3: {}", e),
+            #[cfg(feature="day6")]
+            Self::Orbit(e) => write!(f, "Day 6: {}", e),
+            #[cfg(feature="day8")]
+            Self::Image(e) => write!(f, "Day 8: {}", e),
+            #[cfg(feature="day10")]
+            Self::AsteroidMap(e) => write!(f, "Day 10: {}", e),
+            #[cfg(feature="day11")]
+            Self::Robot(e) => write!(f, "Day 11: {}", e),
+            #[cfg(feature="day12")]
+            Self::Moons(e) => write!(f, "Day 12: {}", e),
+            #[cfg(feature="day13")]
+            Self::Arcade(e) => write!(f, "Day 13: {}", e),
+            #[cfg(feature="day13")]
+            Self::InputLog(e) => write!(f, "Day 13: {}", e),
+            #[cfg(feature="flamegraph")]
+            Self::Profiler(e) => write!(f, "Profiler error: {}", e),
+            Self::Parse(e) => write!(f, "{}", e),
+            Self::NoSolution(reason) => write!(f, "No solution: {}", reason),
+            Self::TimedOut(timeout) => write!(f, "Timed out after {:?}", timeout),
+            Self::NoSuchPart(day, part) => write!(f, "Day {} has no part {}", day, part),
+            Self::WrongInput(day) => write!(f, "Day {}: The input wasn't parsed by this day", day),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::ParseInt(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Where and why a generator rejected its input. Lines and columns count from 1, like in editors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// The message quotes `fragment`, the part of the input that is wrong. Long fragments, like a whole line, are cut
+    /// off after their first line or 40 characters.
+    pub fn new(day: u32, line: usize, column: usize, fragment: &str, reason: impl fmt::Display) -> Self {
+        let fragment = fragment.trim();
+        let message = if fragment.is_empty() {
+            reason.to_string()
+        }
+        else {
+            let mut cut = fragment.lines().next().unwrap_or_default().chars().take(40).collect::<String>();
+            if cut.len() < fragment.len() {
+                cut.push_str("...");
+            }
+            format!("{} at {:?}", reason, cut)
+        };
+        Self { day, line, column, message }
+    }
+
+    /// Finds where `fragment` is in `input`. Fragments should be slices of `input`, like its lines or their parts, or
+    /// else we search for the first copy.
+    pub fn at(day: u32, input: &str, fragment: &str, reason: impl fmt::Display) -> Self {
+        let (start, base) = (fragment.as_ptr() as usize, input.as_ptr() as usize);
+        let offset = if start >= base && start + fragment.len() <= base + input.len() {
+            start - base
+        }
+        else {
+            input.find(fragment).unwrap_or_default()
+        };
+
+        let before = &input[.. offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self::new(day, before.matches('\n').count() + 1, before[line_start ..].chars().count() + 1, fragment, reason)
+    }
+
+    /// For what's missing at the end of the input, e.g. a second wire.
+    pub fn end(day: u32, input: &str, reason: impl fmt::Display) -> Self {
+        Self::at(day, input, &input[input.len() ..], reason)
+    }
+
+    /// Moves the error down by `lines`, for generators that parse line by line.
+    pub fn below(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Day {}: Invalid input in line {}, column {}: {}", self.day, self.line, self.column, self.message)
+    }
+}
+
+impl StdError for ParseError {}
+
+macro_rules! impl_from {
+    ($($(#[$meta:meta])* $variant:ident($error:ty)),*) => {
+        $(
+            $(#[$meta])*
+            impl From<$error> for Error {
+                fn from(e: $error) -> Self {
+                    Self::$variant(e)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(
+    Intcode(intcode::Error),
+    Input(inputs::InputError),
+    Io(io::Error),
+    ParseInt(ParseIntError),
+    #[cfg(feature="day3")]
+    Wire(day3::WireError),
+    #[cfg(feature="day6")]
+    Orbit(day6::Error),
+    #[cfg(feature="day8")]
+    Image(day8::ParseError),
+    #[cfg(feature="day10")]
+    AsteroidMap(day10::ParseError),
+    #[cfg(feature="day11")]
+    Robot(day11::Error),
+    #[cfg(feature="day12")]
+    Moons(day12::Error),
+    #[cfg(feature="day13")]
+    Arcade(day13::Error),
+    #[cfg(feature="day13")]
+    InputLog(day13::InputLogError),
+    #[cfg(feature="flamegraph")]
+    Profiler(pprof::Error),
+    Parse(ParseError)
+);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors_find_their_fragment() {
+        let input = "12\n3x4\n";
+        let error = ParseError::at(1, input, &input[4 .. 5], "Not a digit");
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.to_string(), "Day 1: Invalid input in line 2, column 2: Not a digit at \"x\"");
+        // Copies are searched for.
+        assert_eq!(ParseError::at(1, input, "4", "").column, 3);
+        assert_eq!((ParseError::end(1, input, "").line, ParseError::end(1, input, "").column), (3, 1));
+    }
+}
@@ -0,0 +1,27 @@
+extern crate aoc_2019;
+
+use std::env;
+
+use aoc_2019::{inputs, y2019};
+
+
+pub fn main() {
+    aoc_2019::util::init();
+
+    let mut year = y2019::YEAR;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--year" {
+            year = args.next().and_then(|year| year.parse().ok()).expect("Invalid year");
+            continue;
+        }
+        let day: u32 = arg.parse().expect("Invalid day");
+        match inputs::load(year, day) {
+            Ok(_) => println!("Day {}: {}", day, inputs::path(year, day).display()),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            },
+        }
+    }
+}
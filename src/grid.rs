@@ -0,0 +1,158 @@
+//! Spots what changed between two states of a grid, e.g. two frames of the arcade or the hull before and after a few
+//! steps of the robot, instead of comparing two printouts by eye.
+
+use std::fmt::Write;
+
+use crate::viz::Frame;
+
+
+/// Anything that is drawn as characters on a grid.
+pub trait Grid {
+    /// The smallest and the largest x and y, or `None` if nothing is drawn yet.
+    fn bounds(&self) -> Option<((i64, i64), (i64, i64))>;
+
+    /// The character at a position. Positions outside of the grid are blank.
+    fn glyph(&self, x: i64, y: i64) -> char;
+}
+
+/// Frames start at (0, 0).
+impl Grid for Frame {
+    fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        if self.width() == 0 || self.height() == 0 {
+            return None;
+        }
+        Some(((0, 0), (self.width() as i64 - 1, self.height() as i64 - 1)))
+    }
+
+    fn glyph(&self, x: i64, y: i64) -> char {
+        if x < 0 || y < 0 {
+            return ' ';
+        }
+        self.get(x as usize, y as usize).map_or(' ', |cell| cell.glyph)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub x: i64,
+    pub y: i64,
+    pub before: char,
+    pub after: char,
+}
+
+/// Two grids over the area that either of them covers, and the cells in which they differ, row by row.
+#[derive(Clone, Debug, Default)]
+pub struct Diff {
+    pub changes: Vec<Change>,
+    origin: (i64, i64),
+    before: Vec<String>,
+    after: Vec<String>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn changed_columns(&self, y: i64) -> impl Iterator<Item = usize> + '_ {
+        let x = self.origin.0;
+        self.changes.iter()
+            .filter(move |change| change.y == y)
+            .map(move |change| (change.x - x) as usize)
+    }
+
+    /// Both grids next to each other, with the rows that changed marked with `*`.
+    pub fn side_by_side(&self) -> String {
+        let width = self.before.first().map_or(0, |row| row.chars().count());
+        let mut text = String::new();
+        for (i, (before, after)) in self.before.iter().zip(&self.after).enumerate() {
+            let y = self.origin.1 + i as i64;
+            let marker = if self.changed_columns(y).next().is_some() { '*' } else { ' ' };
+            writeln!(text, "{} {:>4} {:<width$} │ {}", marker, y, before, after, width = width).unwrap();
+        }
+        text
+    }
+
+    /// Only the rows that changed, before (`-`) and after (`+`), with `^` under the cells that changed.
+    pub fn unified(&self) -> String {
+        let mut text = String::new();
+        for (i, (before, after)) in self.before.iter().zip(&self.after).enumerate() {
+            let y = self.origin.1 + i as i64;
+            let columns = self.changed_columns(y).collect::<Vec<_>>();
+            let last = match columns.iter().max() {
+                Some(&last) => last,
+                None => continue,
+            };
+            let carets = (0 ..= last).map(|x| if columns.contains(&x) { '^' } else { ' ' }).collect::<String>();
+            writeln!(text, "-{:>4} {}", y, before).unwrap();
+            writeln!(text, "+{:>4} {}", y, after).unwrap();
+            writeln!(text, "      {}", carets).unwrap();
+        }
+        text
+    }
+}
+
+fn union(a: Option<((i64, i64), (i64, i64))>, b: Option<((i64, i64), (i64, i64))>) -> Option<((i64, i64), (i64, i64))> {
+    match (a, b) {
+        (Some((min_a, max_a)), Some((min_b, max_b))) => {
+            Some(((min_a.0.min(min_b.0), min_a.1.min(min_b.1)), (max_a.0.max(max_b.0), max_a.1.max(max_b.1))))
+        },
+        (bounds, None) | (None, bounds) => bounds,
+    }
+}
+
+/// Compares `before` and `after` cell by cell. They don't have to be the same size: cells outside of one of them count
+/// as blank.
+pub fn diff(before: &impl Grid, after: &impl Grid) -> Diff {
+    let (min, max) = match union(before.bounds(), after.bounds()) {
+        Some(bounds) => bounds,
+        None => return Diff::default(),
+    };
+
+    let mut diff = Diff { origin: min, ..Diff::default() };
+    for y in min.1 ..= max.1 {
+        let (mut row_before, mut row_after) = (String::new(), String::new());
+        for x in min.0 ..= max.0 {
+            let (a, b) = (before.glyph(x, y), after.glyph(x, y));
+            if a != b {
+                diff.changes.push(Change { x, y, before: a, after: b });
+            }
+            row_before.push(a);
+            row_after.push(b);
+        }
+        diff.before.push(row_before);
+        diff.after.push(row_after);
+    }
+    diff
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::viz::Cell;
+
+    fn frame(rows: &[&str]) -> Frame {
+        let mut frame = Frame::new(rows[0].len(), rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, glyph) in row.chars().enumerate() {
+                frame.set(x, y, Cell::new(glyph, 0));
+            }
+        }
+        frame
+    }
+
+    #[test]
+    fn it_finds_changed_cells() {
+        let diff = diff(&frame(&["#..", "..."]), &frame(&["#.o", "...", ".x."]));
+        assert_eq!(diff.changes, vec![
+            Change { x: 2, y: 0, before: '.', after: 'o' },
+            Change { x: 0, y: 2, before: ' ', after: '.' },
+            Change { x: 1, y: 2, before: ' ', after: 'x' },
+            Change { x: 2, y: 2, before: ' ', after: '.' },
+        ]);
+        assert_eq!(diff.unified(), "-   0 #..\n+   0 #.o\n        ^\n-   2    \n+   2 .x.\n      ^^^\n");
+        assert_eq!(diff.side_by_side().lines().nth(1), Some("     1 ... │ ..."));
+        assert!(super::diff(&frame(&["#"]), &frame(&["#"])).is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use failure::Fail;
+use serde::Deserialize;
+
+use crate::config;
+
+
+#[derive(Debug, Fail)]
+pub enum InputError {
+    #[fail(display = "Set `AOC_SESSION` or `session_file` in aoc.toml to download inputs")]
+    SessionNotSet,
+    #[fail(display = "IO error: {}", _0)]
+    Io(#[cause] io::Error),
+    #[fail(display = "Failed to download the input for day {} of {}: {}", _1, _0, _2)]
+    Download(u32, u32, String),
+    #[fail(display = "Invalid answers in {}: {}", _0, _1)]
+    InvalidAnswers(String, String),
+}
+
+impl From<io::Error> for InputError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Where the input of a day is kept, by default where cargo-aoc expects it, e.g. `input/2019/day13.txt`.
+pub fn path(year: u32, day: u32) -> PathBuf {
+    config::get().input_dir().join(year.to_string()).join(format!("day{}.txt", day))
+}
+
+/// Reads the input of a day, and downloads it first if it isn't cached yet.
+pub fn load(year: u32, day: u32) -> Result<String, InputError> {
+    let path = path(year, day);
+    if !path.exists() {
+        info!("Downloading input for day {} of {}", day, year);
+        let input = download(year, day)?;
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(&path, &input)?;
+    }
+    Ok(fs::read_to_string(path)?)
+}
+
+/// The session cookie from `AOC_SESSION` (or `.env`), or else from the configured session file.
+fn session() -> Result<String, InputError> {
+    // Also loads `.env`.
+    let config = config::get();
+    if let Ok(session) = env::var("AOC_SESSION") {
+        return Ok(session);
+    }
+    let path = config.session_file.as_ref().ok_or(InputError::SessionNotSet)?;
+    Ok(fs::read_to_string(path)?)
+}
+
+/// A curl config with the session cookie, quoted as curl expects.
+fn curl_config(session: &str) -> String {
+    let quoted = session.trim().replace('\\', "\\\\").replace('"', "\\\"");
+    format!("cookie = \"session={}\"\n", quoted)
+}
+
+/// Downloads the input of a day from adventofcode.com with the session cookie. This uses `curl`, so we don't need a
+/// TLS stack. The cookie goes to curl on stdin, since anyone on the machine can see its arguments.
+pub fn download(year: u32, day: u32) -> Result<String, InputError> {
+    let session = session()?;
+
+    let mut curl = Command::new("curl")
+        .arg("--silent")
+        .arg("--show-error")
+        .arg("--fail")
+        .arg("--user-agent").arg("github.com/jgraef/aoc-2019")
+        .arg("--config").arg("-")
+        .arg(format!("https://adventofcode.com/{}/day/{}/input", year, day))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Dropping stdin closes it, so that curl stops reading its config.
+    curl.stdin.take().expect("Piped stdin").write_all(curl_config(&session).as_bytes())?;
+    let output = curl.wait_with_output()?;
+
+    if !output.status.success() {
+        return Err(InputError::Download(year, day, String::from_utf8_lossy(&output.stderr).trim().to_owned()));
+    }
+
+    String::from_utf8(output.stdout)
+        .map_err(|e| InputError::Download(year, day, e.to_string()))
+}
+
+/// The answers that we know to be right for our inputs, e.g. from submitting them, keyed by day, e.g.
+/// `day1 = [3256794, 4882337]`. Pictures can be multi-line strings.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Answers(BTreeMap<String, Vec<toml::Value>>);
+
+impl Answers {
+    pub fn get(&self, day: u32, part: usize) -> Option<String> {
+        let answer = self.0.get(&format!("day{}", day))?.get(part.checked_sub(1)?)?;
+        Some(match answer {
+            toml::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+}
+
+/// Where the known answers of a year are kept, next to its inputs, e.g. `input/2019/answers.toml`.
+pub fn answers_path(year: u32) -> PathBuf {
+    config::get().input_dir().join(year.to_string()).join("answers.toml")
+}
+
+/// Reads the known answers of a year. Without a file we don't know any.
+pub fn answers(year: u32) -> Result<Answers, InputError> {
+    let path = answers_path(year);
+    if !path.exists() {
+        return Ok(Answers::default());
+    }
+    toml::from_str(&fs::read_to_string(&path)?)
+        .map_err(|e| InputError::InvalidAnswers(path.display().to_string(), e.to_string()))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_answers() {
+        let answers: Answers = toml::from_str("day1 = [3256794, \"4882337\"]\nday8 = [1703, \"\"\"\n#..#\n.##.\"\"\"]")
+            .unwrap();
+        assert_eq!(answers.get(1, 1).as_deref(), Some("3256794"));
+        assert_eq!(answers.get(1, 2).as_deref(), Some("4882337"));
+        assert_eq!(answers.get(8, 2).as_deref(), Some("#..#\n.##."));
+        assert_eq!(answers.get(2, 1), None);
+        assert_eq!(answers.get(1, 3), None);
+    }
+
+    #[test]
+    fn it_quotes_the_session_for_curl() {
+        assert_eq!(curl_config("53616c74\n"), "cookie = \"session=53616c74\"\n");
+        assert_eq!(curl_config("a\"b\\c"), "cookie = \"session=a\\\"b\\\\c\"\n");
+    }
+}
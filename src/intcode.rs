@@ -4,6 +4,12 @@ use std::collections::VecDeque;
 
 use failure::Fail;
 
+use crate::ParseError;
+
+
+/// Programs can't write past this address, since memory grows up to it.
+const MAX_ADDRESS: usize = 1 << 24;
+
 
 #[derive(Debug, Clone, Fail)]
 pub enum Error {
@@ -23,6 +29,8 @@ pub enum Error {
     InvalidArgument(i64),
     #[fail(display = "Not an integer: {}", _0)]
     NotAnInteger(String),
+    #[fail(display = "Arithmetic overflow at address {}", _0)]
+    Overflow(usize),
 }
 
 pub enum ParameterMode {
@@ -44,6 +52,16 @@ impl TryFrom<u8> for ParameterMode {
     }
 }
 
+/// Execution counters of a machine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of instructions executed.
+    pub instructions: u64,
+    pub outputs_written: u64,
+    /// Address of the last instruction executed.
+    pub last_pc: Option<usize>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Machine {
     memory: Vec<i64>,
@@ -53,6 +71,8 @@ pub struct Machine {
     output: VecDeque<i64>,
     relative_base: i64,
     constant_input: Option<i64>,
+    inputs_read: usize,
+    stats: Stats,
 }
 
 impl Machine {
@@ -66,6 +86,8 @@ impl Machine {
             output: VecDeque::new(),
             relative_base: 0,
             constant_input: None,
+            inputs_read: 0,
+            stats: Stats::default(),
         }
     }
 
@@ -77,6 +99,7 @@ impl Machine {
         self.input.push_back(value);
     }
 
+    /// Sets the input that is read whenever the input queue is empty.
     pub fn set_contant_input(&mut self, value: i64) {
         self.constant_input = Some(value);
     }
@@ -89,10 +112,33 @@ impl Machine {
         self.output.drain(..).collect()
     }
 
+    /// Number of input instructions executed so far.
+    pub fn inputs_read(&self) -> usize {
+        self.inputs_read
+    }
+
+    /// Number of queued inputs.
+    pub fn input_len(&self) -> usize {
+        self.input.len()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Whether the next instruction reads input.
+    pub fn awaits_input(&self) -> bool {
+        !self.halted && self.get_data(self.pc) % 100 == 3
+    }
+
     pub fn is_halted(&self) -> bool {
         self.halted
     }
 
+    pub fn memory(&self) -> &[i64] {
+        &self.memory
+    }
+
     pub fn get_data(&self, address: usize) -> i64 {
         self.memory.get(address)
             .copied()
@@ -127,7 +173,7 @@ impl Machine {
             },
             ParameterMode::Immediate => arg,
             ParameterMode::Relative => {
-                let address = arg + self.relative_base;
+                let address = self.relative_address(arg)?;
                 let address = address.try_into()
                     .map_err(|_| Error::InvalidAddress(address))?;
                 self.get_data(address)
@@ -140,15 +186,24 @@ impl Machine {
         let address = match Self::get_param_mode(opcode, arg_num)? {
             ParameterMode::Position => arg,
             ParameterMode::Immediate => return Err(Error::InvalidInstruction(opcode)),
-            ParameterMode::Relative => arg + self.relative_base,
+            ParameterMode::Relative => self.relative_address(arg)?,
         };
-        let address = address.try_into().map_err(|_| Error::InvalidAddress(address))?;
+        let address = address.try_into()
+            .ok()
+            .filter(|&address| address <= MAX_ADDRESS)
+            .ok_or(Error::InvalidAddress(address))?;
         self.set_data(address, value);
         Ok(())
     }
 
-    fn bin_op<F: FnOnce(i64, i64) -> i64>(&mut self, op: F, opcode: i64) -> Result<(), Error> {
-        let r = op(self.get_arg(0, opcode)?, self.get_arg(1, opcode)?);
+    fn relative_address(&self, arg: i64) -> Result<i64, Error> {
+        arg.checked_add(self.relative_base).ok_or(Error::InvalidAddress(arg))
+    }
+
+    /// Arithmetic is checked, since a wrapped result would silently give a wrong answer.
+    fn bin_op<F: FnOnce(i64, i64) -> Option<i64>>(&mut self, op: F, opcode: i64) -> Result<(), Error> {
+        let r = op(self.get_arg(0, opcode)?, self.get_arg(1, opcode)?)
+            .ok_or(Error::Overflow(self.pc))?;
         self.set_return(2, r, opcode)?;
         self.pc += 4;
         Ok(())
@@ -173,34 +228,34 @@ impl Machine {
         }
 
         let opcode = self.get_data(self.pc);
+        let pc = self.pc;
 
         //debug!("Executing {:?}", opcode);
         match opcode % 100 {
-            1 => self.bin_op(|a, b| a + b, opcode)?,
-            2 => self.bin_op(|a, b| a * b, opcode)?,
+            1 => self.bin_op(i64::checked_add, opcode)?,
+            2 => self.bin_op(i64::checked_mul, opcode)?,
             3 => {
-                if let Some(input) = self.constant_input {
-                    self.set_return(0, input, opcode)?;
-                }
-                else {
-                    let input = self.input.pop_front()
-                        .ok_or(Error::NoInput)?;
-                    self.set_return(0, input, opcode)?;
-                }
+                let input = self.input.pop_front()
+                    .or(self.constant_input)
+                    .ok_or(Error::NoInput)?;
+                self.set_return(0, input, opcode)?;
 
+                self.inputs_read += 1;
                 self.pc += 2;
             },
             4 => {
                 let output = self.get_arg(0, opcode)?;
                 self.output.push_back(output);
+                self.stats.outputs_written += 1;
                 self.pc += 2;
             },
             5 => self.jump_op(true, opcode)?,
             6 => self.jump_op(false, opcode)?,
-            7 => self.bin_op(|a, b| if a < b { 1 } else { 0 }, opcode)?,
-            8 => self.bin_op(|a, b| if a == b { 1 } else { 0 }, opcode)?,
+            7 => self.bin_op(|a, b| Some(if a < b { 1 } else { 0 }), opcode)?,
+            8 => self.bin_op(|a, b| Some(if a == b { 1 } else { 0 }), opcode)?,
             9 => {
-                self.relative_base += self.get_arg(0, opcode)?;
+                self.relative_base = self.relative_base.checked_add(self.get_arg(0, opcode)?)
+                    .ok_or(Error::Overflow(self.pc))?;
                 self.pc += 2;
             }
             99 => {
@@ -209,9 +264,45 @@ impl Machine {
             data => return Err(Error::InvalidInstruction(data)),
         }
 
+        self.stats.instructions += 1;
+        self.stats.last_pc = Some(pc);
+
         Ok(())
     }
 
+    /// Human-readable instruction at an address, e.g. `ADD [12] 1 [rb+3]`.
+    pub fn disassemble(&self, address: usize) -> String {
+        let opcode = self.get_data(address);
+        let (name, num_args) = match opcode % 100 {
+            1 => ("ADD", 3),
+            2 => ("MUL", 3),
+            3 => ("IN", 1),
+            4 => ("OUT", 1),
+            5 => ("JT", 2),
+            6 => ("JF", 2),
+            7 => ("LT", 3),
+            8 => ("EQ", 3),
+            9 => ("ARB", 1),
+            99 => ("HALT", 0),
+            _ => return format!("DATA {}", opcode),
+        };
+
+        let mut instruction = name.to_owned();
+        for arg_num in 0 .. num_args {
+            let arg = self.get_data(address + 1 + arg_num);
+            let arg = match Self::get_param_mode(opcode, arg_num) {
+                Ok(ParameterMode::Position) => format!("[{}]", arg),
+                Ok(ParameterMode::Immediate) => format!("{}", arg),
+                Ok(ParameterMode::Relative) => format!("[rb{:+}]", arg),
+                Err(_) => format!("?{}", arg),
+            };
+            instruction.push(' ');
+            instruction.push_str(&arg);
+        }
+        instruction
+    }
+
+    #[cfg_attr(feature="spans", tracing::instrument(name="Machine::run", level="debug", skip_all))]
     pub fn run(&mut self) -> Result<(), Error> {
         while !self.halted {
             self.step()?;
@@ -219,6 +310,7 @@ impl Machine {
         Ok(())
     }
 
+    #[cfg_attr(feature="spans", tracing::instrument(name="Machine::next_output", level="debug", skip_all))]
     pub fn next_output(&mut self) -> Result<Option<i64>, Error> {
         Ok(loop {
             if self.halted {
@@ -233,11 +325,42 @@ impl Machine {
             }
         })
     }
+
+    /// Like `next_output`, but returns `None` instead of reading input.
+    #[cfg_attr(feature="spans", tracing::instrument(name="Machine::next_output_before_input", level="debug", skip_all))]
+    pub fn next_output_before_input(&mut self) -> Result<Option<i64>, Error> {
+        Ok(loop {
+            if let Some(output) = self.pop_output() {
+                break Some(output);
+            }
+
+            if self.halted {
+                debug!("Machine is halted");
+                return Err(Error::Halted);
+            }
+
+            if self.awaits_input() {
+                break None;
+            }
+
+            self.step()?;
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct Program(Vec<i64>);
 
+impl Program {
+    /// Parses the program of a day's input, for the generators.
+    pub fn parse_input(day: u32, input: &str) -> Result<Self, crate::Error> {
+        let program = input.split(',')
+            .map(|num| num.trim().parse::<i64>().map_err(|_| ParseError::at(day, input, num.trim(), "Not an integer")))
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(Self(program))
+    }
+}
+
 impl FromStr for Program {
     type Err = Error;
 
@@ -251,3 +374,30 @@ impl FromStr for Program {
         Ok(Self(program))
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(program: &str) -> Result<Vec<i64>, Error> {
+        let mut machine = Machine::new(program.parse()?);
+        machine.run()?;
+        Ok(machine.get_output())
+    }
+
+    #[test]
+    fn large_numbers() {
+        assert_eq!(run("104,1125899906842624,99").unwrap(), vec![1125899906842624]);
+        assert_eq!(run("1102,34915192,34915192,7,4,7,99,0").unwrap(), vec![1219070632396864]);
+    }
+
+    #[test]
+    fn arithmetic_overflow_is_an_error() {
+        // These used to wrap around to -2^63 and 0 in release builds.
+        assert!(matches!(run("1101,9223372036854775807,1,5,99,0"), Err(Error::Overflow(0))));
+        assert!(matches!(run("1102,4611686018427387904,4,5,99,0"), Err(Error::Overflow(0))));
+        assert!(matches!(run("109,9223372036854775807,109,1,99"), Err(Error::Overflow(2))));
+        assert!(matches!(run("109,9223372036854775807,204,1,99"), Err(Error::InvalidAddress(1))));
+    }
+}
@@ -3,23 +3,45 @@ extern crate log;
 
 use aoc_runner_derive::aoc_lib;
 
+mod error;
+
+pub use error::{Error, ParseError};
+
 pub mod util;
+pub mod cache;
+pub mod config;
+pub mod grid;
+pub mod inputs;
 pub mod intcode;
-#[cfg(feature="arcade_game")]
-pub mod arcade_game;
+pub mod math;
+pub mod memo;
+pub mod ocr;
+pub mod prelude;
+pub mod progress;
+pub mod registry;
+pub mod search;
+pub mod viz;
+#[cfg(feature="crossterm")]
+mod terminal;
+#[cfg(feature="arcade_tui")]
+pub mod arcade_tui;
+#[cfg(feature="export")]
+pub mod arcade_export;
+#[cfg(feature="viz")]
+pub mod orbit_viz;
+#[cfg(feature="runner")]
+pub mod runner;
+#[cfg(feature="runner")]
+pub mod report;
+#[cfg(feature="dashboard")]
+pub mod dashboard;
+#[cfg(feature="profiling")]
+pub mod profiling;
+#[cfg(feature="spans")]
+pub mod spans;
+#[cfg(feature="web")]
+pub mod web;
 
-pub mod day1;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
+pub mod y2019;
 
 aoc_lib!{ year = 2019 }
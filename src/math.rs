@@ -0,0 +1,145 @@
+//! Number theory for puzzles about cycles and modular arithmetic. Products are computed with `i128`, so moduli may
+//! use the whole `i64` range.
+
+use num::Integer;
+
+
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    a.gcd(&b)
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    a.lcm(&b)
+}
+
+/// `lcm`, or `None` if it doesn't fit.
+pub fn checked_lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+
+    if r0 < 0 { (-r0, -x0, -y0) } else { (r0, x0, y0) }
+}
+
+/// `a * b mod m`, in `0 .. m`.
+pub fn mod_mul(a: i64, b: i64, m: i64) -> i64 {
+    (i128::from(a) * i128::from(b)).rem_euclid(i128::from(m)) as i64
+}
+
+/// `base ^ exp mod m`, in `0 .. m`.
+pub fn mod_pow(base: i64, mut exp: u64, m: i64) -> i64 {
+    let mut base = base.rem_euclid(m);
+    let mut result = 1 % m;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, m);
+        }
+        base = mod_mul(base, base, m);
+        exp >>= 1;
+    }
+
+    result
+}
+
+/// The `x` in `0 .. m` with `a * x = 1 mod m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    if g == 1 { Some(x.rem_euclid(m)) } else { None }
+}
+
+/// Chinese remainder theorem: Solves `x = r mod m` for all `(r, m)` in `congruences`. Returns `(x, lcm)` with `x` in
+/// `0 .. lcm` of all moduli, or `None` if the congruences contradict each other or the lcm doesn't fit into an `i64`.
+/// The moduli don't need to be coprime.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    congruences.iter().try_fold((0, 1), |(x, m), &(r, n)| {
+        let (g, p, _) = extended_gcd(m, n);
+        let difference = i128::from(r) - i128::from(x);
+        if difference % i128::from(g) != 0 {
+            return None;
+        }
+
+        // x + m * k = r mod n, with k = difference / g * p mod n / g.
+        let lcm = (m / g).checked_mul(n)?;
+        let k = mod_mul((difference / i128::from(g)).rem_euclid(i128::from(n / g)) as i64, p, n / g);
+        let x = (i128::from(x) + i128::from(m) * i128::from(k)).rem_euclid(i128::from(lcm)) as i64;
+        Some((x, lcm))
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(lcm(12u64, 18), 36);
+        assert_eq!(lcm(lcm(18, 28), 44), 2772);
+        assert_eq!(checked_lcm(0, 5), Some(0));
+        assert_eq!(checked_lcm(1 << 40, 3 << 40), Some(3 << 40));
+        assert_eq!(checked_lcm(1 << 40, (1 << 40) + 1), None);
+    }
+
+    #[test]
+    fn extended_gcd_finds_bezout_coefficients() {
+        for &(a, b) in &[(240, 46), (46, 240), (-240, 46), (17, 0), (0, 5)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+        assert_eq!(mod_pow(7, 0, 1), 0);
+        // Near the top of the i64 range, where a plain product would overflow.
+        let m = 119_315_717_514_047;
+        assert_eq!(mod_mul(m - 1, m - 1, m), 1);
+        assert_eq!(mod_pow(2, m as u64 - 1, m), 1);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        let inverse = mod_inverse(2019, m).unwrap();
+        assert_eq!(mod_mul(inverse, 2019, m), 1);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Not coprime, but consistent.
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        // Residues from the whole i64 range.
+        assert_eq!(crt(&[(1, 3), (i64::MIN, 2)]), Some((4, 6)));
+        assert_eq!(crt(&[(i64::MAX, 1 << 40), (i64::MIN, 3 << 40)]), None);
+        // The lcm is about 2^80.
+        assert_eq!(crt(&[(1, 1 << 40), (2, (1 << 40) - 1)]), None);
+        let congruences = [(-1, 1 << 31), (i64::MAX, (1 << 31) - 1)];
+        let (x, lcm) = crt(&congruences).unwrap();
+        assert_eq!(lcm, (1 << 31) * ((1 << 31) - 1));
+        for &(r, n) in &congruences {
+            assert_eq!(x % n, r.rem_euclid(n));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Caching the values of (recursive) functions.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+
+/// Remembers values by key, so that every value is computed once.
+#[derive(Clone, Debug)]
+pub struct Memoized<K, V> {
+    cache: HashMap<K, V>,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Memoized<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Returns the value for `key`, and computes it with `f` if it isn't cached yet.
+    pub fn get_or_insert_with(&mut self, key: &K, f: impl FnOnce() -> V) -> V {
+        if let Some(value) = self.cache.get(key) {
+            return value.clone();
+        }
+        let value = f();
+        self.cache.insert(key.clone(), value.clone());
+        value
+    }
+
+    /// For recursive functions: `f` gets a function to look up the values it depends on, which are cached as well,
+    /// and the key. See `OrbitMap::compute_checksum` of day 6 for an example.
+    pub fn get_recursive<F>(&mut self, key: &K, f: &F) -> V
+        where F: Fn(&mut dyn FnMut(&K) -> V, &K) -> V
+    {
+        if let Some(value) = self.cache.get(key) {
+            return value.clone();
+        }
+        let value = f(&mut |key| self.get_recursive(key, f), key);
+        self.cache.insert(key.clone(), value.clone());
+        value
+    }
+}
+
+impl<K, V> Default for Memoized<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[test]
+    fn computes_every_key_once() {
+        let computed = RefCell::new(Vec::new());
+        let fibonacci = |fib: &mut dyn FnMut(&u64) -> u64, n: &u64| {
+            computed.borrow_mut().push(*n);
+            if *n < 2 { *n } else { fib(&(n - 1)) + fib(&(n - 2)) }
+        };
+
+        let mut memo = Memoized::new();
+        assert_eq!(memo.get_recursive(&50, &fibonacci), 12_586_269_025);
+        assert_eq!(memo.len(), 51);
+        assert_eq!(memo.get(&10), Some(&55));
+
+        let mut keys = computed.borrow().clone();
+        keys.sort_unstable();
+        assert_eq!(keys, (0 ..= 50).collect::<Vec<_>>());
+
+        // Cached values aren't computed again.
+        assert_eq!(memo.get_recursive(&40, &fibonacci), 102_334_155);
+        assert_eq!(memo.get_or_insert_with(&30, || unreachable!()), 832_040);
+        assert_eq!(computed.borrow().len(), 51);
+    }
+}
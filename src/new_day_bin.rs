@@ -0,0 +1,138 @@
+//! Sets up a new day: writes `src/y<year>/day<N>.rs` from `template.rs`, declares the module, adds its feature and
+//! puts it into the registry. With `--fetch` it also downloads the input.
+
+extern crate aoc_2019;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use aoc_2019::{inputs, y2019};
+
+
+const TEMPLATE: &str = include_str!("template.rs");
+
+fn usage() -> ! {
+    eprintln!("Usage: new_day [--year <year>] [--fetch] <day>");
+    process::exit(1);
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| fail(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+fn write(path: &Path, contents: &str) {
+    fs::write(path, contents).unwrap_or_else(|e| fail(format!("Failed to write {}: {}", path.display(), e)));
+    println!("Wrote {}", path.display());
+}
+
+/// 2019 came first, so its days have the short names.
+fn feature(year: u32, day: u32) -> String {
+    if year == y2019::YEAR { format!("day{}", day) } else { format!("y{}_day{}", year, day) }
+}
+
+/// Inserts `line` after the last line that starts with `prefix`.
+fn insert_after_last(text: &str, prefix: &str, line: &str) -> Option<String> {
+    let start = text.rfind(&format!("\n{}", prefix))? + 1;
+    let end = text[start ..].find('\n').map_or(text.len(), |i| start + i + 1);
+    Some(format!("{}{}\n{}", &text[.. end], line, &text[end ..]))
+}
+
+fn add_module(root: &Path, year: u32, day: u32) {
+    let path = root.join(format!("src/y{}.rs", year));
+    let module = read(&path);
+    if module.contains(&format!("pub mod day{};", day)) {
+        return;
+    }
+    let declaration = format!("#[cfg(feature=\"{}\")]\npub mod day{};", feature(year, day), day);
+    write(&path, &format!("{}{}\n", module, declaration));
+}
+
+fn add_feature(root: &Path, year: u32, day: u32) {
+    let path = root.join("Cargo.toml");
+    let manifest = read(&path);
+    let feature = feature(year, day);
+    if manifest.contains(&format!("\n{} = ", feature)) {
+        return;
+    }
+
+    let manifest = insert_after_last(&manifest, "day", &format!("{} = []", feature))
+        .unwrap_or_else(|| fail("No day features in Cargo.toml".to_owned()));
+    // New days come last, so appending keeps the defaults in order.
+    let end = manifest.find("\ndefault = [")
+        .and_then(|start| Some(start + manifest[start ..].find(']')?))
+        .unwrap_or_else(|| fail("No default features in Cargo.toml".to_owned()));
+    let manifest = format!("{}, \"{}\"{}", &manifest[.. end], feature, &manifest[end ..]);
+    write(&path, &manifest);
+}
+
+fn add_to_registry(root: &Path, year: u32, day: u32) {
+    let path = root.join("src/registry.rs");
+    let registry = read(&path);
+    let entry = format!("Day::of::<crate::y{}::day{}::Day{}>()", year, day, day);
+    if registry.contains(&entry) {
+        return;
+    }
+
+    let table = registry.find(&format!("\nfn y{}() -> Vec<Day> {{", year))
+        .unwrap_or_else(|| fail(format!("No table for {} in {}", year, path.display())));
+    let end = table + registry[table ..].find("\n    days\n}")
+        .unwrap_or_else(|| fail(format!("Unexpected table for {} in {}", year, path.display())));
+    let line = format!(
+        "\n    #[cfg(feature=\"{}\")]\n    days.push({});", feature(year, day), entry
+    );
+    write(&path, &format!("{}{}{}", &registry[.. end], line, &registry[end ..]));
+}
+
+pub fn main() {
+    let mut year = y2019::YEAR;
+    let mut fetch = false;
+    let mut day = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => year = args.next().and_then(|year| year.parse().ok()).unwrap_or_else(|| usage()),
+            "--fetch" => fetch = true,
+            _ => {
+                let n = arg.parse::<u32>().ok().filter(|day| (1 ..= 25).contains(day));
+                day = Some(n.unwrap_or_else(|| usage()));
+            },
+        }
+    }
+    let day = day.unwrap_or_else(|| usage());
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    if !root.join(format!("src/y{}.rs", year)).exists() {
+        fail(format!("There is no module for {} yet, add one like src/y2019.rs", year));
+    }
+
+    // Empty files are placeholders, anything else would be lost.
+    let path = root.join(format!("src/y{}/day{}.rs", year, day));
+    if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+        fail(format!("{} exists already", path.display()));
+    }
+    fs::create_dir_all(path.parent().unwrap())
+        .unwrap_or_else(|e| fail(format!("Failed to create {}: {}", path.parent().unwrap().display(), e)));
+    let source = TEMPLATE
+        .replace("dayXX", &format!("day{}", day))
+        .replace("XX", &day.to_string());
+    write(&path, &source);
+
+    add_module(root, year, day);
+    add_feature(root, year, day);
+    add_to_registry(root, year, day);
+
+    if fetch {
+        aoc_2019::util::init();
+        match inputs::load(year, day) {
+            Ok(_) => println!("Input in {}", inputs::path(year, day).display()),
+            Err(e) => fail(e.to_string()),
+        }
+    }
+}
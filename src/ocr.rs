@@ -0,0 +1,52 @@
+pub const LETTER_WIDTH: usize = 4;
+pub const LETTER_HEIGHT: usize = 6;
+pub const LETTER_SPACING: usize = 1;
+
+const ALPHABET: [(char, [&str; LETTER_HEIGHT]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+fn read_letter<F: Fn(usize, usize) -> bool>(offset: usize, is_set: &F) -> char {
+    ALPHABET.iter()
+        .find(|(_, glyph)| {
+            glyph.iter().enumerate().all(|(y, row)| {
+                row.chars().enumerate().all(|(x, c)| (c == '#') == is_set(offset + x, y))
+            })
+        })
+        .map(|(letter, _)| *letter)
+        .unwrap_or('?')
+}
+
+/// Reads block letters, with `is_set(0, 0)` being the top-left pixel of the first letter.
+pub fn read_letters<F: Fn(usize, usize) -> bool>(width: usize, height: usize, is_set: F) -> Option<String> {
+    if height != LETTER_HEIGHT {
+        return None;
+    }
+
+    let mut letters = String::new();
+    let mut offset = 0;
+
+    while offset < width {
+        let letter_is_set = |x, y| x < width && is_set(x, y);
+        letters.push(read_letter(offset, &letter_is_set));
+        offset += LETTER_WIDTH + LETTER_SPACING;
+    }
+
+    Some(letters)
+}
@@ -0,0 +1,152 @@
+use std::collections::VecDeque;
+
+use ggez::{Context, ContextBuilder, GameResult};
+use ggez::event::{self, EventHandler, KeyCode, KeyMods};
+use ggez::graphics::{self, Color, DrawMode, DrawParam, MeshBuilder, Text};
+use ggez::conf::WindowMode;
+use nalgebra::{Vector3, Rotation3};
+
+use crate::y2019::day12::System;
+
+
+const MOON_COLORS: [(u8, u8, u8); 4] = [
+    (0xe0, 0x6c, 0x75),
+    (0x98, 0xc3, 0x79),
+    (0x61, 0xaf, 0xef),
+    (0xe5, 0xc0, 0x7b),
+];
+
+struct OrbitViewer {
+    system: System,
+    trails: Vec<VecDeque<Vector3<f32>>>,
+    trail_length: usize,
+    extent: f32,
+    angle: f32,
+    steps_per_frame: usize,
+    paused: bool,
+}
+
+impl OrbitViewer {
+    pub fn new(system: System) -> Self {
+        let trails = vec![VecDeque::new(); system.bodies().len()];
+
+        let mut viewer = Self {
+            system,
+            trails,
+            trail_length: 500,
+            extent: 1.,
+            angle: 0.,
+            steps_per_frame: 1,
+            paused: false,
+        };
+        viewer.record_positions();
+        viewer
+    }
+
+    fn record_positions(&mut self) {
+        for (body, trail) in self.system.bodies().iter().zip(&mut self.trails) {
+            let [x, y, z] = *body.position();
+            let position = Vector3::new(x as f32, y as f32, z as f32);
+
+            self.extent = self.extent.max(position.amax());
+
+            trail.push_back(position);
+            if trail.len() > self.trail_length {
+                trail.pop_front();
+            }
+        }
+    }
+
+    /// Rotates the scene around the vertical axis, tilts it towards the viewer and projects it onto the window.
+    fn project(&self, position: &Vector3<f32>, center: (f32, f32), scale: f32) -> mint::Point2<f32> {
+        let rotation = Rotation3::from_euler_angles(0.4, self.angle, 0.);
+        let p = rotation * position;
+        mint::Point2::from([center.0 + p.x * scale, center.1 + p.y * scale])
+    }
+}
+
+impl EventHandler for OrbitViewer {
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if !self.paused {
+            for _ in 0 .. self.steps_per_frame {
+                if let Err(e) = self.system.step() {
+                    warn!("{}", e);
+                    self.paused = true;
+                    break;
+                }
+                self.record_positions();
+            }
+            self.angle += ggez::timer::duration_to_f64(ggez::timer::delta(ctx)) as f32 * 0.2;
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        graphics::clear(ctx, Color::from_rgb(0x10, 0x10, 0x18));
+
+        let (width, height) = graphics::drawable_size(ctx);
+        let center = (width / 2., height / 2.);
+        let scale = 0.45 * width.min(height) / self.extent;
+
+        let mut mesh = MeshBuilder::new();
+        let mut empty = true;
+
+        for (trail, &(r, g, b)) in self.trails.iter().zip(MOON_COLORS.iter().cycle()) {
+            let points = trail.iter()
+                .map(|position| self.project(position, center, scale))
+                .collect::<Vec<mint::Point2<f32>>>();
+
+            if points.len() >= 2 {
+                mesh.line(&points, 1., Color::from_rgba(r, g, b, 0x80))?;
+            }
+            if let Some(moon) = points.last() {
+                mesh.circle(DrawMode::fill(), *moon, 6., 0.5, Color::from_rgb(r, g, b));
+                empty = false;
+            }
+        }
+
+        if !empty {
+            let mesh = mesh.build(ctx)?;
+            graphics::draw(ctx, &mesh, DrawParam::default())?;
+        }
+
+        let info = Text::new(format!(
+            "STEP {}  ENERGY {}  SPEED {}{}",
+            self.system.step_count(),
+            self.system.energy(),
+            self.steps_per_frame,
+            if self.paused { "  PAUSED" } else { "" }
+        ));
+        graphics::draw(ctx, &info, DrawParam::new().dest(mint::Point2::from([8., 8.])))?;
+
+        graphics::present(ctx)
+    }
+
+    fn key_up_event(&mut self, ctx: &mut Context, keycode: KeyCode, _keymod: KeyMods) {
+        match keycode {
+            KeyCode::Escape => event::quit(ctx),
+            KeyCode::Space => self.paused = !self.paused,
+            KeyCode::Up => self.steps_per_frame *= 2,
+            KeyCode::Down => self.steps_per_frame = (self.steps_per_frame / 2).max(1),
+            _ => {},
+        }
+    }
+}
+
+pub fn run(system: System) {
+    let window_mode = WindowMode::default()
+        .dimensions(1024.0, 1024.0)
+        .resizable(true);
+
+    let (mut ctx, mut event_loop) = ContextBuilder::new("Advent of Code 2019 Moons", "Janosch Gräf")
+        .window_mode(window_mode)
+        .build()
+        .unwrap();
+
+    let mut viewer = OrbitViewer::new(system);
+
+    match event::run(&mut ctx, &mut event_loop, &mut viewer) {
+        Ok(_) => debug!("Exited cleanly."),
+        Err(e) => debug!("Error occured: {}", e)
+    }
+}
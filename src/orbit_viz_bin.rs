@@ -0,0 +1,11 @@
+extern crate aoc_2019;
+
+use aoc_2019::{inputs, y2019};
+
+
+pub fn main() {
+    aoc_2019::util::init();
+    let input = inputs::load(y2019::YEAR, 12).expect("Failed to load input");
+    let system = y2019::day12::parse_system(&input).expect("Failed to parse input");
+    aoc_2019::orbit_viz::run(system);
+}
@@ -0,0 +1,43 @@
+extern crate aoc_2019;
+
+use std::env;
+use std::fs::read_to_string;
+use std::process;
+
+use aoc_2019::{inputs, viz, y2019};
+use aoc_2019::viz::terminal::Player;
+
+
+fn usage() -> ! {
+    eprintln!("Usage: play 11 [--white] [--fps <n>]");
+    eprintln!("       play 12 [--steps <n>] [--fps <n>]");
+    eprintln!("       play 13 [--level <path>] [--fps <n>]");
+    process::exit(1);
+}
+
+fn arg(name: &str) -> Option<String> {
+    env::args()
+        .skip_while(|arg| arg != name)
+        .nth(1)
+}
+
+fn number(name: &str, default: usize) -> usize {
+    arg(name).map_or(default, |n| n.parse().unwrap_or_else(|_| usage()))
+}
+
+pub fn main() {
+    aoc_2019::util::init();
+    let day: u32 = env::args().nth(1).and_then(|day| day.parse().ok()).unwrap_or_else(|| usage());
+    let input = inputs::load(y2019::YEAR, day).expect("Failed to load input");
+
+    let options = viz::Options {
+        white: env::args().any(|arg| arg == "--white"),
+        steps: arg("--steps").map(|n| n.parse().unwrap_or_else(|_| usage())),
+        level: arg("--level").map(|path| read_to_string(path).unwrap().parse().expect("Failed to parse level")),
+    };
+    let visualization = viz::for_day(day, &input, &options)
+        .expect("Visualization failed")
+        .unwrap_or_else(|| usage());
+
+    Player::new(number("--fps", 20) as u32).play(visualization.as_ref()).expect("Terminal error");
+}
@@ -0,0 +1,10 @@
+//! The types that most programs on top of the crate need, in one `use aoc_2019::prelude::*;`.
+//!
+//! Only what is shared by several days is in here. Types of a single day, like day 13's `Arcade`, stay in their day.
+
+pub use crate::{Error, ParseError};
+pub use crate::grid::Grid;
+pub use crate::intcode::{Machine, Program};
+pub use crate::registry::{Answer, Day, IntoAnswer, Solution};
+pub use crate::search::{astar, bfs, dijkstra};
+pub use crate::viz::{Cell, Frame, Visualize};
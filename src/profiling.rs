@@ -0,0 +1,176 @@
+//! Where does the time go: times, allocations and peak heap usage of each step of a day, and a report across days.
+//!
+//! Allocations are only counted if the binary uses `CountingAlloc` as its global allocator, like `aoc` does. Solvers
+//! that spawn threads have those threads' allocations counted as well, so only profile one day at a time.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::Error;
+use crate::registry::Day;
+use crate::runner::Step;
+use crate::util::format_bytes;
+
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+/// What is allocated right now, and the most since the last `peak`.
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+fn grow(bytes: usize) {
+    let current = CURRENT_BYTES.fetch_add(bytes, Ordering::Relaxed) + bytes;
+    PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+}
+
+fn shrink(bytes: usize) {
+    CURRENT_BYTES.fetch_sub(bytes, Ordering::Relaxed);
+}
+
+/// The system allocator, but counting allocations and keeping track of the peak heap usage. Install it with
+/// `#[global_allocator] static ALLOC: CountingAlloc = CountingAlloc;`.
+pub struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        grow(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        shrink(layout.size());
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        grow(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        if new_size > layout.size() { grow(new_size - layout.size()) } else { shrink(layout.size() - new_size) }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+fn allocations() -> (usize, usize) {
+    (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED_BYTES.load(Ordering::Relaxed))
+}
+
+/// Calls `f` and returns how much more heap than before it used at most, or 0 without `CountingAlloc`.
+pub fn peak<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = CURRENT_BYTES.load(Ordering::Relaxed);
+    PEAK_BYTES.store(before, Ordering::Relaxed);
+    let result = f();
+    (result, PEAK_BYTES.load(Ordering::Relaxed).saturating_sub(before))
+}
+
+/// One step of a day, run once.
+#[derive(Clone, Debug, Serialize)]
+pub struct Profile {
+    pub year: u32,
+    pub day: u32,
+    pub step: Step,
+    pub duration_ms: f64,
+    /// Allocations, including reallocations, or 0 without `CountingAlloc`.
+    pub allocations: usize,
+    pub allocated_bytes: usize,
+    /// How much more heap the step used at most than before it.
+    pub peak_bytes: usize,
+}
+
+fn measure<T>(day: &Day, step: Step, f: impl FnOnce() -> Result<T, Error>) -> Result<(T, Profile), Error> {
+    let (allocations_before, bytes_before) = allocations();
+    let start = Instant::now();
+    let (result, peak_bytes) = peak(f);
+    let duration = start.elapsed();
+    let result = result?;
+    let (allocations_after, bytes_after) = allocations();
+
+    Ok((result, Profile {
+        year: day.year,
+        day: day.day,
+        step,
+        duration_ms: duration.as_secs_f64() * 1000.,
+        allocations: allocations_after - allocations_before,
+        allocated_bytes: bytes_after - bytes_before,
+        peak_bytes,
+    }))
+}
+
+/// Runs the generator and every part of a day once, and measures each.
+pub fn profile(day: &Day, input: &str) -> Result<Vec<Profile>, Error> {
+    let (parsed, generator) = measure(day, Step::Generator, || day.generate(input))?;
+    let mut profiles = vec![generator];
+    for part in 1 ..= day.num_parts() {
+        let (_, profile) = measure(day, Step::Part(part), || day.solve(part, parsed.as_ref()))?;
+        profiles.push(profile);
+    }
+    Ok(profiles)
+}
+
+/// Like `profile`, but also samples the stacks with pprof and writes a flamegraph of the whole day to `path`.
+#[cfg(feature="flamegraph")]
+pub fn profile_with_flamegraph(day: &Day, input: &str, path: &std::path::Path) -> Result<Vec<Profile>, Error> {
+    let guard = pprof::ProfilerGuard::new(1000)?;
+    let profiles = profile(day, input)?;
+    guard.report().build()?.flamegraph(std::fs::File::create(path)?)?;
+    Ok(profiles)
+}
+
+/// The steps from slowest to fastest, with their share of the total time.
+pub fn report(profiles: &[Profile]) -> String {
+    let total: f64 = profiles.iter().map(|profile| profile.duration_ms).sum();
+    let mut sorted = profiles.iter().collect::<Vec<_>>();
+    sorted.sort_by(|a, b| b.duration_ms.total_cmp(&a.duration_ms));
+
+    let mut report = format!(
+        "{:>4}  {:>3}  {:<9}  {:>10}  {:>6}  {:>11}  {:>12}  {:>10}\n",
+        "year", "day", "step", "ms", "share", "allocations", "bytes", "peak"
+    );
+    for profile in sorted {
+        let share = if total > 0. { profile.duration_ms / total * 100. } else { 0. };
+        writeln!(
+            report, "{:>4}  {:>3}  {:<9}  {:>10.3}  {:>5.1}%  {:>11}  {:>12}  {:>10}",
+            profile.year, profile.day, profile.step.to_string(), profile.duration_ms, share, profile.allocations,
+            profile.allocated_bytes, format_bytes(profile.peak_bytes)
+        ).unwrap();
+    }
+    write!(report, "Total: {:.3} ms", total).unwrap();
+    report
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature="day1")]
+    #[test]
+    fn it_reports_slowest_first() {
+        let day = crate::registry::get(2019, 1).unwrap();
+        let profiles = profile(&day, "12\n14").unwrap();
+        assert_eq!(profiles.len(), 3);
+
+        let mut profiles = profiles;
+        profiles[1].duration_ms = 3.;
+        profiles[0].duration_ms = 1.;
+        profiles[2].duration_ms = 0.;
+        let report = report(&profiles);
+        let lines = report.lines().collect::<Vec<_>>();
+        assert!(lines[1].contains("part 1") && lines[1].contains("75.0%"), "{}", report);
+        assert!(lines[2].contains("generator"), "{}", report);
+        assert_eq!(lines[4], "Total: 4.000 ms");
+    }
+
+}
@@ -0,0 +1,174 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use failure::Fail;
+
+use crate::config;
+
+
+static MODE: AtomicU8 = AtomicU8::new(UNSET);
+
+const UNSET: u8 = u8::MAX;
+
+
+#[derive(Debug, Fail)]
+#[fail(display = "Unknown progress mode: {}", _0)]
+pub struct UnknownMode(pub String);
+
+/// Receives progress of a long-running search. A search may run several tasks in parallel (e.g. one per axis), and
+/// usually doesn't know how long it will take, so progress is reported in steps searched.
+pub trait Reporter: Sync {
+    fn update(&self, task: usize, steps: usize);
+    fn done(&self, task: usize, steps: usize);
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Silent;
+
+impl Reporter for Silent {
+    fn update(&self, _task: usize, _steps: usize) {}
+    fn done(&self, _task: usize, _steps: usize) {}
+}
+
+/// Logs progress with `info!`, naming tasks e.g. "Axis 0".
+#[derive(Copy, Clone, Debug)]
+pub struct Log {
+    task: &'static str,
+}
+
+impl Log {
+    pub fn new(task: &'static str) -> Self {
+        Self {
+            task,
+        }
+    }
+}
+
+impl Reporter for Log {
+    fn update(&self, task: usize, steps: usize) {
+        info!("{} {}: searched {} steps", self.task, task, steps);
+    }
+
+    fn done(&self, task: usize, steps: usize) {
+        info!("{} {}: done after {} steps", self.task, task, steps);
+    }
+}
+
+/// One spinner per task.
+#[cfg(feature="indicatif")]
+pub struct Bar {
+    bars: Vec<indicatif::ProgressBar>,
+}
+
+#[cfg(feature="indicatif")]
+impl Bar {
+    pub fn new(task: &str, tasks: usize) -> Self {
+        let multi = indicatif::MultiProgress::new();
+        let style = indicatif::ProgressStyle::with_template("{spinner} {prefix}: {pos} steps {msg}")
+            .expect("Invalid progress bar template");
+
+        Self {
+            bars: (0 .. tasks)
+                .map(|i| {
+                    let bar = multi.add(indicatif::ProgressBar::new_spinner());
+                    bar.set_style(style.clone());
+                    bar.set_prefix(format!("{} {}", task, i));
+                    bar
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature="indicatif")]
+impl Reporter for Bar {
+    fn update(&self, task: usize, steps: usize) {
+        self.bars[task].set_position(steps as u64);
+    }
+
+    fn done(&self, task: usize, steps: usize) {
+        self.bars[task].set_position(steps as u64);
+        self.bars[task].finish_with_message("(done)");
+    }
+}
+
+/// Which reporter solvers use, picked with `AOC_PROGRESS=silent|log|bar` or `progress` in `aoc.toml`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Silent,
+    Log,
+    Bar,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Silent, Mode::Log, Mode::Bar];
+
+    /// The mode from `set_mode`, or else from the configuration. Without either, we show bars if stderr is a terminal
+    /// (and we're built with `indicatif`), and log otherwise.
+    pub fn from_env() -> Self {
+        match MODE.load(Ordering::Relaxed) {
+            UNSET => config::get().visualization.progress.unwrap_or_else(Self::default_for_terminal),
+            mode => Self::ALL[mode as usize],
+        }
+    }
+
+    #[cfg(feature="indicatif")]
+    fn default_for_terminal() -> Self {
+        use std::io::IsTerminal;
+        if std::io::stderr().is_terminal() { Self::Bar } else { Self::Log }
+    }
+
+    #[cfg(not(feature="indicatif"))]
+    fn default_for_terminal() -> Self {
+        Self::Log
+    }
+
+    /// Creates a reporter for `tasks` tasks, which are called `task` in messages.
+    pub fn reporter(self, task: &'static str, tasks: usize) -> Box<dyn Reporter> {
+        match self {
+            Self::Silent => Box::new(Silent),
+            Self::Log => Box::new(Log::new(task)),
+            #[cfg(feature="indicatif")]
+            Self::Bar => Box::new(Bar::new(task, tasks)),
+            #[cfg(not(feature="indicatif"))]
+            Self::Bar => {
+                let _ = tasks;
+                warn!("Progress bars need the `indicatif` feature");
+                Box::new(Log::new(task))
+            },
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Silent => "silent",
+            Self::Log => "log",
+            Self::Bar => "bar",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Mode {
+    type Err = UnknownMode;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.iter()
+            .find(|mode| mode.to_string() == s)
+            .copied()
+            .ok_or_else(|| UnknownMode(s.to_owned()))
+    }
+}
+
+/// Overrides the configured mode, e.g. for frontends that progress bars would draw over.
+pub fn set_mode(mode: Mode) {
+    MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+/// The reporter of the configured mode.
+pub fn from_env(task: &'static str, tasks: usize) -> Box<dyn Reporter> {
+    Mode::from_env().reporter(task, tasks)
+}
@@ -0,0 +1,244 @@
+//! All days in one table, so that our frontends can run the solvers without cargo-aoc's generated main. Each day
+//! implements `Solution`, and then only needs a line in its year's table, e.g. `y2019`. A new year gets a table of its
+//! own.
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::Error;
+
+
+type Generator = Arc<dyn Fn(&str) -> Result<Box<dyn Any>, Error> + Send + Sync>;
+type Solver = Arc<dyn Fn(&dyn Any) -> Result<Answer, Error> + Send + Sync>;
+
+/// The answer of a part, with the type the solver returned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Signed(x) => write!(f, "{}", x),
+            Self::Unsigned(x) => write!(f, "{}", x),
+            Self::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Solvers either return their answer, or a `Result` of it.
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, Error>;
+}
+
+macro_rules! impl_into_answer {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl IntoAnswer for $ty {
+                fn into_answer(self) -> Result<Answer, Error> {
+                    Ok(Answer::$variant(self.into()))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_answer!(i64 => Signed, u64 => Unsigned, String => Text);
+
+impl IntoAnswer for usize {
+    fn into_answer(self) -> Result<Answer, Error> {
+        Ok(Answer::Unsigned(self as u64))
+    }
+}
+
+impl<T: IntoAnswer> IntoAnswer for Result<T, Error> {
+    fn into_answer(self) -> Result<Answer, Error> {
+        self?.into_answer()
+    }
+}
+
+/// A day's puzzle. The implementations call the functions that cargo-aoc knows from `#[aoc_generator]` and `#[aoc]`,
+/// so that both ways of running a day solve it the same way.
+pub trait Solution: 'static {
+    type Input: 'static;
+
+    const YEAR: u32;
+    const DAY: u32;
+    /// The last day only has one part.
+    const PARTS: usize = 2;
+
+    fn parse(input: &str) -> Result<Self::Input, Error>;
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error>;
+
+    /// Only called if `PARTS` is 2.
+    fn part2(_input: &Self::Input) -> Result<Answer, Error> {
+        Err(Error::NoSuchPart(Self::DAY, 2))
+    }
+
+    /// Parses the input and solves part 1 or 2, e.g. for tests.
+    fn solve(part: usize, input: &str) -> Result<Answer, Error> {
+        let input = Self::parse(input)?;
+        match part {
+            1 => Self::part1(&input),
+            2 if Self::PARTS == 2 => Self::part2(&input),
+            _ => Err(Error::NoSuchPart(Self::DAY, part)),
+        }
+    }
+}
+
+/// A day's generator and solvers, with the parsed input hidden behind `Any`, so that all days can be run the same way.
+#[derive(Clone)]
+pub struct Day {
+    pub year: u32,
+    pub day: u32,
+    generator: Generator,
+    parts: Vec<Solver>,
+}
+
+impl Day {
+    pub fn of<S: Solution>() -> Self {
+        fn input<S: Solution>(input: &dyn Any) -> Result<&S::Input, Error> {
+            input.downcast_ref().ok_or(Error::WrongInput(S::DAY))
+        }
+
+        let parts: Vec<Solver> = vec![
+            Arc::new(|parsed| S::part1(input::<S>(parsed)?)),
+            Arc::new(|parsed| S::part2(input::<S>(parsed)?)),
+        ];
+        Self {
+            year: S::YEAR,
+            day: S::DAY,
+            generator: Arc::new(|input| Ok(Box::new(S::parse(input)?))),
+            parts: parts.into_iter().take(S::PARTS).collect(),
+        }
+    }
+
+    pub fn generate(&self, input: &str) -> Result<Box<dyn Any>, Error> {
+        #[cfg(feature="spans")]
+        let _span = tracing::info_span!("parse", year = self.year, day = self.day).entered();
+        (self.generator)(input)
+    }
+
+    pub fn num_parts(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Solves part 1 or 2 with input from this day's `generate`.
+    pub fn solve(&self, part: usize, input: &dyn Any) -> Result<Answer, Error> {
+        let solver = part.checked_sub(1)
+            .and_then(|i| self.parts.get(i))
+            .ok_or(Error::NoSuchPart(self.day, part))?;
+        #[cfg(feature="spans")]
+        let _span = match part {
+            1 => tracing::info_span!("part1", year = self.year, day = self.day),
+            _ => tracing::info_span!("part2", year = self.year, day = self.day),
+        }.entered();
+        solver(input)
+    }
+}
+
+impl fmt::Debug for Day {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Day")
+            .field("year", &self.year)
+            .field("day", &self.day)
+            .field("parts", &self.parts.len())
+            .finish()
+    }
+}
+
+/// All days of all years in order.
+pub fn days() -> Vec<Day> {
+    y2019()
+}
+
+/// The days of one year.
+pub fn days_of(year: u32) -> Vec<Day> {
+    days().into_iter().filter(|day| day.year == year).collect()
+}
+
+/// Only the days with their feature enabled are included.
+// The pushes depend on features, so this can't be a `vec!`, and without any day features nothing is pushed.
+#[allow(clippy::vec_init_then_push, unused_mut)]
+fn y2019() -> Vec<Day> {
+    let mut days = Vec::new();
+    #[cfg(feature="day1")]
+    days.push(Day::of::<crate::y2019::day1::Day1>());
+    #[cfg(feature="day2")]
+    days.push(Day::of::<crate::y2019::day2::Day2>());
+    #[cfg(feature="day3")]
+    days.push(Day::of::<crate::y2019::day3::Day3>());
+    #[cfg(feature="day4")]
+    days.push(Day::of::<crate::y2019::day4::Day4>());
+    #[cfg(feature="day5")]
+    days.push(Day::of::<crate::y2019::day5::Day5>());
+    #[cfg(feature="day6")]
+    days.push(Day::of::<crate::y2019::day6::Day6>());
+    #[cfg(feature="day7")]
+    days.push(Day::of::<crate::y2019::day7::Day7>());
+    #[cfg(feature="day8")]
+    days.push(Day::of::<crate::y2019::day8::Day8>());
+    #[cfg(feature="day9")]
+    days.push(Day::of::<crate::y2019::day9::Day9>());
+    #[cfg(feature="day10")]
+    days.push(Day::of::<crate::y2019::day10::Day10>());
+    #[cfg(feature="day11")]
+    days.push(Day::of::<crate::y2019::day11::Day11>());
+    #[cfg(feature="day12")]
+    days.push(Day::of::<crate::y2019::day12::Day12>());
+    #[cfg(feature="day13")]
+    days.push(Day::of::<crate::y2019::day13::Day13>());
+    days
+}
+
+pub fn get(year: u32, day: u32) -> Option<Day> {
+    days().into_iter().find(|d| d.year == year && d.day == day)
+}
+
+/// Parses the input and solves one part. Returns `None` if there is no such day or part.
+pub fn solve(year: u32, day: u32, part: usize, input: &str) -> Option<Result<Answer, Error>> {
+    let day = get(year, day).filter(|day| (1 ..= day.num_parts()).contains(&part))?;
+    Some(day.generate(input).and_then(|parsed| day.solve(part, parsed.as_ref())))
+}
+
+
+#[cfg(all(test, feature="day1", feature="day4", feature="day6"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_with_typed_answers() {
+        assert_eq!(solve(2019, 1, 1, "12\n14").unwrap().unwrap(), Answer::Unsigned(4));
+        assert_eq!(solve(2019, 6, 1, "COM)B\nB)C").unwrap().unwrap(), Answer::Unsigned(3));
+        assert!(solve(2019, 4, 2, "").is_none());
+        assert!(solve(2019, 25, 1, "").is_none());
+        assert!(solve(2018, 1, 1, "12").is_none());
+    }
+
+    #[test]
+    fn solutions_solve_on_their_own() {
+        use crate::y2019::{day1::Day1, day4::Day4};
+
+        assert_eq!(Day1::solve(2, "14\n1969").unwrap(), Answer::Unsigned(968));
+        assert_eq!(Day4::PARTS, 1);
+        assert_eq!(Day::of::<Day4>().num_parts(), 1);
+        assert!(matches!(Day4::solve(2, "111110-111112"), Err(Error::NoSuchPart(4, 2))));
+        assert!(matches!(Day1::solve(3, "12"), Err(Error::NoSuchPart(1, 3))));
+    }
+
+    #[test]
+    fn days_check_their_part_and_input() {
+        let (day1, day4) = (get(2019, 1).unwrap(), get(2019, 4).unwrap());
+        let parsed = day1.generate("12").unwrap();
+        assert_eq!(day1.solve(1, parsed.as_ref()).unwrap(), Answer::Unsigned(2));
+        assert!(matches!(day1.solve(0, parsed.as_ref()), Err(Error::NoSuchPart(1, 0))));
+        assert!(matches!(day1.solve(3, parsed.as_ref()), Err(Error::NoSuchPart(1, 3))));
+        assert!(matches!(day4.solve(2, parsed.as_ref()), Err(Error::NoSuchPart(4, 2))));
+        assert!(matches!(day4.solve(1, parsed.as_ref()), Err(Error::WrongInput(4))));
+    }
+}
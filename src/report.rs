@@ -0,0 +1,135 @@
+//! The results of `aoc run --format pretty`: a row per part, checked against the known answers, with its time as a bar,
+//! so that a wrong answer or a slow day stands out.
+
+use std::fmt::Write;
+
+use crate::inputs::Answers;
+use crate::runner::Solution;
+use crate::util::format_bytes;
+
+
+const BAR_WIDTH: usize = 24;
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Check {
+    Right,
+    Wrong,
+    Unknown,
+}
+
+impl Check {
+    fn of(answer: &str, expected: Option<&str>) -> Self {
+        match expected {
+            Some(expected) if expected.trim() == answer.trim() => Self::Right,
+            Some(_) => Self::Wrong,
+            None => Self::Unknown,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Right => "✓",
+            Self::Wrong => "✗",
+            Self::Unknown => "?",
+        }
+    }
+
+    fn color(self) -> &'static str {
+        match self {
+            Self::Right => GREEN,
+            Self::Wrong => RED,
+            Self::Unknown => YELLOW,
+        }
+    }
+}
+
+/// Wraps `text` in an ANSI color, if we use colors at all.
+fn paint(text: &str, color: &str, colored: bool) -> String {
+    if colored { format!("{}{}{}", color, text, RESET) } else { text.to_owned() }
+}
+
+/// A bar as long as `duration` is compared to `slowest`, with at least a sliver for anything that took time.
+fn bar(duration_ms: f64, slowest_ms: f64) -> String {
+    if slowest_ms <= 0. {
+        return String::new();
+    }
+    let eighths = (duration_ms / slowest_ms * (BAR_WIDTH * 8) as f64).round().max(1.) as usize;
+    let (full, rest) = (eighths / 8, eighths % 8);
+    let mut bar = "█".repeat(full);
+    if rest > 0 {
+        bar.push(['▏', '▎', '▍', '▌', '▋', '▊', '▉'][rest - 1]);
+    }
+    bar
+}
+
+/// A row per part, with multi-line answers like pictures below it, and a summary of how many answers are right.
+/// `colored` should be false if the report doesn't go to a terminal.
+pub fn pretty(solutions: &[Solution], answers: &Answers, colored: bool) -> String {
+    let slowest = solutions.iter().map(|solution| solution.duration_ms).fold(0., f64::max);
+    let mut counts = [0; 3];
+    let mut report = String::new();
+
+    for solution in solutions {
+        let expected = answers.get(solution.day, solution.part);
+        let check = Check::of(&solution.answer, expected.as_deref());
+        counts[check as usize] += 1;
+
+        let mut lines = solution.answer.lines();
+        let first = if solution.answer.contains('\n') { "" } else { lines.next().unwrap_or_default() };
+        // Only with the `profiling` feature.
+        let peak = solution.peak_bytes.map(|bytes| format!("  {:>10}", format_bytes(bytes))).unwrap_or_default();
+        writeln!(
+            report, "{} day {:>2} part {}  {:<20}  {:>10.3} ms{}  {}",
+            paint(check.symbol(), check.color(), colored), solution.day, solution.part, first, solution.duration_ms,
+            peak, paint(&bar(solution.duration_ms, slowest), DIM, colored)
+        ).unwrap();
+        for line in lines {
+            writeln!(report, "    {}", line).unwrap();
+        }
+        if check == Check::Wrong {
+            let expected = expected.unwrap_or_default();
+            writeln!(report, "    {}", paint(&format!("expected {}", expected.trim()), RED, colored)).unwrap();
+        }
+    }
+
+    let [right, wrong, unknown] = counts;
+    let total: f64 = solutions.iter().map(|solution| solution.duration_ms).sum();
+    write!(
+        report, "{}, {}, {} unknown in {:.3} ms",
+        paint(&format!("{} right", right), GREEN, colored && right > 0),
+        paint(&format!("{} wrong", wrong), RED, colored && wrong > 0),
+        unknown, total
+    ).unwrap();
+    report
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solution(day: u32, part: usize, answer: &str, duration_ms: f64) -> Solution {
+        Solution { year: 2019, day, part, answer: answer.to_owned(), duration_ms, peak_bytes: None }
+    }
+
+    #[test]
+    fn it_checks_answers() {
+        let answers: Answers = toml::from_str("day1 = [4, 5]").unwrap();
+        let solutions = [solution(1, 1, "4", 2.), solution(1, 2, "6", 1.), solution(2, 1, "#.\n.#", 0.)];
+        let report = pretty(&solutions, &answers, false);
+        let lines = report.lines().collect::<Vec<_>>();
+
+        assert!(lines[0].starts_with("✓ day  1 part 1") && lines[0].ends_with(&"█".repeat(BAR_WIDTH)), "{}", report);
+        assert!(lines[1].starts_with("✗ day  1 part 2") && lines[1].ends_with(&"█".repeat(BAR_WIDTH / 2)), "{}", report);
+        assert_eq!(lines[2], "    expected 5");
+        assert!(lines[3].starts_with("? day  2 part 1"), "{}", report);
+        assert_eq!(&lines[4 ..], ["    #.", "    .#", "1 right, 1 wrong, 1 unknown in 3.000 ms"]);
+        assert!(!report.contains('\x1b'));
+    }
+}
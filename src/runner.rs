@@ -0,0 +1,213 @@
+use std::any::Any;
+use std::fmt;
+use std::panic;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Serialize, Serializer};
+
+use crate::Error;
+use crate::registry::{Answer, Day};
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    Generator,
+    Part(usize),
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Generator => write!(f, "generator"),
+            Self::Part(part) => write!(f, "part {}", part),
+        }
+    }
+}
+
+impl Serialize for Step {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Timings of one step over several runs, in milliseconds. Warmup runs aren't included.
+#[derive(Clone, Debug, Serialize)]
+pub struct Benchmark {
+    pub year: u32,
+    pub day: u32,
+    pub step: Step,
+    pub warmup: usize,
+    pub runs: usize,
+    pub mean_ms: f64,
+    /// The sample standard deviation, 0 for a single run.
+    pub stddev_ms: f64,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub max_ms: f64,
+    pub total_ms: f64,
+}
+
+impl Benchmark {
+    fn new(day: &Day, step: Step, warmup: usize, mut times: Vec<Duration>) -> Self {
+        times.sort();
+        let ms: Vec<f64> = times.iter().map(|duration| duration.as_secs_f64() * 1000.).collect();
+        let n = ms.len() as f64;
+        let total = ms.iter().sum::<f64>();
+        let mean = total / n;
+        // For an even number of runs, the mean of the middle two.
+        let median = (ms[(ms.len() - 1) / 2] + ms[ms.len() / 2]) / 2.;
+        let variance = if ms.len() > 1 {
+            ms.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.)
+        }
+        else {
+            0.
+        };
+        Self {
+            year: day.year,
+            day: day.day,
+            step,
+            warmup,
+            runs: ms.len(),
+            mean_ms: mean,
+            stddev_ms: variance.sqrt(),
+            min_ms: ms[0],
+            median_ms: median,
+            max_ms: ms[ms.len() - 1],
+            total_ms: total,
+        }
+    }
+}
+
+/// Calls `f` `warmup` times, and then `runs` times while timing it.
+fn time<T>(runs: usize, warmup: usize, mut f: impl FnMut() -> Result<T, Error>) -> Result<(T, Vec<Duration>), Error> {
+    for _ in 0 .. warmup {
+        f()?;
+    }
+
+    let mut times = Vec::with_capacity(runs);
+    let mut result = None;
+    for _ in 0 .. runs.max(1) {
+        let start = Instant::now();
+        result = Some(f()?);
+        times.push(start.elapsed());
+    }
+    Ok((result.unwrap(), times))
+}
+
+/// Runs the generator and every part of a day `runs` times each, after `warmup` runs that aren't timed, e.g. to fill
+/// caches.
+pub fn bench(day: &Day, input: &str, runs: usize, warmup: usize) -> Result<Vec<Benchmark>, Error> {
+    let (parsed, times) = time(runs, warmup, || day.generate(input))?;
+    let mut benchmarks = vec![Benchmark::new(day, Step::Generator, warmup, times)];
+
+    for part in 1 ..= day.num_parts() {
+        let (_, times) = time(runs, warmup, || day.solve(part, parsed.as_ref()))?;
+        benchmarks.push(Benchmark::new(day, Step::Part(part), warmup, times));
+    }
+
+    Ok(benchmarks)
+}
+
+/// The answer to one part, as printed by `aoc run --format json`.
+#[derive(Clone, Debug, Serialize)]
+pub struct Solution {
+    pub year: u32,
+    pub day: u32,
+    pub part: usize,
+    pub answer: String,
+    pub duration_ms: f64,
+    /// How much more heap the part used at most than before it, on top of the parsed input. Only with the `profiling`
+    /// feature, and 0 unless the binary uses `profiling::CountingAlloc`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_bytes: Option<usize>,
+}
+
+#[cfg(feature="profiling")]
+fn solve(day: &Day, part: usize, parsed: &dyn Any) -> (Result<Answer, Error>, Option<usize>) {
+    let (answer, peak) = crate::profiling::peak(|| day.solve(part, parsed));
+    (answer, Some(peak))
+}
+
+#[cfg(not(feature="profiling"))]
+fn solve(day: &Day, part: usize, parsed: &dyn Any) -> (Result<Answer, Error>, Option<usize>) {
+    (day.solve(part, parsed), None)
+}
+
+/// Solves every part of a day once. The duration of a part doesn't include the generator.
+pub fn run(day: &Day, input: &str) -> Result<Vec<Solution>, Error> {
+    let parsed = day.generate(input)?;
+
+    (1 ..= day.num_parts())
+        .map(|part| {
+            let start = Instant::now();
+            let (answer, peak_bytes) = solve(day, part, parsed.as_ref());
+            let duration = start.elapsed();
+            Ok(Solution {
+                year: day.year,
+                day: day.day,
+                part,
+                answer: answer?.to_string(),
+                duration_ms: duration.as_secs_f64() * 1000.,
+                peak_bytes,
+            })
+        })
+        .collect()
+}
+
+/// Runs `solver` on a worker thread and returns `Error::TimedOut` if it doesn't finish within `timeout`. A solver can't
+/// be stopped, so a timed out worker keeps running in the background until the process exits. Panics are passed on.
+pub fn run_with_timeout<T, F>(solver: F, timeout: Duration) -> Result<T, Error>
+    where T: Send + 'static,
+          F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    let worker = thread::spawn(move || {
+        // The receiver is gone if we timed out already.
+        sender.send(solver()).ok();
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(Error::TimedOut(timeout)),
+        Err(RecvTimeoutError::Disconnected) => match worker.join() {
+            Err(payload) => panic::resume_unwind(payload),
+            Ok(()) => unreachable!("Worker finished without a result"),
+        },
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_results_in_time() {
+        assert_eq!(run_with_timeout(|| Ok(42), Duration::from_secs(10)).unwrap(), 42);
+    }
+
+    #[cfg(feature="day1")]
+    #[test]
+    fn it_computes_statistics() {
+        let day = crate::registry::get(2019, 1).unwrap();
+        let times = [3, 1, 2, 6].iter().map(|ms| Duration::from_millis(*ms)).collect();
+        let benchmark = Benchmark::new(&day, Step::Part(1), 2, times);
+
+        assert_eq!(benchmark.runs, 4);
+        assert_eq!(benchmark.mean_ms, 3.);
+        assert!((benchmark.stddev_ms - (14f64 / 3.).sqrt()).abs() < 1e-9);
+        assert_eq!((benchmark.min_ms, benchmark.median_ms, benchmark.max_ms), (1., 2.5, 6.));
+        assert_eq!(benchmark.total_ms, 12.);
+
+        let times = [3, 1, 2].iter().map(|ms| Duration::from_millis(*ms)).collect();
+        assert_eq!(Benchmark::new(&day, Step::Part(1), 0, times).median_ms, 2.);
+    }
+
+    #[test]
+    fn it_times_out() {
+        let result = run_with_timeout(|| -> Result<(), Error> { loop { thread::park() } }, Duration::from_millis(10));
+        assert!(matches!(result, Err(Error::TimedOut(_))));
+    }
+}
@@ -0,0 +1,181 @@
+//! Path finding over implicit graphs. States only need to be hashable, and the graph is given by a closure that
+//! returns the neighbors of a state, so mazes don't have to be turned into graphs first.
+//!
+//! All searches return the path from the start to the first goal found, including both, and its cost.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use num_traits::Zero;
+
+
+/// States in the order they were discovered, with the index of the state they were discovered from.
+struct Tree<S> {
+    nodes: Vec<(S, Option<usize>)>,
+}
+
+impl<S: Clone> Tree<S> {
+    fn new(start: S) -> Self {
+        Self {
+            nodes: vec![(start, None)],
+        }
+    }
+
+    fn state(&self, index: usize) -> &S {
+        &self.nodes[index].0
+    }
+
+    fn push(&mut self, state: S, parent: usize) -> usize {
+        self.nodes.push((state, Some(parent)));
+        self.nodes.len() - 1
+    }
+
+    fn path(&self, mut index: usize) -> Vec<S> {
+        let mut path = vec![self.nodes[index].0.clone()];
+        while let Some(parent) = self.nodes[index].1 {
+            path.push(self.nodes[parent].0.clone());
+            index = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search, for graphs in which every step costs the same. The cost is the number of steps.
+pub fn bfs<S, N, I, G>(start: S, mut neighbors: N, mut is_goal: G) -> Option<(Vec<S>, usize)>
+    where S: Clone + Eq + Hash,
+          N: FnMut(&S) -> I,
+          I: IntoIterator<Item = S>,
+          G: FnMut(&S) -> bool,
+{
+    let mut tree = Tree::new(start.clone());
+    let mut steps = HashMap::new();
+    steps.insert(start, 0);
+    let mut queue = VecDeque::new();
+    queue.push_back(0);
+
+    while let Some(index) = queue.pop_front() {
+        let state = tree.state(index).clone();
+        let cost = steps[&state];
+        if is_goal(&state) {
+            return Some((tree.path(index), cost));
+        }
+
+        for next in neighbors(&state) {
+            if !steps.contains_key(&next) {
+                steps.insert(next.clone(), cost + 1);
+                queue.push_back(tree.push(next, index));
+            }
+        }
+    }
+
+    None
+}
+
+/// Dijkstra's algorithm, for graphs with non-negative step costs. `neighbors` returns each neighbor with the cost of
+/// the step to it.
+pub fn dijkstra<S, C, N, I, G>(start: S, neighbors: N, is_goal: G) -> Option<(Vec<S>, C)>
+    where S: Clone + Eq + Hash,
+          C: Copy + Ord + Zero + Add<Output = C>,
+          N: FnMut(&S) -> I,
+          I: IntoIterator<Item = (S, C)>,
+          G: FnMut(&S) -> bool,
+{
+    astar(start, neighbors, |_| C::zero(), is_goal)
+}
+
+/// A* search. Like `dijkstra`, but explores states with a lower estimate of the total cost first. The `heuristic`
+/// must never overestimate the cost left to a goal, or the path found might not be the cheapest.
+pub fn astar<S, C, N, I, H, G>(start: S, mut neighbors: N, mut heuristic: H, mut is_goal: G) -> Option<(Vec<S>, C)>
+    where S: Clone + Eq + Hash,
+          C: Copy + Ord + Zero + Add<Output = C>,
+          N: FnMut(&S) -> I,
+          I: IntoIterator<Item = (S, C)>,
+          H: FnMut(&S) -> C,
+          G: FnMut(&S) -> bool,
+{
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((heuristic(&start), C::zero(), 0)));
+    let mut tree = Tree::new(start.clone());
+    // The cheapest cost to each state found so far, and where in the tree it was found.
+    let mut best = HashMap::new();
+    best.insert(start, (C::zero(), 0));
+
+    while let Some(Reverse((_, cost, index))) = open.pop() {
+        let state = tree.state(index).clone();
+        if best[&state].1 != index {
+            // We found a cheaper way to this state after this one was queued.
+            continue;
+        }
+        if is_goal(&state) {
+            return Some((tree.path(index), cost));
+        }
+
+        for (next, step) in neighbors(&state) {
+            let next_cost = cost + step;
+            if best.get(&next).is_none_or(|&(known, _)| next_cost < known) {
+                let estimate = next_cost + heuristic(&next);
+                let next_index = tree.push(next.clone(), index);
+                best.insert(next, (next_cost, next_index));
+                open.push(Reverse((estimate, next_cost, next_index)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A grid with walls, moving in four directions.
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#G.
+.####...";
+
+    fn find(c: char) -> (i64, i64) {
+        MAZE.lines()
+            .enumerate()
+            .find_map(|(y, line)| line.find(c).map(|x| (x as i64, y as i64)))
+            .unwrap()
+    }
+
+    fn open_neighbors(&(x, y): &(i64, i64)) -> Vec<(i64, i64)> {
+        let lines: Vec<&[u8]> = MAZE.lines().map(str::as_bytes).collect();
+        [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)].iter()
+            .copied()
+            .filter(|&(x, y)| x >= 0 && y >= 0)
+            .filter(|&(x, y)| lines.get(y as usize).and_then(|line| line.get(x as usize)).is_some_and(|&c| c != b'#'))
+            .collect()
+    }
+
+    #[test]
+    fn searches_agree_on_maze() {
+        let (start, goal) = (find('S'), find('G'));
+        let weighted = |state: &(i64, i64)| open_neighbors(state).into_iter().map(|next| (next, 1));
+        let manhattan = |&(x, y): &(i64, i64)| (goal.0 - x).abs() + (goal.1 - y).abs();
+
+        let (path, steps) = bfs(start, open_neighbors, |&state| state == goal).unwrap();
+        assert_eq!(steps, 14);
+        assert_eq!((path.first(), path.last(), path.len()), (Some(&start), Some(&goal), 15));
+        assert_eq!(dijkstra(start, weighted, |&state| state == goal).unwrap().1, 14);
+        assert_eq!(astar(start, weighted, manhattan, |&state| state == goal).unwrap().1, 14);
+    }
+
+    #[test]
+    fn dijkstra_takes_cheaper_detour() {
+        // 0 -> 2 directly costs 10, but over 1 only 3.
+        let edges = |&state: &u32| match state {
+            0 => vec![(1, 1), (2, 10)],
+            1 => vec![(2, 2)],
+            _ => vec![],
+        };
+        assert_eq!(dijkstra(0, edges, |&state| state == 2), Some((vec![0, 1, 2], 3)));
+        assert_eq!(dijkstra(1, edges, |&state| state == 0), None);
+    }
+}
@@ -0,0 +1,86 @@
+//! Adds up how long the solvers spend in each kind of span, e.g. `parse`, `part2` or `Machine::run`, to answer
+//! questions like "how much of day 13 is spent in the intcode machine".
+//!
+//! `util::init` installs the subscriber. Times are inclusive, so a `Machine::run` in `part1` counts for both.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::span::Id;
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+
+static TOTALS: Mutex<BTreeMap<&'static str, Total>> = Mutex::new(BTreeMap::new());
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Total {
+    time: Duration,
+    /// How often the span was entered.
+    count: usize,
+    /// Whether the span has no parent, i.e. is a phase like `parse` and not something that happens in a phase.
+    root: bool,
+}
+
+/// When the span was entered last.
+struct Entered(Instant);
+
+struct Timings;
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Timings {
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().replace(Entered(Instant::now()));
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let entered = match span.extensions_mut().remove::<Entered>() {
+            Some(Entered(entered)) => entered,
+            None => return,
+        };
+
+        let mut totals = TOTALS.lock().unwrap();
+        let total = totals.entry(span.name()).or_default();
+        total.time += entered.elapsed();
+        total.count += 1;
+        total.root |= span.parent().is_none();
+    }
+}
+
+/// Installs a subscriber that records span times. Does nothing if there is a subscriber already.
+pub fn install() {
+    let subscriber = tracing_subscriber::registry().with(Timings);
+    if tracing::subscriber::set_global_default(subscriber).is_err() {
+        warn!("Another tracing subscriber is installed already, so spans aren't timed");
+    }
+}
+
+/// Forgets the times so far, e.g. to time each day on its own.
+pub fn reset() {
+    TOTALS.lock().unwrap().clear();
+}
+
+/// The spans from slowest to fastest, with their share of the time in spans without a parent.
+pub fn report() -> String {
+    let totals = TOTALS.lock().unwrap();
+    let root: Duration = totals.values().filter(|total| total.root).map(|total| total.time).sum();
+    let mut sorted = totals.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|(_, total)| std::cmp::Reverse(total.time));
+
+    let mut report = format!("{:<34}  {:>10}  {:>6}  {:>10}\n", "span", "ms", "share", "count");
+    for (name, total) in sorted {
+        let share = if root.is_zero() { 0. } else { total.time.as_secs_f64() / root.as_secs_f64() * 100. };
+        writeln!(
+            report, "{:<34}  {:>10.3}  {:>5.1}%  {:>10}", name, total.time.as_secs_f64() * 1000., share, total.count
+        ).unwrap();
+    }
+    report
+}
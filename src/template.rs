@@ -1,21 +1,74 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::registry::{Answer, IntoAnswer, Solution};
 use crate::util;
+use crate::{Error, ParseError};
 
 
 #[aoc_generator(dayXX)]
-pub fn input_generator(input: &str) -> () {
-    unimplemented!()
+pub fn input_generator(input: &str) -> Result<Vec<i64>, Error> {
+    let input = util::normalize_input(input);
+    input.lines()
+        .map(|line| line.parse::<i64>().map_err(|e| ParseError::at(XX, &input, line, e).into()))
+        .collect()
 }
 
 #[aoc(dayXX, part1)]
-pub fn solve_part1(x: &()) -> i64 {
-    unimplemented!()
+pub fn solve_part1(_input: &[i64]) -> i64 {
+    todo!()
 }
 
-/*
 #[aoc(dayXX, part2)]
-pub fn solve_part2(x: &()) -> i64 {
-    unimplemented!()
+pub fn solve_part2(_input: &[i64]) -> i64 {
+    todo!()
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<i64, Error> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<i64, Error> {
+    Ok(solve_part2(&input_generator(input)?))
+}
+
+pub struct DayXX;
+
+impl Solution for DayXX {
+    type Input = Vec<i64>;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = XX;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    #[ignore = "Fill in the example"]
+    fn solves_example_part1() {
+        assert_eq!(DayXX::solve(1, EXAMPLE).unwrap(), Answer::Signed(0));
+    }
+
+    #[test]
+    #[ignore = "Fill in the example"]
+    fn solves_example_part2() {
+        assert_eq!(DayXX::solve(2, EXAMPLE).unwrap(), Answer::Signed(0));
+    }
 }
-*/
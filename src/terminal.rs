@@ -0,0 +1,27 @@
+use std::io::{self, Stdout};
+
+use crossterm::{cursor, execute, terminal};
+
+
+/// Puts the terminal into raw mode on an alternate screen and restores it when dropped, even on panics.
+pub struct TerminalGuard {
+    pub stdout: Stdout,
+}
+
+impl TerminalGuard {
+    pub fn new() -> io::Result<Self> {
+        let mut stdout = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self {
+            stdout,
+        })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(self.stdout, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
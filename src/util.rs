@@ -1,10 +1,263 @@
+use std::borrow::Cow;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
 use std::sync::Once;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use failure::Fail;
+use log::LevelFilter;
+use rand::{RngCore, SeedableRng};
+
+use crate::config::{self, Config};
 
 static INIT: Once = Once::new();
+static VERBOSITY: AtomicU8 = AtomicU8::new(UNSET);
+
+const UNSET: u8 = u8::MAX;
+
+
+#[derive(Debug, Fail)]
+#[fail(display = "Unknown verbosity: {}", _0)]
+pub struct UnknownVerbosity(pub String);
+
+/// How much is logged. Except when quiet, `RUST_LOG` can still change the level per module.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    /// Only errors.
+    Quiet,
+    /// Warnings and errors.
+    #[default]
+    Normal,
+    /// Progress of the solvers.
+    Verbose,
+    /// Everything, which slows down the hot loops considerably.
+    Debug,
+}
+
+impl Verbosity {
+    pub const ALL: [Verbosity; 4] = [Verbosity::Quiet, Verbosity::Normal, Verbosity::Verbose, Verbosity::Debug];
+
+    pub fn level_filter(self) -> LevelFilter {
+        match self {
+            Self::Quiet => LevelFilter::Error,
+            Self::Normal => LevelFilter::Warn,
+            Self::Verbose => LevelFilter::Info,
+            Self::Debug => LevelFilter::Debug,
+        }
+    }
+}
+
+impl fmt::Display for Verbosity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Quiet => "quiet",
+            Self::Normal => "normal",
+            Self::Verbose => "verbose",
+            Self::Debug => "debug",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Verbosity {
+    type Err = UnknownVerbosity;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.iter()
+            .find(|verbosity| verbosity.to_string() == s)
+            .copied()
+            .ok_or_else(|| UnknownVerbosity(s.to_owned()))
+    }
+}
+
+/// Sets the verbosity for `init`, overriding the configuration. Has no effect once the logger is initialized.
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// The verbosity from `set_verbosity`, or else from the configuration.
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        UNSET => config::get().verbosity.unwrap_or_default(),
+        verbosity => Verbosity::ALL[verbosity as usize],
+    }
+}
+
+/// A small PCG (XSH-RR with 64 bits of state) for everything random that should be reproducible, like tests and
+/// shuffled arcade levels. Unlike rand's `StdRng`, its numbers for a seed won't change when we update rand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6364136223846793005;
+    /// Stream 54, like the demo of the reference implementation, so that we can test against its numbers.
+    const INCREMENT: u64 = (54 << 1) | 1;
+
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Self { state: 0 };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+}
+
+impl RngCore for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.state = state.wrapping_mul(Self::MULTIPLIER).wrapping_add(Self::INCREMENT);
+        let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+        xorshifted.rotate_right((state >> 59) as u32)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32() as u64;
+        low | ((self.next_u32() as u64) << 32)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[.. chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
 
+impl SeedableRng for Pcg32 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    fn seed_from_u64(seed: u64) -> Self {
+        Self::new(seed)
+    }
+}
+
+/// A random number generator that gives the same numbers for the same seed, on every platform.
+pub fn rng(seed: u64) -> Pcg32 {
+    Pcg32::new(seed)
+}
+
+/// Removes what editors and downloads on Windows add to inputs: A byte order mark, `\r\n` line endings and trailing
+/// whitespace.
+pub fn normalize_input(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input).trim_end();
+    if input.contains("\r\n") {
+        Cow::Owned(input.replace("\r\n", "\n"))
+    }
+    else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// Bytes with a binary unit, e.g. `1.5 GiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024. && unit < UNITS.len() - 1 {
+        size /= 1024.;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", size, UNITS[unit]) }
+}
+
+/// Sets up logging once, with the verbosity and `RUST_LOG` filters from `.env` and the configuration. Both are
+/// optional.
 pub fn init() {
     INIT.call_once(|| {
-        dotenv::dotenv().unwrap();
-        pretty_env_logger::init();
+        install_logger(None);
+        init_globals();
+    });
+}
+
+/// Like `init`, but logs as `filter` says instead of the verbosity and `RUST_LOG`. The syntax is the one of `RUST_LOG`,
+/// e.g. `info` or `warn,aoc_2019::y2019::day12=debug`.
+pub fn init_with_filter(filter: &str) {
+    INIT.call_once(|| {
+        install_logger(Some(filter));
+        init_globals();
     });
 }
+
+/// For embedding the solvers: Uses `config` instead of reading `aoc.toml` and `.env`, and leaves logging to the
+/// caller. This has to be called before anything else of this crate, or the config is returned.
+#[allow(clippy::result_large_err)]
+pub fn init_with(config: Config) -> Result<(), Config> {
+    config::set(config)?;
+    INIT.call_once(init_globals);
+    Ok(())
+}
+
+/// What the optional features need set up once, besides logging.
+fn init_globals() {
+    // Limits rayon to the configured number of threads.
+    #[cfg(feature="parallel")]
+    if let Err(e) = rayon::ThreadPoolBuilder::new().num_threads(config::get().threads()).build_global() {
+        warn!("Failed to set up the thread pool: {}", e);
+    }
+
+    #[cfg(feature="spans")]
+    crate::spans::install();
+}
+
+fn install_logger(filter: Option<&str>) {
+    // Also loads `.env`, in which `RUST_LOG` may be set.
+    config::get();
+
+    let mut builder = pretty_env_logger::formatted_builder();
+    match filter {
+        Some(filter) => {
+            builder.parse_filters(filter);
+        },
+        None => {
+            let verbosity = verbosity();
+            builder.filter_level(verbosity.level_filter());
+            if verbosity != Verbosity::Quiet {
+                if let Ok(filters) = env::var("RUST_LOG") {
+                    builder.parse_filters(&filters);
+                }
+            }
+        },
+    }
+    // Frontends like the dashboard may have installed their own logger already.
+    builder.try_init().ok();
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_windows_inputs() {
+        assert_eq!(normalize_input("\u{feff}1,2\r\n3,4\r\n\r\n"), "1,2\n3,4");
+        assert!(matches!(normalize_input("1,2\n3,4\n"), Cow::Borrowed("1,2\n3,4")));
+    }
+
+    #[test]
+    fn rng_is_reproducible() {
+        // The output of the reference implementation for seed 42.
+        let mut rng = rng(42);
+        let numbers = (0 .. 6).map(|_| rng.next_u32()).collect::<Vec<_>>();
+        assert_eq!(numbers, vec![0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]);
+        assert_eq!(Pcg32::seed_from_u64(7), super::rng(7));
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(1000), "1000 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 << 30), "3.0 GiB");
+    }
+}
@@ -0,0 +1,207 @@
+//! Visualizations as streams of frames, so that every renderer works with every day. A frame is a grid of glyphs
+//! with colors from a palette, which the renderer picks, e.g. the terminal or an image exporter.
+
+use std::fmt;
+use std::str::FromStr;
+
+use failure::Fail;
+
+#[cfg(all(feature="day11", feature="day12", feature="day13"))]
+use crate::y2019::{day11, day12, day13};
+
+#[cfg(feature="crossterm")]
+pub mod terminal;
+#[cfg(feature="image")]
+pub mod export;
+
+
+/// Color indices with a common meaning, so that palettes fit all visualizations.
+pub const BACKGROUND: u8 = 0;
+pub const DIM: u8 = 1;
+pub const NORMAL: u8 = 2;
+pub const BRIGHT: u8 = 3;
+/// Something to follow, like a robot or ball.
+pub const HIGHLIGHT: u8 = 4;
+
+pub type Rgb = [u8; 3];
+
+#[derive(Debug, Fail)]
+#[fail(display = "Invalid palette: {}", _0)]
+pub struct InvalidPalette(String);
+
+/// The colors for the color indices of a frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Palette {
+    colors: Vec<Rgb>,
+}
+
+impl Palette {
+    pub fn new(colors: Vec<Rgb>) -> Self {
+        assert!(!colors.is_empty(), "Empty palette");
+        Self {
+            colors,
+        }
+    }
+
+    /// The colors of the arcade, plus red for highlights.
+    pub fn game_boy() -> Self {
+        Self::new(vec![[0x0f, 0x38, 0x0f], [0x30, 0x62, 0x30], [0x8b, 0xac, 0x0f], [0x9b, 0xbc, 0x0f], [0xe0, 0x40, 0x40]])
+    }
+
+    /// Colors past the end of the palette wrap around.
+    pub fn get(&self, color: u8) -> Rgb {
+        self.colors[color as usize % self.colors.len()]
+    }
+}
+
+/// Parses comma-separated hex colors, e.g. `#000000,#555555,#aaaaaa,#ffffff,#ff0000`.
+impl FromStr for Palette {
+    type Err = InvalidPalette;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let colors = s.split(',')
+            .map(|color| {
+                let hex = color.trim().trim_start_matches('#');
+                if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(InvalidPalette(color.to_owned()));
+                }
+                let rgb = u32::from_str_radix(hex, 16).unwrap();
+                Ok([(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8])
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(colors))
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::game_boy()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub color: u8,
+}
+
+impl Cell {
+    pub const EMPTY: Cell = Cell::new(' ', BACKGROUND);
+
+    pub const fn new(glyph: char, color: u8) -> Self {
+        Self {
+            glyph,
+            color,
+        }
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    /// A line of text to show with the frame, e.g. the score.
+    pub caption: String,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::EMPTY; width * height],
+            caption: String::new(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        if x < self.width && y < self.height { self.cells.get(y * self.width + x) } else { None }
+    }
+
+    /// Sets a cell. Cells outside of the frame are ignored, so callers don't have to clip.
+    pub fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = cell;
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> + '_ {
+        self.cells.chunks(self.width.max(1))
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", row.iter().map(|cell| cell.glyph).collect::<String>())?;
+        }
+        write!(f, "{}", self.caption)
+    }
+}
+
+/// Something that can be animated. The frames of one visualization should all have the same size.
+pub trait Visualize {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame>>;
+}
+
+/// Settings for the visualizations of `for_day`. Each day only looks at its own.
+#[cfg(all(feature="day11", feature="day12", feature="day13"))]
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// Day 11: Start on a white panel.
+    pub white: bool,
+    /// Day 12: How many steps to simulate.
+    pub steps: Option<usize>,
+    /// Day 13: Play this level instead of the input's.
+    pub level: Option<day13::Level>,
+}
+
+/// The visualization of a day, for the binaries to share. Returns `None` for days without one.
+#[cfg(all(feature="day11", feature="day12", feature="day13"))]
+pub fn for_day(day: u32, input: &str, options: &Options) -> Result<Option<Box<dyn Visualize>>, crate::Error> {
+    let visualization: Box<dyn Visualize> = match day {
+        11 => {
+            let start = day11::StartState {
+                panel_color: if options.white { Some(day11::Color::White) } else { None },
+                .. day11::StartState::default()
+            };
+            Box::new(day11::HullHistory::record(day11::input_generator(input)?, &start)?)
+        },
+        12 => Box::new(day12::Snapshots::record(&day12::input_generator(input)?, options.steps.unwrap_or(1000))?),
+        13 => Box::new(day13::Autoplay {
+            program: day13::input_generator(input)?,
+            level: options.level.clone(),
+        }),
+        _ => return Ok(None),
+    };
+    Ok(Some(visualization))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_palettes() {
+        let palette: Palette = "#000000, 55aaff".parse().unwrap();
+        assert_eq!(palette, Palette::new(vec![[0, 0, 0], [0x55, 0xaa, 0xff]]));
+        assert!("#12345".parse::<Palette>().is_err());
+        assert!("#00000g".parse::<Palette>().is_err());
+        assert!("".parse::<Palette>().is_err());
+    }
+}
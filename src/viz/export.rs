@@ -0,0 +1,116 @@
+//! Renders visualizations to images, one filled square per cell. Glyphs and captions aren't drawn, so visualizations
+//! read best if they tell things apart by color.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+use failure::Fail;
+use image::{Delay, ImageFormat, Rgba, RgbaImage};
+use image::codecs::gif::{GifEncoder, Repeat};
+
+use crate::viz::{Frame, Palette, Visualize};
+
+
+#[derive(Debug, Fail)]
+pub enum ExportError {
+    #[fail(display = "Image error: {}", _0)]
+    Image(#[cause] image::ImageError),
+    #[fail(display = "IO error: {}", _0)]
+    Io(#[cause] io::Error),
+    #[fail(display = "Visualization has no frames")]
+    NoFrames,
+}
+
+impl From<image::ImageError> for ExportError {
+    fn from(e: image::ImageError) -> Self {
+        Self::Image(e)
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Exporter {
+    cell_size: u32,
+    palette: Palette,
+}
+
+impl Exporter {
+    /// `cell_size` is the width and height of a cell in pixels.
+    pub fn new(cell_size: u32) -> Self {
+        Self {
+            cell_size: cell_size.max(1),
+            palette: Palette::default(),
+        }
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    pub fn render(&self, frame: &Frame) -> RgbaImage {
+        let mut image = RgbaImage::new(frame.width() as u32 * self.cell_size, frame.height() as u32 * self.cell_size);
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            let cell = frame.get((x / self.cell_size) as usize, (y / self.cell_size) as usize).unwrap();
+            let [r, g, b] = self.palette.get(cell.color);
+            *pixel = Rgba([r, g, b, 0xff]);
+        }
+        image
+    }
+
+    /// Writes an animated GIF that loops forever. Returns the number of frames.
+    pub fn export_gif<P: AsRef<Path>>(&self, visualization: &dyn Visualize, fps: u32, path: P) -> Result<usize, ExportError> {
+        let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+        encoder.set_repeat(Repeat::Infinite)?;
+        let delay = Delay::from_numer_denom_ms(1000, fps.max(1));
+
+        let mut count = 0;
+        for frame in visualization.frames() {
+            encoder.encode_frame(image::Frame::from_parts(self.render(&frame), 0, 0, delay))?;
+            count += 1;
+        }
+        if count == 0 {
+            return Err(ExportError::NoFrames);
+        }
+        Ok(count)
+    }
+
+    /// Writes `frame-00000.png`, `frame-00001.png`, ... into a directory, which is created if necessary. Returns the
+    /// number of frames.
+    pub fn export_png_sequence<P: AsRef<Path>>(&self, visualization: &dyn Visualize, dir: P) -> Result<usize, ExportError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let mut count = 0;
+        for frame in visualization.frames() {
+            self.render(&frame).save_with_format(dir.join(format!("frame-{:05}.png", count)), ImageFormat::Png)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::viz::{Cell, HIGHLIGHT};
+
+    #[test]
+    fn it_renders_cells_as_squares() {
+        let mut frame = Frame::new(2, 1);
+        frame.set(1, 0, Cell::new('o', HIGHLIGHT));
+        let palette = Palette::new(vec![[0, 0, 0], [1, 1, 1], [2, 2, 2], [3, 3, 3], [255, 0, 0]]);
+        let image = Exporter::new(3).with_palette(palette).render(&frame);
+
+        assert_eq!(image.dimensions(), (6, 3));
+        assert_eq!(*image.get_pixel(2, 2), Rgba([0, 0, 0, 0xff]));
+        assert_eq!(*image.get_pixel(3, 0), Rgba([255, 0, 0, 0xff]));
+    }
+}
@@ -0,0 +1,134 @@
+//! Plays visualizations in the terminal, in the colors of a palette.
+
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::{cursor, queue, terminal};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+
+use crate::terminal::TerminalGuard;
+use crate::viz::{Frame, Palette, Visualize, BACKGROUND};
+
+
+const MAX_FPS: u32 = 960;
+
+fn color(palette: &Palette, index: u8) -> Color {
+    let [r, g, b] = palette.get(index);
+    Color::Rgb { r, g, b }
+}
+
+/// Plays frames at a fixed rate. While playing, SPACE pauses, RIGHT or `.` steps one frame, `+` and `-` double or
+/// halve the speed, and Q or ESC quit.
+#[derive(Clone, Debug)]
+pub struct Player {
+    fps: u32,
+    palette: Palette,
+}
+
+impl Player {
+    pub fn new(fps: u32) -> Self {
+        Self {
+            fps: fps.clamp(1, MAX_FPS),
+            palette: Palette::default(),
+        }
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    fn render(&self, stdout: &mut Stdout, frame: &Frame, status: &str) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        // Two lines for the caption and status.
+        let rows = (height as usize).saturating_sub(2);
+
+        queue!(stdout, cursor::MoveTo(0, 0))?;
+        for row in frame.rows().take(rows) {
+            let mut current = None;
+            queue!(stdout, SetBackgroundColor(color(&self.palette, BACKGROUND)))?;
+            for cell in row.iter().take(width as usize) {
+                if current != Some(cell.color) {
+                    queue!(stdout, SetForegroundColor(color(&self.palette, cell.color)))?;
+                    current = Some(cell.color);
+                }
+                queue!(stdout, Print(cell.glyph))?;
+            }
+            queue!(stdout, ResetColor, terminal::Clear(terminal::ClearType::UntilNewLine), Print("\r\n"))?;
+        }
+        queue!(
+            stdout,
+            Print(&frame.caption), terminal::Clear(terminal::ClearType::UntilNewLine), Print("\r\n"),
+            Print(status), terminal::Clear(terminal::ClearType::FromCursorDown),
+        )?;
+        stdout.flush()
+    }
+
+    /// Plays the frames until the user quits. The last frame stays on screen until then.
+    pub fn play(&self, visualization: &dyn Visualize) -> io::Result<()> {
+        let mut frames = visualization.frames();
+        let mut frame = match frames.next() {
+            Some(frame) => frame,
+            None => return Ok(()),
+        };
+
+        let mut guard = TerminalGuard::new()?;
+        queue!(guard.stdout, terminal::Clear(terminal::ClearType::All))?;
+
+        let mut fps = self.fps;
+        let mut index = 0;
+        let mut paused = false;
+        let mut ended = false;
+        let mut next_frame = Instant::now();
+
+        loop {
+            let state = if ended { "END" } else if paused { "PAUSED" } else { "" };
+            let status = format!(
+                "Frame {} at {} fps {}  SPACE: pause, RIGHT: step, +/-: speed, Q: quit", index, fps, state
+            );
+            self.render(&mut guard.stdout, &frame, &status)?;
+
+            let timeout = if paused || ended {
+                Duration::from_secs(1)
+            }
+            else {
+                next_frame.saturating_duration_since(Instant::now())
+            };
+
+            let mut step = false;
+            if event::poll(timeout)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Release {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char(' ') => paused = !paused,
+                        KeyCode::Right | KeyCode::Char('.') => {
+                            paused = true;
+                            step = true;
+                        },
+                        KeyCode::Char('+') => fps = (fps * 2).min(MAX_FPS),
+                        KeyCode::Char('-') => fps = (fps / 2).max(1),
+                        KeyCode::Esc | KeyCode::Char('q') => break,
+                        _ => {},
+                    }
+                }
+            }
+
+            let now = Instant::now();
+            if !ended && (step || (!paused && now >= next_frame)) {
+                match frames.next() {
+                    Some(next) => {
+                        frame = next;
+                        index += 1;
+                    },
+                    None => ended = true,
+                }
+                next_frame = now + Duration::from_secs(1) / fps;
+            }
+        }
+
+        Ok(())
+    }
+}
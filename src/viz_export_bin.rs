@@ -0,0 +1,58 @@
+extern crate aoc_2019;
+
+use std::env;
+use std::fs::read_to_string;
+use std::process;
+
+use aoc_2019::{inputs, viz, y2019};
+use aoc_2019::viz::Palette;
+use aoc_2019::viz::export::Exporter;
+
+
+fn usage() -> ! {
+    eprintln!("Usage: viz_export <day> <output.gif | directory> [--fps <n>] [--cell-size <px>] [--palette <#rrggbb,...>]");
+    eprintln!("       Options of play: 11 [--white], 12 [--steps <n>], 13 [--level <path>]");
+    process::exit(1);
+}
+
+fn arg(name: &str) -> Option<String> {
+    env::args()
+        .skip_while(|arg| arg != name)
+        .nth(1)
+}
+
+fn number(name: &str, default: usize) -> usize {
+    arg(name).map_or(default, |n| n.parse().unwrap_or_else(|_| usage()))
+}
+
+pub fn main() {
+    aoc_2019::util::init();
+    let day: u32 = env::args().nth(1).and_then(|day| day.parse().ok()).unwrap_or_else(|| usage());
+    let output = env::args().nth(2).filter(|output| !output.starts_with("--")).unwrap_or_else(|| usage());
+    let input = inputs::load(y2019::YEAR, day).expect("Failed to load input");
+
+    let options = viz::Options {
+        white: env::args().any(|arg| arg == "--white"),
+        steps: arg("--steps").map(|n| n.parse().unwrap_or_else(|_| usage())),
+        level: arg("--level").map(|path| read_to_string(path).unwrap().parse().expect("Failed to parse level")),
+    };
+    let visualization = viz::for_day(day, &input, &options)
+        .expect("Visualization failed")
+        .unwrap_or_else(|| usage());
+
+    let mut exporter = Exporter::new(number("--cell-size", 8) as u32);
+    if let Some(palette) = arg("--palette") {
+        exporter = exporter.with_palette(palette.parse::<Palette>().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            usage()
+        }));
+    }
+
+    let frames = if output.ends_with(".gif") {
+        exporter.export_gif(visualization.as_ref(), number("--fps", 20) as u32, &output)
+    }
+    else {
+        exporter.export_png_sequence(visualization.as_ref(), &output)
+    };
+    println!("Wrote {} frames to {}", frames.expect("Export failed"), output);
+}
@@ -0,0 +1,145 @@
+//! Bindings for the browser frontend in `web/`. The frontend steps the simulations and draws their state to a canvas,
+//! so everything here just hands out plain numbers.
+
+use std::convert::TryInto;
+
+use wasm_bindgen::prelude::*;
+
+use crate::y2019::day11::{Hull, Robot, Color, StartState};
+use crate::y2019::day13::{Arcade, Error as ArcadeError, JoystickPosition};
+use crate::intcode::{Program, Error as IntcodeError};
+
+
+fn js_error<E: ToString>(e: E) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
+
+fn parse_program(program: &str) -> Result<Program, JsValue> {
+    program.trim().parse().map_err(js_error)
+}
+
+/// The painting robot of day 11.
+#[wasm_bindgen]
+pub struct PaintingRobot {
+    robot: Robot,
+    hull: Hull,
+    done: bool,
+}
+
+#[wasm_bindgen]
+impl PaintingRobot {
+    /// Starts on a black panel, or on a white one for part 2.
+    #[wasm_bindgen(constructor)]
+    pub fn new(program: &str, start_on_white: bool) -> Result<PaintingRobot, JsValue> {
+        let start = StartState {
+            panel_color: if start_on_white { Some(Color::White) } else { None },
+            .. StartState::default()
+        };
+        Ok(Self {
+            robot: Robot::with_start(parse_program(program)?, &start),
+            hull: start.hull(),
+            done: false,
+        })
+    }
+
+    /// Paints one panel. Returns `false` once the robot is done.
+    pub fn step(&mut self) -> Result<bool, JsValue> {
+        if !self.done {
+            self.done = self.robot.step(&mut self.hull).map_err(js_error)?.is_none();
+        }
+        Ok(!self.done)
+    }
+
+    pub fn x(&self) -> i32 {
+        self.robot.position().x() as i32
+    }
+
+    pub fn y(&self) -> i32 {
+        self.robot.position().y() as i32
+    }
+
+    /// Painted panels as `[x, y, color, ...]`, with 0 for black and 1 for white.
+    pub fn panels(&self) -> Vec<i32> {
+        self.hull.panels()
+            .flat_map(|(position, color)| vec![position.x() as i32, position.y() as i32, i64::from(color) as i32])
+            .collect()
+    }
+
+    pub fn num_painted(&self) -> usize {
+        self.hull.num_painted()
+    }
+}
+
+/// The breakout game of day 13.
+#[wasm_bindgen]
+pub struct BreakoutGame {
+    arcade: Arcade,
+    autopilot: bool,
+    game_over: bool,
+}
+
+#[wasm_bindgen]
+impl BreakoutGame {
+    #[wasm_bindgen(constructor)]
+    pub fn new(program: &str) -> Result<BreakoutGame, JsValue> {
+        let mut arcade = Arcade::new(parse_program(program)?);
+        arcade.load_screen().map_err(js_error)?;
+        Ok(Self {
+            arcade,
+            autopilot: false,
+            game_over: false,
+        })
+    }
+
+    pub fn set_autopilot(&mut self, autopilot: bool) {
+        self.autopilot = autopilot;
+    }
+
+    /// Runs one frame with the joystick at -1 (left), 0 or 1 (right). Returns `false` once the game is over.
+    pub fn frame(&mut self, joystick: i32) -> Result<bool, JsValue> {
+        if self.game_over {
+            return Ok(false);
+        }
+
+        if self.autopilot {
+            self.arcade.autopilot().map_err(js_error)?;
+        }
+        else {
+            let joystick: JoystickPosition = i64::from(joystick).try_into().map_err(js_error)?;
+            self.arcade.set_joystick(joystick);
+        }
+
+        match self.arcade.wait_frame() {
+            Err(ArcadeError::Intcode(IntcodeError::Halted)) => self.game_over = true,
+            result => { result.map_err(js_error)?; },
+        }
+        Ok(!self.game_over)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.arcade.screen.screen_size().map_or(0, |(width, _)| width as u32)
+    }
+
+    pub fn height(&self) -> u32 {
+        self.arcade.screen.screen_size().map_or(0, |(_, height)| height as u32)
+    }
+
+    /// The screen row by row, with the tile ids of the Intcode program (0 empty, 1 wall, 2 block, 3 paddle, 4 ball).
+    pub fn tiles(&self) -> Vec<u8> {
+        let (width, height) = (self.width() as i64, self.height() as i64);
+        (0 .. height)
+            .flat_map(|y| (0 .. width).map(move |x| (x, y)))
+            .map(|position| {
+                i64::from(self.arcade.screen.framebuffer.get(&position).copied().unwrap_or_default()) as u8
+            })
+            .collect()
+    }
+
+    pub fn score(&self) -> f64 {
+        self.arcade.screen.score as f64
+    }
+
+    pub fn blocks(&self) -> usize {
+        self.arcade.screen.num_blocks
+    }
+}
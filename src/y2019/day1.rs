@@ -0,0 +1,115 @@
+use std::io::BufRead;
+
+use aoc_runner_derive::{aoc, aoc_generator};
+
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::{Error, ParseError};
+
+
+fn fuel_required(mass: u64) -> u64 {
+    (mass / 3).saturating_sub(2)
+}
+
+fn with_extra_fuel(fuel: u64) -> u64 {
+    match fuel {
+        0 => 0,
+        fuel => fuel + with_extra_fuel(fuel_required(fuel))
+    }
+}
+
+
+#[aoc_generator(day1)]
+pub fn input_generator(input: &str) -> Result<Vec<u64>, Error> {
+    let input = util::normalize_input(input);
+    input.lines()
+        .map(|line| line.parse::<u64>().map_err(|e| ParseError::at(1, &input, line, e).into()))
+        .collect()
+}
+
+/// Like `input_generator`, but reads line by line.
+pub fn from_reader<R: BufRead>(reader: R) -> Result<Vec<u64>, Error> {
+    reader.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let line = line?;
+            line.parse::<u64>().map_err(|e| ParseError::at(1, &line, &line, e).below(i).into())
+        })
+        .collect()
+}
+
+#[aoc(day1, part1)]
+pub fn solve_part1(input: &[u64]) -> u64 {
+    input.iter()
+        .map(|mass| fuel_required(*mass))
+        .sum()
+}
+
+#[aoc(day1, part2)]
+pub fn solve_part2(input: &[u64]) -> u64 {
+    input.iter()
+        .map(|mass| with_extra_fuel(fuel_required(*mass)))
+        .sum()
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<u64, Error> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<u64, Error> {
+    Ok(solve_part2(&input_generator(input)?))
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<u64>;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 1;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuel_for_modules() {
+        assert_eq!(fuel_required(12), 2);
+        assert_eq!(fuel_required(14), 2);
+        assert_eq!(fuel_required(1969), 654);
+        assert_eq!(fuel_required(100756), 33583);
+    }
+
+    #[test]
+    fn fuel_for_fuel() {
+        assert_eq!(solve_part2(&[14]), 2);
+        assert_eq!(solve_part2(&[1969]), 966);
+        assert_eq!(solve_part2(&[100756]), 50346);
+    }
+
+    #[test]
+    fn sums_modules() {
+        let input = input_generator("12\n14\n1969\n100756").unwrap();
+        assert_eq!(solve_part1(&input), 2 + 2 + 654 + 33583);
+    }
+
+    #[test]
+    fn reads_windows_inputs() {
+        assert_eq!(input_generator("\u{feff}12\r\n14\r\n").unwrap(), vec![12, 14]);
+    }
+}
@@ -0,0 +1,426 @@
+use std::str::FromStr;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hasher, Hash};
+use std::cmp::Ordering;
+
+use aoc_runner_derive::{aoc, aoc_generator};
+use failure::Fail;
+use itertools::Itertools;
+
+use crate::math;
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::Error;
+
+
+#[derive(Clone, Debug, Fail)]
+pub enum ParseError {
+    #[fail(display = "Empty map")]
+    Empty,
+    #[fail(display = "Invalid line: {}", _0)]
+    InvalidLine(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct Ray {
+    x0: i64,
+    y0: i64,
+    dx: i64,
+    dy: i64,
+}
+
+impl Ray {
+    pub fn new(from: &Asteroid, to: &Asteroid) -> Self {
+        let mut dx = to.x - from.x;
+        let mut dy = to.y - from.y;
+
+        assert!(dx != 0 || dy != 0);
+
+        if dx == 0 {
+            dy = dy.signum();
+        }
+        else if dy == 0 {
+            dx = dx.signum();
+        }
+        else {
+            let k = math::gcd(dx.abs(), dy.abs());
+            dx /= k;
+            dy /= k;
+        }
+
+        Ray {
+            x0: from.x,
+            y0: from.y,
+            dx,
+            dy,
+        }
+    }
+
+    fn angle_quadrant(&self) -> u8 {
+        match (self.dx.signum(), self.dy.signum()) {
+            (0, -1) => 0,
+            (1, -1) => 1,
+            (1, 0) => 2,
+            (1, 1) => 3,
+            (0, 1) => 4,
+            (-1, 1) => 5,
+            (-1, 0) => 6,
+            (-1, -1) => 7,
+            _ => unreachable!()
+        }
+    }
+}
+
+impl PartialEq for Ray {
+    fn eq(&self, other: &Ray) -> bool {
+        // Check that both rays have the same origin
+        if self.x0 != other.x0 || self.y0 != other.y0 {
+            return false;
+        }
+
+        // Check that both rays have the same direction. Direction is normalized.
+        if self.dx != other.dx || self.dy != other.dy {
+            return false;
+        }
+
+        true
+    }
+}
+
+impl PartialOrd for Ray {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ray {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.angle_quadrant().cmp(&other.angle_quadrant()) {
+            Ordering::Equal => {
+                (other.dx * self.dy).cmp(&(self.dx * other.dy))
+            },
+            ordering => ordering,
+        }
+    }
+}
+
+impl Eq for Ray {}
+
+impl Hash for Ray {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dx.hash(state);
+        self.dy.hash(state);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Asteroid {
+    pub x: i64,
+    pub y: i64,
+}
+
+pub struct AsteroidMap {
+    asteroids: Vec<Asteroid>,
+}
+
+impl AsteroidMap {
+    pub fn asteroids(&self) -> &[Asteroid] {
+        &self.asteroids
+    }
+
+    pub fn get_visible_asteroids(&self, asteroid: &Asteroid) -> HashMap<Ray, Vec<&Asteroid>> {
+        let mut collisions = HashMap::new();
+
+        for asteroid2 in &self.asteroids {
+            if asteroid != asteroid2 {
+                let ray = Ray::new(asteroid, asteroid2);
+                collisions.entry(ray)
+                    .or_insert_with(|| Vec::with_capacity(1))
+                    .push(asteroid2)
+            }
+        }
+
+        for (_, asteroids) in &mut collisions {
+            asteroids.sort_by_key(|asteroid2| (asteroid2.x - asteroid.x).abs() + (asteroid2.y - asteroid.y).abs())
+        }
+
+        collisions
+    }
+
+    /// How many asteroids can be seen from `asteroid`, i.e. `get_visible_asteroids(asteroid).len()`, but with only the
+    /// rays, which go into `rays`. Reusing `rays` for every asteroid saves allocating a collision map for each.
+    pub fn count_visible(&self, asteroid: &Asteroid, rays: &mut Vec<Ray>) -> usize {
+        rays.clear();
+        rays.extend(self.asteroids.iter()
+            .filter(|asteroid2| *asteroid2 != asteroid)
+            .map(|asteroid2| Ray::new(asteroid, asteroid2)));
+        // Rays in the same direction compare as equal, so they end up next to each other.
+        rays.sort_unstable();
+        rays.iter().dedup().count()
+    }
+
+    pub fn get_kill_order<'a>(&self, collisions: HashMap<Ray, Vec<&'a Asteroid>>) -> Vec<&'a Asteroid> {
+        let mut collisions = collisions.into_iter()
+            .map(|(ray, asteroids)| (ray, VecDeque::from(asteroids)))
+            .collect::<HashMap<Ray, VecDeque<&'a Asteroid>>>();
+        let mut rays: Vec<Ray> = collisions.keys()
+            .into_iter()
+            .cloned()
+            .collect();
+        rays.sort_unstable();
+        let mut rays_cycle = rays.iter().cycle();
+        let mut kills = Vec::new();
+
+        while !collisions.is_empty() {
+            let ray = rays_cycle.next().unwrap();
+
+            if let Some(asteroids) = collisions.get_mut(ray) {
+                if let Some(asteroid) = asteroids.pop_front() {
+                    kills.push(asteroid);
+                }
+                else {
+                    collisions.remove(ray);
+                }
+            }
+        }
+
+        kills
+    }
+}
+
+impl FromStr for AsteroidMap {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s.lines().collect::<Vec<&str>>();
+        let height = lines.len();
+        if height == 0 {
+            return Err(ParseError::Empty)
+        }
+        let width = lines[0].len();
+        if width == 0 {
+            return Err(ParseError::Empty)
+        }
+
+        let mut asteroids = Vec::new();
+        for (y, line) in lines.into_iter().enumerate() {
+            if line.len() != width {
+                return Err(ParseError::InvalidLine(line.to_owned()))
+            }
+            for (x, c) in line.chars().enumerate() {
+                if c == '#' {
+                    asteroids.push(Asteroid {
+                        x: x as i64,
+                        y: y as i64,
+                    });
+                }
+            }
+        }
+
+        Ok(AsteroidMap {
+            asteroids
+        })
+    }
+}
+
+
+#[aoc_generator(day10)]
+pub fn input_generator(input: &str) -> Result<AsteroidMap, Error> {
+    util::init();
+    let input = util::normalize_input(input);
+    input.parse().map_err(|e| match e {
+        ParseError::InvalidLine(_) => {
+            let width = input.lines().next().map_or(0, str::len);
+            let line = input.lines().find(|line| line.len() != width).unwrap_or_default();
+            crate::ParseError::at(10, &input, line, "Lines have different lengths").into()
+        },
+        ParseError::Empty => crate::ParseError::at(10, &input, &input, e).into(),
+    })
+}
+
+/// Only the rays are collected for each candidate, in a `Vec` that is reused, and only the best location gets its
+/// collision map.
+fn get_best_asteroid(map: &AsteroidMap) -> Result<(&Asteroid, HashMap<Ray, Vec<&Asteroid>>), Error> {
+    let mut rays = Vec::with_capacity(map.asteroids.len());
+    let asteroid = map.asteroids.iter()
+        .max_by_key(|asteroid| map.count_visible(asteroid, &mut rays))
+        .ok_or(Error::NoSolution("Map has no asteroids"))?;
+    Ok((asteroid, map.get_visible_asteroids(asteroid)))
+}
+
+#[aoc(day10, part1)]
+pub fn solve_part1(map: &AsteroidMap) -> Result<usize, Error> {
+    let (asteroid, collisions) = get_best_asteroid(map)?;
+
+    debug!("Best location: {:?}", asteroid);
+    //debug!("Collisions: {:#?}", collisions);
+    debug!("Visible asteroids: {}", collisions.len());
+
+    Ok(collisions.len())
+}
+
+#[aoc(day10, part2)]
+pub fn solve_part2(map: &AsteroidMap) -> Result<i64, Error> {
+    let (laser_station, collisions) = get_best_asteroid(map)?;
+
+    let kills = map.get_kill_order(collisions);
+
+    for (i, kill) in kills.iter().enumerate() {
+        let dx = kill.x - laser_station.x;
+        let dy = kill.y - laser_station.y;
+        let a = (dx as f64).atan2(-dy as f64).to_degrees();
+        debug!("Kill #{}: {:?} - {},{} {}", i + 1, kill, dx, dy, a);
+    }
+
+    let asteroid = kills.get(199).ok_or(Error::NoSolution("Less than 200 asteroids are vaporized"))?;
+    debug!("200th asteroid: {:?}", asteroid);
+
+    Ok(asteroid.x * 100 + asteroid.y)
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<usize, Error> {
+    solve_part1(&input_generator(input)?)
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<i64, Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = AsteroidMap;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 10;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const SMALL: &str = ".#..#\n.....\n#####\n....#\n...##";
+
+    const LARGE: &str = "\
+.#..##.###...#######
+##.############..##.
+.#.######.########.#
+.###.#######.####.#.
+#####.##.#.##.###.##
+..#####..#.#########
+####################
+#.####....###.#.#.##
+##.#################
+#####.##.###..####..
+..######..##.#######
+####.##.####...##..#
+.#####..#.######.###
+##...#.##########...
+#.##########.#######
+.####.#.###.###.#.##
+....##.##.###..#####
+.#.#.###########.###
+#.#.#.#####.####.###
+###.##.####.##.#..##";
+
+    #[test]
+    fn best_location() {
+        let map = input_generator(SMALL).unwrap();
+        let (asteroid, collisions) = get_best_asteroid(&map).unwrap();
+        assert_eq!((asteroid.x, asteroid.y), (3, 4));
+        assert_eq!(collisions.len(), 8);
+
+        let map = input_generator(LARGE).unwrap();
+        let (asteroid, _) = get_best_asteroid(&map).unwrap();
+        assert_eq!((asteroid.x, asteroid.y), (11, 13));
+        assert_eq!(solve_part1(&map).unwrap(), 210);
+    }
+
+    #[test]
+    fn vaporization_order() {
+        let map = input_generator(LARGE).unwrap();
+        let (_, collisions) = get_best_asteroid(&map).unwrap();
+        let kills = map.get_kill_order(collisions);
+        let position = |i: usize| (kills[i - 1].x, kills[i - 1].y);
+        assert_eq!(position(1), (11, 12));
+        assert_eq!(position(2), (12, 1));
+        assert_eq!(position(3), (12, 2));
+        assert_eq!(position(10), (12, 8));
+        assert_eq!(position(20), (16, 0));
+        assert_eq!(position(50), (16, 9));
+        assert_eq!(position(100), (10, 16));
+        assert_eq!(position(199), (9, 6));
+        assert_eq!(position(200), (8, 2));
+        assert_eq!(position(201), (10, 9));
+        assert_eq!(position(299), (11, 1));
+        assert_eq!(solve_part2(&map).unwrap(), 802);
+    }
+
+    #[test]
+    fn counts_the_same_as_the_collision_map() {
+        let map = input_generator(LARGE).unwrap();
+        let mut rays = Vec::new();
+        for asteroid in &map.asteroids {
+            assert_eq!(map.count_visible(asteroid, &mut rays), map.get_visible_asteroids(asteroid).len());
+        }
+    }
+
+    #[test]
+    fn not_enough_asteroids() {
+        let map = input_generator(SMALL).unwrap();
+        assert!(solve_part2(&map).is_err());
+    }
+
+    fn asteroid() -> impl Strategy<Value = Asteroid> {
+        (-20i64 .. 20, -20i64 .. 20).prop_map(|(x, y)| Asteroid { x, y })
+    }
+
+    fn ray() -> impl Strategy<Value = Ray> {
+        (asteroid(), asteroid())
+            .prop_filter("A ray needs two asteroids", |(from, to)| from != to)
+            .prop_map(|(from, to)| Ray::new(&from, &to))
+    }
+
+    /// The clockwise angle from up, with y pointing down.
+    fn angle(ray: &Ray) -> f64 {
+        let angle = (ray.dx as f64).atan2(-ray.dy as f64);
+        if angle < 0. { angle + 2. * PI } else { angle }
+    }
+
+    proptest! {
+        #[test]
+        fn ray_order_is_antisymmetric(a in ray(), b in ray()) {
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        }
+
+        #[test]
+        fn ray_order_is_transitive(a in ray(), b in ray(), c in ray()) {
+            if a <= b && b <= c {
+                prop_assert!(a <= c);
+            }
+        }
+
+        #[test]
+        fn ray_order_matches_atan2(a in ray(), b in ray()) {
+            prop_assert_eq!(Some(a.cmp(&b)), angle(&a).partial_cmp(&angle(&b)));
+        }
+    }
+}
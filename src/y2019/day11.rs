@@ -0,0 +1,740 @@
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+#[cfg(feature="image")]
+use std::path::Path;
+
+use aoc_runner_derive::{aoc, aoc_generator};
+use failure::Fail;
+use itertools::Itertools;
+use core::fmt::Write;
+
+use crate::grid::Grid;
+use crate::intcode::{Program, Machine, Error as IntcodeError};
+use crate::ocr;
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::viz::{self, Cell, Frame, Visualize};
+
+
+#[derive(Clone, Debug, Fail)]
+pub enum Error {
+    #[fail(display = "Intcode error: {}", _0)]
+    Intcode(#[cause] IntcodeError),
+    #[fail(display = "Invalid color value: {}", _0)]
+    InvalidColor(i64),
+    #[fail(display = "Invalid direction value: {}", _0)]
+    InvalidDirection(i64),
+    #[fail(display = "Incomplete instruction")]
+    IncompleteInstruction,
+}
+
+impl From<IntcodeError> for Error {
+    fn from(e: IntcodeError) -> Self {
+        Self::Intcode(e)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    White
+}
+
+impl TryFrom<i64> for Color {
+    type Error = Error;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Black),
+            1 => Ok(Self::White),
+            _ => Err(Error::InvalidColor(value))
+        }
+    }
+}
+
+impl From<Color> for i64 {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => 0,
+            Color::White => 1,
+        }
+    }
+}
+
+impl From<Color> for char {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::White => '█',
+            Color::Black => ' ',
+        }
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Color::Black
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RelativeDirection {
+    Left,
+    Right,
+}
+
+impl TryFrom<i64> for RelativeDirection {
+    type Error = Error;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Left),
+            1 => Ok(Self::Right),
+            _ => Err(Error::InvalidDirection(value))
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Instruction {
+    color: Color,
+    direction: RelativeDirection,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    x: i64,
+    y: i64,
+}
+
+impl Position {
+    pub fn new(x: i64, y: i64) -> Self {
+        Self {
+            x,
+            y
+        }
+    }
+
+    pub fn x(&self) -> i64 {
+        self.x
+    }
+
+    pub fn y(&self) -> i64 {
+        self.y
+    }
+
+    pub fn go(&mut self, direction: &AbsoluteDirection) {
+        match direction {
+            AbsoluteDirection::North => self.y -= 1,
+            AbsoluteDirection::East => self.x += 1,
+            AbsoluteDirection::South => self.y += 1,
+            AbsoluteDirection::West => self.x -= 1,
+        }
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position {
+            x: 0,
+            y: 0,
+        }
+    }
+}
+
+impl Ord for Position {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.y.cmp(&other.y)
+            .then_with(|| self.x.cmp(&other.x))
+    }
+}
+
+impl PartialOrd for Position {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AbsoluteDirection {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl AbsoluteDirection {
+    pub fn turned(&self, by: &RelativeDirection) -> AbsoluteDirection {
+        match by {
+            RelativeDirection::Left => {
+                match self {
+                    Self::North => Self::West,
+                    Self::East => Self::North,
+                    Self::South => Self::East,
+                    Self::West => Self::South,
+                }
+            },
+            RelativeDirection::Right => {
+                match self {
+                    Self::North => Self::East,
+                    Self::East => Self::South,
+                    Self::South => Self::West,
+                    Self::West => Self::North,
+                }
+            }
+        }
+    }
+
+    pub fn turn(&mut self, by: &RelativeDirection) {
+        *self = self.turned(by);
+    }
+}
+
+impl Default for AbsoluteDirection {
+    fn default() -> Self {
+        AbsoluteDirection::North
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Hull {
+    painted: HashMap<Position, Color>,
+}
+
+impl Hull {
+    pub fn paint(&mut self, position: &Position, color: Color) {
+        self.painted.insert(position.clone(), color);
+    }
+
+    pub fn get_color(&self, position: &Position) -> Color {
+        self.painted.get(position)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn num_painted(&self) -> usize {
+        self.painted.len()
+    }
+
+    pub fn panels(&self) -> impl Iterator<Item=(&Position, Color)> + '_ {
+        self.painted.iter().map(|(position, color)| (position, *color))
+    }
+
+    /// Returns the top-left and bottom-right corner of the painted area.
+    pub fn bounding_box(&self) -> Option<(Position, Position)> {
+        let (min_x, max_x) = self.painted.keys().map(|position| position.x).minmax().into_option()?;
+        let (min_y, max_y) = self.painted.keys().map(|position| position.y).minmax().into_option()?;
+        Some((Position::new(min_x, min_y), Position::new(max_x, max_y)))
+    }
+
+    /// Returns the painted area as `(width, height, colors)` with colors in row-major order.
+    pub fn to_grid(&self) -> (usize, usize, Vec<Color>) {
+        let (min, max) = match self.bounding_box() {
+            Some(bounding_box) => bounding_box,
+            None => return (0, 0, Vec::new()),
+        };
+
+        let width = (max.x - min.x + 1) as usize;
+        let height = (max.y - min.y + 1) as usize;
+        let grid = (min.y ..= max.y)
+            .cartesian_product(min.x ..= max.x)
+            .map(|(y, x)| self.get_color(&Position::new(x, y)))
+            .collect();
+
+        (width, height, grid)
+    }
+
+    pub fn read_letters(&self) -> Option<String> {
+        let (width, height, grid) = self.to_grid();
+        let is_white = |x: usize, y: usize| grid[y * width + x] == Color::White;
+
+        // Black panels painted around the letters are not part of the identifier.
+        let (min_x, max_x) = (0 .. width)
+            .filter(|&x| (0 .. height).any(|y| is_white(x, y)))
+            .minmax()
+            .into_option()?;
+        let (min_y, max_y) = (0 .. height)
+            .filter(|&y| (0 .. width).any(|x| is_white(x, y)))
+            .minmax()
+            .into_option()?;
+
+        // The letters sit on a grid of `LETTER_WIDTH + LETTER_SPACING` columns, but some, like 'I' and 'J', don't start
+        // in their first column. So the grid may start a few columns left of the first white one.
+        // Columns left of `min_x` are black anyway.
+        (0 .. ocr::LETTER_WIDTH)
+            .filter_map(|shift| {
+                ocr::read_letters(max_x - min_x + 1 + shift, max_y - min_y + 1, |x, y| {
+                    x >= shift && is_white(min_x + x - shift, min_y + y)
+                })
+            })
+            .find(|letters| !letters.contains('?'))
+    }
+
+    #[cfg(feature="image")]
+    pub fn write_png<P: AsRef<Path>>(&self, path: P) -> Result<(), image::ImageError> {
+        let (width, height, grid) = self.to_grid();
+
+        let image = image::GrayImage::from_fn(width as u32, height as u32, |x, y| {
+            match grid[y as usize * width + x as usize] {
+                Color::White => image::Luma([0xff]),
+                Color::Black => image::Luma([0x00]),
+            }
+        });
+
+        image.save_with_format(path, image::ImageFormat::Png)
+    }
+}
+
+impl fmt::Display for Hull {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let (width, _, grid) = self.to_grid();
+        if width > 0 {
+            for row in grid.chunks(width) {
+                for color in row {
+                    f.write_char((*color).into())?;
+                }
+                f.write_char('\n')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Grid for Hull {
+    fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        let (min, max) = self.bounding_box()?;
+        Some(((min.x, min.y), (max.x, max.y)))
+    }
+
+    fn glyph(&self, x: i64, y: i64) -> char {
+        self.painted.get(&Position::new(x, y)).map_or(' ', |&color| color.into())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StartState {
+    pub position: Position,
+    pub direction: AbsoluteDirection,
+    /// Color of the panel the robot starts on. `None` leaves it unpainted.
+    pub panel_color: Option<Color>,
+}
+
+impl StartState {
+    pub fn hull(&self) -> Hull {
+        let mut hull = Hull::default();
+        if let Some(color) = self.panel_color {
+            hull.paint(&self.position, color);
+        }
+        hull
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Robot {
+    machine: Machine,
+    direction: AbsoluteDirection,
+    position: Position,
+}
+
+impl Robot {
+    pub fn new(program: Program) -> Self {
+        Self::with_start(program, &StartState::default())
+    }
+
+    pub fn with_start(program: Program, start: &StartState) -> Self {
+        Self {
+            machine: Machine::new(program),
+            direction: start.direction,
+            position: start.position.clone(),
+        }
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn direction(&self) -> AbsoluteDirection {
+        self.direction
+    }
+
+    fn next_instruction(&mut self, color: Color) -> Result<Option<Instruction>, Error> {
+        self.machine.push_input(i64::from(color));
+
+        // The program halting before it produced an instruction means it's done painting.
+        let output1 = match self.machine.next_output() {
+            Ok(Some(output)) => output,
+            Ok(None) | Err(IntcodeError::Halted) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let output2 = match self.machine.next_output() {
+            Ok(Some(output)) => output,
+            Ok(None) | Err(IntcodeError::Halted) => return Err(Error::IncompleteInstruction),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Some(Instruction {
+            color: output1.try_into()?,
+            direction: output2.try_into()?,
+        }))
+    }
+
+    pub fn step(&mut self, hull: &mut Hull) -> Result<Option<PaintEvent>, Error> {
+        let instruction = match self.next_instruction(hull.get_color(&self.position))? {
+            Some(instruction) => instruction,
+            None => return Ok(None),
+        };
+
+        trace!("Position: {:?}", self.position);
+        trace!("Instruction: {:?}", instruction);
+
+        let event = PaintEvent {
+            position: self.position.clone(),
+            color: instruction.color,
+            direction: self.direction.turned(&instruction.direction),
+        };
+
+        hull.paint(&self.position, instruction.color);
+        self.direction = event.direction;
+        self.position.go(&self.direction);
+
+        Ok(Some(event))
+    }
+
+    pub fn steps<'r, 'h>(&'r mut self, hull: &'h mut Hull) -> Steps<'r, 'h> {
+        Steps {
+            robot: self,
+            hull,
+            done: false,
+        }
+    }
+
+    pub fn paint_hull(&mut self, hull: &mut Hull) -> Result<(), Error> {
+        self.paint_hull_observed(hull, |_| {})
+    }
+
+    /// Paints the hull and calls `observer` for every panel painted.
+    pub fn paint_hull_observed<F: FnMut(&PaintEvent)>(&mut self, hull: &mut Hull, mut observer: F) -> Result<(), Error> {
+        for event in self.steps(hull) {
+            observer(&event?);
+        }
+
+        Ok(())
+    }
+}
+
+/// A panel painted by the robot and the direction the robot turned to afterwards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaintEvent {
+    pub position: Position,
+    pub color: Color,
+    pub direction: AbsoluteDirection,
+}
+
+pub struct Steps<'r, 'h> {
+    robot: &'r mut Robot,
+    hull: &'h mut Hull,
+    done: bool,
+}
+
+impl<'r, 'h> Iterator for Steps<'r, 'h> {
+    type Item = Result<PaintEvent, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.robot.step(self.hull) {
+            Ok(Some(event)) => Some(Ok(event)),
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PaintStats {
+    pub paints: usize,
+    pub white: usize,
+    pub black: usize,
+    panels: HashSet<Position>,
+}
+
+impl PaintStats {
+    fn record(&mut self, event: &PaintEvent) {
+        self.paints += 1;
+        match event.color {
+            Color::White => self.white += 1,
+            Color::Black => self.black += 1,
+        }
+        self.panels.insert(event.position.clone());
+    }
+
+    /// Number of distinct panels this robot painted, including ones painted over by others later.
+    pub fn num_panels(&self) -> usize {
+        self.panels.len()
+    }
+}
+
+/// Multiple robots painting the same hull. Robots take turns painting one panel each, in the order they were added.
+/// If robots paint the same panel, the last write wins.
+#[derive(Clone, Debug, Default)]
+pub struct Fleet {
+    robots: Vec<Robot>,
+    stats: Vec<PaintStats>,
+    done: Vec<bool>,
+}
+
+impl Fleet {
+    pub fn add_robot(&mut self, robot: Robot) -> usize {
+        self.robots.push(robot);
+        self.stats.push(PaintStats::default());
+        self.done.push(false);
+        self.robots.len() - 1
+    }
+
+    pub fn robot(&self, index: usize) -> Option<&Robot> {
+        self.robots.get(index)
+    }
+
+    pub fn stats(&self) -> &[PaintStats] {
+        &self.stats
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done.iter().all(|done| *done)
+    }
+
+    /// Lets every robot that's still running paint one panel. Returns the events with the index of the robot.
+    pub fn step(&mut self, hull: &mut Hull) -> Result<Vec<(usize, PaintEvent)>, Error> {
+        let mut events = Vec::new();
+
+        for (i, robot) in self.robots.iter_mut().enumerate() {
+            if self.done[i] {
+                continue;
+            }
+
+            match robot.step(hull)? {
+                Some(event) => {
+                    self.stats[i].record(&event);
+                    events.push((i, event));
+                },
+                None => {
+                    debug!("Robot #{} is done", i);
+                    self.done[i] = true;
+                },
+            }
+        }
+
+        Ok(events)
+    }
+
+    pub fn paint_hull(&mut self, hull: &mut Hull) -> Result<(), Error> {
+        while !self.is_done() {
+            self.step(hull)?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Everything a robot painted, in order, to replay it.
+#[derive(Clone, Debug)]
+pub struct HullHistory {
+    start: StartState,
+    events: Vec<PaintEvent>,
+}
+
+impl HullHistory {
+    pub fn record(program: Program, start: &StartState) -> Result<Self, Error> {
+        let mut events = Vec::new();
+        Robot::with_start(program, start).paint_hull_observed(&mut start.hull(), |event| events.push(event.clone()))?;
+        Ok(Self {
+            start: start.clone(),
+            events,
+        })
+    }
+
+    pub fn events(&self) -> &[PaintEvent] {
+        &self.events
+    }
+
+    /// Where the robot is after each event, starting with where it starts.
+    fn robot_positions(&self) -> impl Iterator<Item = (Position, AbsoluteDirection)> + '_ {
+        let start = (self.start.position.clone(), self.start.direction);
+        std::iter::once(start).chain(self.events.iter().map(|event| {
+            let mut position = event.position.clone();
+            position.go(&event.direction);
+            (position, event.direction)
+        }))
+    }
+}
+
+/// One frame per painted panel, with the robot in red.
+impl Visualize for HullHistory {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame>> {
+        // All frames are as large as the area the robot ever visits.
+        let (min_x, max_x) = self.robot_positions().map(|(position, _)| position.x).minmax().into_option().unwrap();
+        let (min_y, max_y) = self.robot_positions().map(|(position, _)| position.y).minmax().into_option().unwrap();
+        let (width, height) = ((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize);
+
+        let mut hull = self.start.hull();
+        let robots = self.robot_positions().collect_vec();
+        let events = std::iter::once(None).chain(self.events.clone().into_iter().map(Some));
+
+        Box::new(events.zip(robots).map(move |(event, (robot, direction))| {
+            if let Some(event) = event {
+                hull.paint(&event.position, event.color);
+            }
+
+            let mut frame = Frame::new(width, height);
+            for (position, color) in hull.panels() {
+                let shade = if color == Color::White { viz::BRIGHT } else { viz::DIM };
+                frame.set((position.x - min_x) as usize, (position.y - min_y) as usize, Cell::new(color.into(), shade));
+            }
+            let glyph = match direction {
+                AbsoluteDirection::North => '^',
+                AbsoluteDirection::East => '>',
+                AbsoluteDirection::South => 'v',
+                AbsoluteDirection::West => '<',
+            };
+            frame.set((robot.x - min_x) as usize, (robot.y - min_y) as usize, Cell::new(glyph, viz::HIGHLIGHT));
+            frame.caption = format!("Panels painted: {}", hull.num_painted());
+            frame
+        }))
+    }
+}
+
+
+#[aoc_generator(day11)]
+pub fn input_generator(input: &str) -> Result<Program, crate::Error> {
+    util::init();
+    let input = util::normalize_input(input);
+    Program::parse_input(11, &input)
+}
+
+#[aoc(day11, part1)]
+pub fn solve_part1(program: &Program) -> Result<usize, crate::Error> {
+    let mut hull = Hull::default();
+    let mut robot = Robot::new(program.clone());
+
+    robot.paint_hull(&mut hull)?;
+
+    Ok(hull.num_painted())
+}
+
+#[aoc(day11, part2)]
+pub fn solve_part2(program: &Program) -> Result<String, crate::Error> {
+    let start = StartState {
+        panel_color: Some(Color::White),
+        .. StartState::default()
+    };
+    let mut hull = start.hull();
+    let mut robot = Robot::with_start(program.clone(), &start);
+
+    robot.paint_hull(&mut hull)?;
+
+    debug!("Hull:\n{}", hull);
+
+    hull.read_letters().ok_or(crate::Error::NoSolution("Registration identifier unreadable"))
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<usize, crate::Error> {
+    solve_part1(&input_generator(input)?)
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<String, crate::Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Program;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 11;
+
+    fn parse(input: &str) -> Result<Self::Input, crate::Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, crate::Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, crate::Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Paints and turns like the robot in the puzzle text, without looking at the camera.
+    const EXAMPLE: &str = "104,1,104,0,104,0,104,0,104,1,104,0,104,1,104,0,104,0,104,1,104,1,104,0,104,1,104,0,99";
+
+    #[test]
+    fn paints_example() {
+        let program = input_generator(EXAMPLE).unwrap();
+        assert_eq!(solve_part1(&program).unwrap(), 6);
+
+        let mut hull = Hull::default();
+        let mut robot = Robot::new(program);
+        robot.paint_hull(&mut hull).unwrap();
+        assert_eq!(hull.get_color(&Position::new(0, 0)), Color::Black);
+        assert_eq!(hull.get_color(&Position::new(-1, 0)), Color::Black);
+        assert_eq!(hull.get_color(&Position::new(0, 1)), Color::White);
+        assert_eq!(hull.get_color(&Position::new(1, 0)), Color::White);
+    }
+
+    /// A hull with `glyphs` painted next to each other, the first one at x = 0.
+    fn painted(glyphs: &[[&str; ocr::LETTER_HEIGHT]]) -> Hull {
+        let mut hull = Hull::default();
+        for (i, glyph) in glyphs.iter().enumerate() {
+            for (y, row) in glyph.iter().enumerate() {
+                for (x, c) in row.chars().enumerate() {
+                    let x = (i * (ocr::LETTER_WIDTH + ocr::LETTER_SPACING) + x) as i64;
+                    hull.paint(&Position::new(x, y as i64), if c == '#' { Color::White } else { Color::Black });
+                }
+            }
+        }
+        hull
+    }
+
+    const I: [&str; ocr::LETTER_HEIGHT] = [".###", "..#.", "..#.", "..#.", "..#.", ".###"];
+    const J: [&str; ocr::LETTER_HEIGHT] = ["..##", "...#", "...#", "...#", "#..#", ".##."];
+    const E: [&str; ocr::LETTER_HEIGHT] = ["####", "#...", "###.", "#...", "#...", "####"];
+    const Z: [&str; ocr::LETTER_HEIGHT] = ["####", "...#", "..#.", ".#..", "#...", "####"];
+    const Q: [&str; ocr::LETTER_HEIGHT] = ["####", "####", "####", "####", "####", "####"];
+
+    #[test]
+    fn reads_letters_aligned_to_the_grid() {
+        assert_eq!(painted(&[E, Z]).read_letters().as_deref(), Some("EZ"));
+        assert_eq!(painted(&[I, J]).read_letters().as_deref(), Some("IJ"));
+        assert_eq!(painted(&[J, E, I]).read_letters().as_deref(), Some("JEI"));
+        assert_eq!(painted(&[I, I, Z]).read_letters().as_deref(), Some("IIZ"));
+        assert_eq!(painted(&[E, Q]).read_letters(), None);
+    }
+
+    #[test]
+    fn replays_paint_history() {
+        let history = HullHistory::record(input_generator(EXAMPLE).unwrap(), &StartState::default()).unwrap();
+        let frames = history.frames().collect_vec();
+        assert_eq!(frames.len(), history.events().len() + 1);
+        assert!(frames.iter().all(|frame| (frame.width(), frame.height()) == (3, 3)));
+        assert_eq!(frames[0].get(1, 1), Some(&Cell::new('^', viz::HIGHLIGHT)));
+        assert_eq!(frames.last().unwrap().caption, "Panels painted: 6");
+    }
+}
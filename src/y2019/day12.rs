@@ -0,0 +1,876 @@
+use std::hash::{Hash, Hasher};
+use std::convert::TryInto;
+use std::thread;
+use std::cmp::Ordering;
+#[cfg(feature="recorder")]
+use std::io::Write;
+
+use regex::Regex;
+use num::{BigInt, Signed, Zero};
+use itertools::Itertools;
+use aoc_runner_derive::{aoc, aoc_generator};
+use failure::Fail;
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+use crate::config;
+use crate::math;
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::progress::{self, Reporter, Silent};
+use crate::viz::{self, Cell, Frame, Visualize};
+use crate::ParseError;
+
+
+#[derive(Clone, Debug, Fail)]
+pub enum Error {
+    #[fail(display = "Arithmetic overflow in step {}", _0)]
+    Overflow(usize),
+    #[fail(display = "The cycle is longer than 2^64 steps")]
+    CycleOverflow,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Body<const D: usize = 3> {
+    position: [i64; D],
+    velocity: [i64; D],
+}
+
+impl<const D: usize> Body<D> {
+    pub fn new(position: [i64; D]) -> Self {
+        Self {
+            position,
+            velocity: [0; D],
+        }
+    }
+
+    pub fn position(&self) -> &[i64; D] {
+        &self.position
+    }
+
+    pub fn velocity(&self) -> &[i64; D] {
+        &self.velocity
+    }
+
+    pub fn acceleration_towards(&self, other: &Self) -> [i64; D] {
+        let mut acceleration = [0; D];
+        for (a, (p, q)) in acceleration.iter_mut().zip(self.position.iter().zip(&other.position)) {
+            // Not `(q - p).signum()`, which overflows for bodies far apart.
+            *a = q.cmp(p) as i64;
+        }
+        acceleration
+    }
+
+    pub fn potential_energy(&self) -> i64 {
+        self.position.iter().map(|x| x.abs()).sum()
+    }
+
+    pub fn kinetic_energy(&self) -> i64 {
+        self.velocity.iter().map(|x| x.abs()).sum()
+    }
+
+    pub fn energy(&self) -> i64 {
+        self.potential_energy() * self.kinetic_energy()
+    }
+
+    pub fn checked_energy(&self) -> Option<i64> {
+        let checked_sum = |xs: &[i64; D]| xs.iter()
+            .try_fold(0i64, |sum, x| sum.checked_add(x.checked_abs()?));
+        checked_sum(&self.position)?.checked_mul(checked_sum(&self.velocity)?)
+    }
+}
+
+/// A body with arbitrary precision, for systems whose values don't fit into an `i64`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BigBody {
+    position: Vec<BigInt>,
+    velocity: Vec<BigInt>,
+}
+
+impl BigBody {
+    pub fn new(position: Vec<BigInt>) -> Self {
+        let velocity = vec![BigInt::zero(); position.len()];
+        Self {
+            position,
+            velocity,
+        }
+    }
+
+    pub fn position(&self) -> &[BigInt] {
+        &self.position
+    }
+
+    pub fn velocity(&self) -> &[BigInt] {
+        &self.velocity
+    }
+
+    pub fn energy(&self) -> BigInt {
+        let potential = self.position.iter().map(|x| x.abs()).sum::<BigInt>();
+        let kinetic = self.velocity.iter().map(|x| x.abs()).sum::<BigInt>();
+        potential * kinetic
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BigSystem {
+    bodies: Vec<BigBody>,
+    step: usize,
+}
+
+impl BigSystem {
+    pub fn add_body(&mut self, body: BigBody) {
+        self.bodies.push(body);
+    }
+
+    pub fn bodies(&self) -> &[BigBody] {
+        &self.bodies
+    }
+
+    pub fn step(&mut self) {
+        for (i, j) in (0 .. self.bodies.len()).tuple_combinations() {
+            for axis in 0 .. self.bodies[i].position.len() {
+                let acceleration = match self.bodies[j].position[axis].cmp(&self.bodies[i].position[axis]) {
+                    Ordering::Greater => 1,
+                    Ordering::Equal => 0,
+                    Ordering::Less => -1,
+                };
+                self.bodies[i].velocity[axis] += acceleration;
+                self.bodies[j].velocity[axis] -= acceleration;
+            }
+        }
+
+        for body in &mut self.bodies {
+            for (position, velocity) in body.position.iter_mut().zip(&body.velocity) {
+                *position += velocity;
+            }
+        }
+
+        self.step += 1;
+    }
+
+    pub fn energy(&self) -> BigInt {
+        self.bodies.iter()
+            .map(|body| body.energy())
+            .sum()
+    }
+
+    pub fn simulate(&mut self, steps: usize) -> BigInt {
+        for _ in 0 .. steps {
+            self.step();
+        }
+        self.energy()
+    }
+}
+
+impl<const D: usize> From<&System<D>> for BigSystem {
+    fn from(system: &System<D>) -> Self {
+        Self {
+            bodies: system.bodies.iter()
+                .map(|body| BigBody {
+                    position: body.position.iter().map(|x| BigInt::from(*x)).collect(),
+                    velocity: body.velocity.iter().map(|x| BigInt::from(*x)).collect(),
+                })
+                .collect(),
+            step: system.step,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DimensionalState {
+    positions: Vec<i64>,
+    velocities: Vec<i64>,
+    step: usize,
+}
+
+impl DimensionalState {
+    /// Advances the axis by one step. If a velocity or position would overflow, an error is returned and the state is
+    /// only partly updated.
+    pub fn step(&mut self) -> Result<(), Error> {
+        let n = self.positions.len();
+        let step = self.step;
+        let overflow = move || Error::Overflow(step);
+
+        for i in 0 .. n {
+            for j in i + 1 .. n {
+                let acceleration = self.positions[j].cmp(&self.positions[i]) as i64;
+                self.velocities[i] = self.velocities[i].checked_add(acceleration).ok_or_else(overflow)?;
+                self.velocities[j] = self.velocities[j].checked_sub(acceleration).ok_or_else(overflow)?;
+            }
+        }
+
+        for (position, velocity) in self.positions.iter_mut().zip(&self.velocities) {
+            *position = position.checked_add(*velocity).ok_or_else(overflow)?;
+        }
+
+        self.step += 1;
+
+        Ok(())
+    }
+
+    pub fn find_cycle(&self) -> Result<Cycle, Error> {
+        self.find_cycle_with(0, &Silent)
+    }
+
+    pub fn find_cycle_with<P: Reporter + ?Sized>(&self, axis: usize, progress: &P) -> Result<Cycle, Error> {
+        let mut state = self.clone();
+        loop {
+            state.step()?;
+            if state == *self {
+                let cycle = Cycle::new(self, &state);
+                progress.done(axis, cycle.n);
+                break Ok(cycle);
+            }
+            if (state.step - self.step) & (PROGRESS_INTERVAL - 1) == 0 {
+                progress.update(axis, state.step - self.step);
+            }
+        }
+    }
+}
+
+// Must be a power of two
+const PROGRESS_INTERVAL: usize = 1 << 20;
+
+impl PartialEq for DimensionalState {
+    fn eq(&self, other: &DimensionalState) -> bool {
+        self.positions == other.positions && self.velocities == other.velocities
+    }
+}
+
+impl Eq for DimensionalState {}
+
+impl Hash for DimensionalState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.positions.hash(state);
+        self.velocities.hash(state);
+    }
+}
+
+impl<const D: usize> PartialEq<System<D>> for DimensionalState {
+    fn eq(&self, other: &System<D>) -> bool {
+        self.positions.iter()
+            .zip(&other.bodies)
+            .all(|(a, b)| *a == b.position[0])
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct System<const D: usize = 3> {
+    bodies: Vec<Body<D>>,
+    step: usize,
+}
+
+impl<const D: usize> System<D> {
+    pub fn add_body(&mut self, body: Body<D>) {
+        self.bodies.push(body);
+    }
+
+    pub fn bodies(&self) -> &[Body<D>] {
+        &self.bodies
+    }
+
+    pub fn step_count(&self) -> usize {
+        self.step
+    }
+
+    /// Advances the system by one step. If a velocity or position would overflow, the system is left unchanged and an
+    /// error is returned.
+    pub fn step(&mut self) -> Result<(), Error> {
+        self.step_using(&mut vec![[0; D]; self.bodies.len()])
+    }
+
+    /// `step` with the new velocities in `velocities`, which must hold a zero velocity per body. That way a loop can
+    /// reuse them instead of allocating them every step.
+    fn step_using(&mut self, velocities: &mut [[i64; D]]) -> Result<(), Error> {
+        let overflow = Error::Overflow(self.step);
+
+        for ((i, body_i), (j, body_j)) in self.bodies.iter().enumerate().tuple_combinations() {
+            let acceleration = body_i.acceleration_towards(body_j);
+            for (axis, a) in acceleration.iter().enumerate() {
+                velocities[i][axis] += a;
+                velocities[j][axis] -= a;
+            }
+        }
+
+        for (body, velocity) in self.bodies.iter().zip(velocities.iter_mut()) {
+            for (v, (old, position)) in velocity.iter_mut().zip(body.velocity.iter().zip(&body.position)) {
+                *v = v.checked_add(*old).ok_or_else(|| overflow.clone())?;
+                position.checked_add(*v).ok_or_else(|| overflow.clone())?;
+            }
+        }
+
+        for (body, velocity) in self.bodies.iter_mut().zip(velocities.iter()) {
+            body.velocity = *velocity;
+            for (position, velocity) in body.position.iter_mut().zip(&body.velocity) {
+                *position += velocity;
+            }
+        }
+
+        self.step += 1;
+
+        Ok(())
+    }
+
+    pub fn energy(&self) -> i64 {
+        self.bodies.iter()
+            .map(|body| body.energy())
+            .sum()
+    }
+
+    pub fn checked_energy(&self) -> Result<i64, Error> {
+        self.bodies.iter()
+            .try_fold(0i64, |energy, body| energy.checked_add(body.checked_energy()?))
+            .ok_or(Error::Overflow(self.step))
+    }
+
+    /// Runs the simulation for `steps` steps and returns the total energy afterwards.
+    pub fn simulate(&mut self, steps: usize) -> Result<i64, Error> {
+        self.simulate_with(steps, |_| {})
+    }
+
+    /// Like `simulate`, but calls `observer` with the system before every step.
+    pub fn simulate_with<F: FnMut(&Self)>(&mut self, steps: usize, mut observer: F) -> Result<i64, Error> {
+        let mut velocities = vec![[0; D]; self.bodies.len()];
+        for _ in 0 .. steps {
+            observer(self);
+            velocities.fill([0; D]);
+            self.step_using(&mut velocities)?;
+        }
+        self.checked_energy()
+    }
+
+    pub fn axis(&self, axis: usize) -> DimensionalState {
+        DimensionalState {
+            positions: self.bodies.iter()
+                .map(|body| body.position[axis])
+                .collect_vec(),
+            velocities: self.bodies.iter()
+                .map(|body| body.velocity[axis])
+                .collect_vec(),
+            step: self.step,
+        }
+    }
+
+    pub fn dimensions(&self) -> Vec<DimensionalState> {
+        (0 .. D).map(|axis| self.axis(axis)).collect()
+    }
+
+    /// Finds the cycle of each axis. The axes don't interact, so each one is simulated on its own thread.
+    pub fn find_cycles(&self) -> Result<Cycles, Error> {
+        self.find_cycles_with(&Silent)
+    }
+
+    pub fn find_cycles_with<P: Reporter + ?Sized>(&self, progress: &P) -> Result<Cycles, Error> {
+        self.find_cycles_on(D, progress)
+    }
+
+    /// Like `find_cycles_with`, but runs at most `threads` axes at once.
+    pub fn find_cycles_on<P: Reporter + ?Sized>(&self, threads: usize, progress: &P) -> Result<Cycles, Error> {
+        let dimensions = self.dimensions();
+        let threads = threads.max(1);
+        let mut axes = Vec::with_capacity(dimensions.len());
+
+        for (chunk, states) in dimensions.chunks(threads).enumerate() {
+            let first_axis = chunk * threads;
+            thread::scope(|scope| {
+                let handles = states.iter()
+                    .enumerate()
+                    .map(|(i, state)| scope.spawn(move || state.find_cycle_with(first_axis + i, progress)))
+                    .collect_vec();
+
+                for handle in handles {
+                    axes.push(handle.join().expect("Cycle search panicked")?);
+                }
+                Ok::<_, Error>(())
+            })?;
+        }
+
+        Ok(Cycles {
+            axes,
+        })
+    }
+}
+
+/// The system with positions and velocities stored as one flat array per axis, which lets the compiler vectorize
+/// `step`.
+#[derive(Clone, Debug)]
+pub struct FlatSystem<const D: usize = 3> {
+    positions: [Vec<i64>; D],
+    velocities: [Vec<i64>; D],
+    step: usize,
+}
+
+impl<const D: usize> FlatSystem<D> {
+    pub fn step(&mut self) {
+        for (positions, velocities) in self.positions.iter_mut().zip(self.velocities.iter_mut()) {
+            for (position, velocity) in positions.iter().zip(velocities.iter_mut()) {
+                *velocity += positions.iter()
+                    .map(|other| (*other > *position) as i64 - (*other < *position) as i64)
+                    .sum::<i64>();
+            }
+
+            for (position, velocity) in positions.iter_mut().zip(velocities.iter()) {
+                *position += *velocity;
+            }
+        }
+
+        self.step += 1;
+    }
+
+    pub fn simulate(&mut self, steps: usize) -> i64 {
+        for _ in 0 .. steps {
+            self.step();
+        }
+        self.energy()
+    }
+
+    pub fn energy(&self) -> i64 {
+        let absolute_sum = |axes: &[Vec<i64>], i: usize| axes.iter().map(|axis| axis[i].abs()).sum::<i64>();
+
+        (0 .. self.positions[0].len())
+            .map(|i| absolute_sum(&self.positions, i) * absolute_sum(&self.velocities, i))
+            .sum()
+    }
+}
+
+impl<const D: usize> From<&System<D>> for FlatSystem<D> {
+    fn from(system: &System<D>) -> Self {
+        let dimensions = system.dimensions();
+
+        Self {
+            positions: std::array::from_fn(|axis| dimensions[axis].positions.clone()),
+            velocities: std::array::from_fn(|axis| dimensions[axis].velocities.clone()),
+            step: system.step,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Cycle {
+    n: usize,
+}
+
+impl Cycle {
+    pub fn new(x0: &DimensionalState, x1: &DimensionalState) -> Self {
+        Self {
+            n: x1.step - x0.step,
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.n
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Cycles {
+    axes: Vec<Cycle>,
+}
+
+impl Cycles {
+    pub fn axes(&self) -> &[Cycle] {
+        &self.axes
+    }
+
+    /// The number of steps until the whole system repeats. This is `u64`, since it can exceed a 32-bit `usize`, e.g.
+    /// on wasm32.
+    pub fn length(&self) -> Result<u64, Error> {
+        self.axes.iter()
+            .try_fold(1, |length, cycle| math::checked_lcm(length, cycle.n as u64))
+            .ok_or(Error::CycleOverflow)
+    }
+}
+
+/// The states of a system over time, to animate it.
+#[derive(Clone, Debug)]
+pub struct Snapshots {
+    systems: Vec<System>,
+}
+
+impl Snapshots {
+    /// Frames are scaled down to fit into this many cells.
+    const MAX_SIZE: (i64, i64) = (80, 40);
+
+    /// Simulates `steps` steps and keeps every state, including the first and last one.
+    pub fn record(system: &System, steps: usize) -> Result<Self, Error> {
+        let mut systems = Vec::with_capacity(steps + 1);
+        let mut system = system.clone();
+        system.simulate_with(steps, |state| systems.push(state.clone()))?;
+        systems.push(system);
+        Ok(Self {
+            systems,
+        })
+    }
+
+    pub fn systems(&self) -> &[System] {
+        &self.systems
+    }
+}
+
+/// The bodies seen from above, i.e. their x and y coordinates, numbered in the order of the input.
+impl Visualize for Snapshots {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame>> {
+        let positions = || self.systems.iter().flat_map(|system| system.bodies().iter().map(Body::position));
+        let (min_x, max_x) = positions().map(|position| position[0]).minmax().into_option().unwrap_or((0, 0));
+        let (min_y, max_y) = positions().map(|position| position[1]).minmax().into_option().unwrap_or((0, 0));
+        let (max_width, max_height) = Self::MAX_SIZE;
+        let scale = ((max_x - min_x) / max_width).max((max_y - min_y) / max_height) + 1;
+        let (width, height) = ((max_x - min_x) / scale + 1, (max_y - min_y) / scale + 1);
+
+        Box::new(self.systems.clone().into_iter().map(move |system| {
+            let mut frame = Frame::new(width as usize, height as usize);
+            for (i, body) in system.bodies().iter().enumerate() {
+                let glyph = std::char::from_digit(i as u32 % 10, 10).unwrap();
+                let (x, y) = ((body.position()[0] - min_x) / scale, (body.position()[1] - min_y) / scale);
+                frame.set(x as usize, y as usize, Cell::new(glyph, viz::DIM + i as u8 % 4));
+            }
+            frame.caption = format!("Step: {}, energy: {}", system.step_count(), system.energy());
+            frame
+        }))
+    }
+}
+
+#[cfg(feature="recorder")]
+#[derive(Debug, Fail)]
+pub enum ExportError {
+    #[fail(display = "JSON error: {}", _0)]
+    Json(#[cause] serde_json::Error),
+    #[fail(display = "CSV error: {}", _0)]
+    Csv(#[cause] csv::Error),
+    #[fail(display = "IO error: {}", _0)]
+    Io(#[cause] std::io::Error),
+}
+
+#[cfg(feature="recorder")]
+#[derive(Clone, Debug, Serialize)]
+pub struct BodyRecord {
+    pub position: Vec<i64>,
+    pub velocity: Vec<i64>,
+    pub energy: i64,
+}
+
+#[cfg(feature="recorder")]
+#[derive(Clone, Debug, Serialize)]
+pub struct StepRecord {
+    pub step: usize,
+    pub energy: i64,
+    pub bodies: Vec<BodyRecord>,
+}
+
+/// Captures the state of a system at every recorded step, e.g. with
+/// `system.simulate_with(steps, |system| recorder.record(system))`.
+#[cfg(feature="recorder")]
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Recorder {
+    steps: Vec<StepRecord>,
+}
+
+#[cfg(feature="recorder")]
+impl Recorder {
+    pub fn record<const D: usize>(&mut self, system: &System<D>) {
+        self.steps.push(StepRecord {
+            step: system.step,
+            energy: system.energy(),
+            bodies: system.bodies.iter()
+                .map(|body| BodyRecord {
+                    position: body.position.to_vec(),
+                    velocity: body.velocity.to_vec(),
+                    energy: body.energy(),
+                })
+                .collect(),
+        });
+    }
+
+    pub fn steps(&self) -> &[StepRecord] {
+        &self.steps
+    }
+
+    pub fn write_json<W: Write>(&self, writer: W) -> Result<(), ExportError> {
+        serde_json::to_writer_pretty(writer, &self.steps)
+            .map_err(ExportError::Json)
+    }
+
+    /// Writes one row per body and step.
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<(), ExportError> {
+        let mut writer = csv::Writer::from_writer(writer);
+
+        let dimensions = self.steps.first()
+            .and_then(|step| step.bodies.first())
+            .map(|body| body.position.len())
+            .unwrap_or_default();
+
+        let mut header = vec!["step".to_owned(), "body".to_owned(), "energy".to_owned()];
+        header.extend((0 .. dimensions).map(|axis| format!("position_{}", axis)));
+        header.extend((0 .. dimensions).map(|axis| format!("velocity_{}", axis)));
+        writer.write_record(&header).map_err(ExportError::Csv)?;
+
+        for step in &self.steps {
+            for (i, body) in step.bodies.iter().enumerate() {
+                let mut row = vec![step.step.to_string(), i.to_string(), body.energy.to_string()];
+                row.extend(body.position.iter().map(ToString::to_string));
+                row.extend(body.velocity.iter().map(ToString::to_string));
+                writer.write_record(&row).map_err(ExportError::Csv)?;
+            }
+        }
+
+        writer.flush().map_err(ExportError::Io)
+    }
+}
+
+pub fn parse_system<const D: usize>(input: &str) -> Result<System<D>, crate::Error> {
+    let re = Regex::new(r"[a-z]=([-+]?\d+)").unwrap();
+
+    let mut system = System::default();
+
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        let position = re.captures_iter(line)
+            .map(|capture| {
+                let coordinate = capture.get(1).unwrap().as_str();
+                coordinate.parse::<i64>().map_err(|e| ParseError::at(12, input, coordinate, e))
+            })
+            .collect::<Result<Vec<i64>, _>>()?
+            .try_into()
+            .map_err(|_| ParseError::at(12, input, line, format!("Expected {} coordinates", D)))?;
+        system.add_body(Body::new(position));
+    }
+
+    Ok(system)
+}
+
+#[aoc_generator(day12)]
+pub fn input_generator(input: &str) -> Result<System, crate::Error> {
+    util::init();
+    parse_system(&util::normalize_input(input))
+}
+
+pub fn report_system<const D: usize>(system: &System<D>, interval: usize) {
+    if log_enabled!(log::Level::Debug) && system.step % interval == 0 {
+        debug!("After {} steps:", system.step);
+        debug!("Energy: {}", system.energy());
+        for body in &system.bodies {
+            debug!(
+                "pos={:>3?}, vel={:>3?}, potential={:?}, kinetic={:?}",
+                body.position,
+                body.velocity,
+                body.potential_energy(),
+                body.kinetic_energy()
+            );
+        }
+    }
+}
+
+#[aoc(day12, part1)]
+pub fn solve_part1(system: &System) -> Result<i64, crate::Error> {
+    if cfg!(feature="soa") {
+        return Ok(FlatSystem::from(system).simulate(1000));
+    }
+
+    let mut system = system.clone();
+
+    debug!("System {:#?}", system);
+
+    Ok(system.simulate_with(1000, |system| report_system(system, 100))?)
+}
+
+/// The version of the cycle search, for the cache. Bump it when the search changes its results.
+const CYCLES_VERSION: u32 = 1;
+
+#[aoc(day12, part2)]
+pub fn solve_part2(initial_state: &System) -> Result<u64, crate::Error> {
+    // The search takes a while, so the cycles are cached by initial state.
+    let cycles = cache::try_get_or_insert_with(12, 2, CYCLES_VERSION, initial_state.bodies(), || {
+        let progress = progress::from_env("Axis", initial_state.dimensions().len());
+        initial_state.find_cycles_on(config::get().threads(), progress.as_ref())
+    })?;
+
+    debug!("Found complete cycle: {:#?}", cycles);
+    for (axis, cycle) in cycles.axes().iter().enumerate() {
+        debug!("Axis {} cycle: {}", axis, cycle.n);
+    }
+    let length = cycles.length()?;
+    debug!("Length: {}", length);
+
+    Ok(length)
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<i64, crate::Error> {
+    solve_part1(&input_generator(input)?)
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<u64, crate::Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = System;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 12;
+
+    fn parse(input: &str) -> Result<Self::Input, crate::Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, crate::Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, crate::Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    const FIRST: &str = "<x=-1, y=0, z=2>\n<x=2, y=-10, z=-7>\n<x=4, y=-8, z=8>\n<x=3, y=5, z=-1>";
+    const SECOND: &str = "<x=-8, y=-10, z=0>\n<x=5, y=5, z=10>\n<x=2, y=-7, z=3>\n<x=9, y=-8, z=-3>";
+
+    fn state(system: &System) -> Vec<([i64; 3], [i64; 3])> {
+        system.bodies().iter()
+            .map(|body| (*body.position(), *body.velocity()))
+            .collect()
+    }
+
+    #[test]
+    fn simulates_first_example() {
+        let mut system = input_generator(FIRST).unwrap();
+
+        system.step().unwrap();
+        assert_eq!(state(&system), vec![
+            ([2, -1, 1], [3, -1, -1]),
+            ([3, -7, -4], [1, 3, 3]),
+            ([1, -7, 5], [-3, 1, -3]),
+            ([2, 2, 0], [-1, -3, 1]),
+        ]);
+
+        assert_eq!(system.simulate(9).unwrap(), 179);
+        assert_eq!(state(&system), vec![
+            ([2, 1, -3], [-3, -2, 1]),
+            ([1, -8, 0], [-1, 1, 3]),
+            ([3, -6, 1], [3, 2, -3]),
+            ([2, 0, 4], [1, -1, -1]),
+        ]);
+        let energies = system.bodies().iter()
+            .map(|body| (body.potential_energy(), body.kinetic_energy()))
+            .collect_vec();
+        assert_eq!(energies, vec![(6, 6), (9, 5), (10, 8), (6, 3)]);
+    }
+
+    #[test]
+    fn simulates_second_example() {
+        let mut system = input_generator(SECOND).unwrap();
+        assert_eq!(system.simulate(100).unwrap(), 1940);
+    }
+
+    #[test]
+    fn snapshots_every_step() {
+        let snapshots = Snapshots::record(&input_generator(FIRST).unwrap(), 10).unwrap();
+        let frames = snapshots.frames().collect_vec();
+        assert_eq!(frames.len(), 11);
+        assert_eq!(frames[10].caption, "Step: 10, energy: 179");
+    }
+
+    #[test]
+    fn finds_cycles() {
+        assert_eq!(solve_part2(&input_generator(FIRST).unwrap()).unwrap(), 2772);
+        assert_eq!(solve_part2(&input_generator(SECOND).unwrap()).unwrap(), 4686774924);
+    }
+
+    #[test]
+    fn cycle_lengths_dont_wrap() {
+        // Axes of about a million steps each, like the puzzle inputs. The product exceeds a 32-bit `usize`.
+        let cycles = Cycles { axes: [1_048_573, 1_048_571, 1_048_559].iter().map(|&n| Cycle { n }).collect() };
+        assert_eq!(cycles.length().unwrap(), 1_048_573 * 1_048_571 * 1_048_559);
+
+        // Used to silently wrap around in release builds.
+        let cycles = Cycles { axes: (0 .. 3).map(|i| Cycle { n: usize::MAX - i }).collect() };
+        assert!(cycles.length().is_err());
+    }
+
+    #[test]
+    fn flat_system_simulates_the_same() {
+        let mut rng = util::rng(12);
+        for _ in 0 .. 20 {
+            let mut system = System::<3>::default();
+            for _ in 0 .. 4 {
+                system.add_body(Body::new([rng.gen_range(-20, 20), rng.gen_range(-20, 20), rng.gen_range(-20, 20)]));
+            }
+            let mut flat = FlatSystem::from(&system);
+
+            assert_eq!(flat.simulate(100), system.simulate(100).unwrap());
+        }
+    }
+
+    #[test]
+    fn simulates_the_same_as_single_steps() {
+        let mut rng = util::rng(12);
+        for _ in 0 .. 20 {
+            let mut system = System::<3>::default();
+            for _ in 0 .. 4 {
+                system.add_body(Body::new([rng.gen_range(-20, 20), rng.gen_range(-20, 20), rng.gen_range(-20, 20)]));
+            }
+            let mut stepped = system.clone();
+            for _ in 0 .. 100 {
+                stepped.step().unwrap();
+            }
+
+            assert_eq!(system.simulate(100).unwrap(), stepped.energy());
+            assert_eq!(state(&system), state(&stepped));
+        }
+    }
+
+    #[test]
+    fn far_apart_bodies_dont_overflow() {
+        let mut system = System::<1>::default();
+        system.add_body(Body::new([i64::MIN + 1]));
+        system.add_body(Body::new([i64::MAX]));
+        system.step().unwrap();
+        assert_eq!(system.bodies()[0].velocity(), &[1]);
+        assert_eq!(system.bodies()[1].velocity(), &[-1]);
+
+        let mut axis = system.axis(0);
+        axis.step().unwrap();
+        assert_eq!(axis.velocities, vec![2, -2]);
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let mut system = System::<1>::default();
+        system.add_body(Body { position: [i64::MAX - 1], velocity: [3] });
+        system.add_body(Body::new([0]));
+        let before = system.clone();
+
+        assert!(matches!(system.step(), Err(Error::Overflow(0))));
+        assert_eq!(system.bodies(), before.bodies());
+        assert!(matches!(system.simulate(10), Err(Error::Overflow(0))));
+        assert!(matches!(before.axis(0).step(), Err(Error::Overflow(0))));
+        assert!(matches!(before.find_cycles(), Err(Error::Overflow(0))));
+    }
+
+    #[test]
+    fn big_system_simulates_the_same() {
+        assert_eq!(BigSystem::from(&input_generator(SECOND).unwrap()).simulate(100), BigInt::from(1940));
+
+        // Way beyond `i64`.
+        let far = num::pow(BigInt::from(10), 30);
+        let mut system = BigSystem::default();
+        system.add_body(BigBody::new(vec![-far.clone()]));
+        system.add_body(BigBody::new(vec![far.clone()]));
+        system.step();
+        assert_eq!(system.bodies()[0].velocity(), &[BigInt::from(1)]);
+        assert_eq!(system.bodies()[1].position(), &[far - 1]);
+    }
+
+    #[test]
+    fn needs_three_coordinates() {
+        assert!(input_generator("<x=1, y=2>").is_err());
+    }
+}
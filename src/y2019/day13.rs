@@ -0,0 +1,1358 @@
+use std::convert::{TryFrom, TryInto};
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt::{self, Write};
+use std::cmp::Ordering;
+use std::str::FromStr;
+use std::path::Path;
+use std::fs;
+use std::io;
+use std::time::{Duration, Instant};
+
+use aoc_runner_derive::{aoc, aoc_generator};
+use failure::Fail;
+use itertools::Itertools;
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::grid::Grid;
+use crate::intcode::{Machine, Program, Error as IntcodeError};
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::viz::{self, Cell, Frame, Visualize};
+
+
+#[derive(Clone, Debug, Fail)]
+pub enum Error {
+    #[fail(display = "Intcode error: {}", _0)]
+    Intcode(#[cause] IntcodeError),
+    #[fail(display = "Invalid tile value: {}", _0)]
+    InvalidTile(i64),
+    #[fail(display = "Incomplete instruction")]
+    IncompleteInstruction,
+    #[fail(display = "Invalid joystick position: {}", _0)]
+    InvalidJoystick(i64),
+    #[fail(display = "Invalid level tile: {:?}", _0)]
+    InvalidLevel(char),
+    #[fail(display = "Board not found in memory")]
+    BoardNotFound,
+    #[fail(display = "Unknown difficulty: {}", _0)]
+    UnknownDifficulty(String),
+}
+
+impl From<IntcodeError> for Error {
+    fn from(e: IntcodeError) -> Self {
+        Self::Intcode(e)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Empty,
+    Wall,
+    Block,
+    Paddle,
+    Ball,
+}
+
+impl TryFrom<i64> for Tile {
+    type Error = Error;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Empty),
+            1 => Ok(Self::Wall),
+            2 => Ok(Self::Block),
+            3 => Ok(Self::Paddle),
+            4 => Ok(Self::Ball),
+            _ => Err(Error::InvalidTile(value))
+        }
+    }
+}
+
+impl From<Tile> for char {
+    fn from(tile: Tile) -> Self {
+        match tile {
+            Tile::Empty => ' ',
+            Tile::Wall => '#',
+            Tile::Block => '█',
+            Tile::Paddle => '|',
+            Tile::Ball => '⬤',
+        }
+    }
+}
+
+impl From<Tile> for i64 {
+    fn from(tile: Tile) -> Self {
+        match tile {
+            Tile::Empty => 0,
+            Tile::Wall => 1,
+            Tile::Block => 2,
+            Tile::Paddle => 3,
+            Tile::Ball => 4,
+        }
+    }
+}
+
+impl Default for Tile {
+    fn default() -> Self {
+        Tile::Empty
+    }
+}
+
+/// A custom block layout. Levels are written like the screen is printed, with `█` or `X` for blocks. Walls, the paddle
+/// and the ball can't be moved, so all other tiles are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Level {
+    pub blocks: BTreeSet<(i64, i64)>,
+}
+
+impl Level {
+    /// Shuffles the blocks of a screen. There are as many as before, and they stay between the topmost and bottommost
+    /// row of blocks, so the ball can still reach them. The same seed always gives the same layout.
+    pub fn shuffle(screen: &Screen, seed: u64) -> Self {
+        let blocks = screen.framebuffer.iter()
+            .filter(|(_, tile)| *tile == Tile::Block)
+            .map(|(pos, _)| pos)
+            .collect::<Vec<(i64, i64)>>();
+        let (top, bottom) = match blocks.iter().map(|(_, y)| *y).minmax().into_option() {
+            Some(rows) => rows,
+            None => return Self::default(),
+        };
+
+        let mut cells = screen.framebuffer.iter()
+            .filter(|((_, y), tile)| (top ..= bottom).contains(y) && (*tile == Tile::Empty || *tile == Tile::Block))
+            .map(|(pos, _)| pos)
+            .collect::<Vec<(i64, i64)>>();
+        // Column by column, like before the framebuffer went row by row, so that seeds keep their layouts.
+        cells.sort_unstable();
+        cells.shuffle(&mut util::rng(seed));
+        cells.truncate(blocks.len());
+
+        Self { blocks: cells.into_iter().collect() }
+    }
+}
+
+impl FromStr for Level {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut blocks = BTreeSet::new();
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '█' | 'X' => { blocks.insert((x as i64, y as i64)); },
+                    ' ' | '.' | '#' | '|' | '⬤' => {},
+                    _ => return Err(Error::InvalidLevel(c)),
+                }
+            }
+        }
+        Ok(Self { blocks })
+    }
+}
+
+/// Where the game keeps its board in memory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    /// Address of the top-left tile. Tiles are stored row by row.
+    pub address: usize,
+    pub width: usize,
+    pub height: usize,
+    /// Address of the number of remaining blocks, if the game keeps one.
+    pub block_counter: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    Draw {
+        x: i64,
+        y: i64,
+        tile: Tile,
+    },
+    Score {
+        score: i64,
+    }
+}
+
+impl Instruction {
+    pub fn is_update(&self) -> bool {
+        match self {
+            Instruction::Draw { tile: Tile::Ball, .. }
+            | Instruction::Draw { tile: Tile::Paddle, .. }
+            | Instruction::Score { .. } => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_ball(&self) -> bool {
+        match self {
+            Instruction::Draw { tile: Tile::Ball, .. } => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Instruction::Draw { tile: Tile::Empty, .. } => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_paddle(&self) -> bool {
+        match self {
+            Instruction::Draw { tile: Tile::Paddle, .. } => true,
+            _ => false,
+        }
+    }
+
+    pub fn paddle_x(&self) -> Option<i64> {
+        debug!("paddle_x: {:?}", self);
+        match self {
+            Instruction::Draw { tile: Tile::Paddle, x, .. } => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+/// Everything that changed on the screen since the previous frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameEvent {
+    pub dirty: Vec<(i64, i64)>,
+    pub score_changed: bool,
+    /// The ball changed direction.
+    pub bounced: bool,
+    pub blocks_broken: usize,
+    pub ball_lost: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub blocks: usize,
+    pub paddle_hits: usize,
+    pub frames: usize,
+    pub balls_lost: usize,
+}
+
+/// The tiles on the screen, row by row. It grows to fit the tiles that are drawn, so that it's as large as the board
+/// once that's drawn. Cells that weren't drawn yet are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Framebuffer {
+    /// The position of the first cell, i.e. the smallest x and y drawn.
+    origin: (i64, i64),
+    width: usize,
+    height: usize,
+    cells: Vec<Option<Tile>>,
+    len: usize,
+}
+
+impl Framebuffer {
+    fn index(&self, (x, y): (i64, i64)) -> Option<usize> {
+        let (column, row) = (x - self.origin.0, y - self.origin.1);
+        if column < 0 || row < 0 || column >= self.width as i64 || row >= self.height as i64 {
+            return None;
+        }
+        Some(row as usize * self.width + column as usize)
+    }
+
+    pub fn get(&self, position: &(i64, i64)) -> Option<&Tile> {
+        self.cells[self.index(*position)?].as_ref()
+    }
+
+    /// Draws a tile and returns the one that was there before.
+    pub fn insert(&mut self, position: (i64, i64), tile: Tile) -> Option<Tile> {
+        let index = match self.index(position) {
+            Some(index) => index,
+            None => {
+                self.grow_to(position);
+                self.index(position).unwrap()
+            },
+        };
+        let previous = self.cells[index].replace(tile);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    fn grow_to(&mut self, (x, y): (i64, i64)) {
+        let ((min_x, min_y), (max_x, max_y)) = match self.bounds() {
+            Some((min, max)) => ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y))),
+            None => ((x, y), (x, y)),
+        };
+        let (width, height) = ((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize);
+
+        let mut cells = vec![None; width * height];
+        let offset = (self.origin.0 - min_x) as usize;
+        for (row, tiles) in self.cells.chunks(self.width.max(1)).enumerate() {
+            let start = (self.origin.1 - min_y) as usize * width + row * width + offset;
+            cells[start .. start + tiles.len()].copy_from_slice(tiles);
+        }
+
+        self.origin = (min_x, min_y);
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+    }
+
+    /// The smallest and the largest x and y drawn.
+    pub fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let max = (self.origin.0 + self.width as i64 - 1, self.origin.1 + self.height as i64 - 1);
+        Some((self.origin, max))
+    }
+
+    /// The drawn tiles with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), Tile)> + '_ {
+        let (origin, width) = (self.origin, self.width);
+        self.cells.iter()
+            .enumerate()
+            .filter_map(move |(i, tile)| {
+                let position = (origin.0 + (i % width) as i64, origin.1 + (i / width) as i64);
+                Some((position, (*tile)?))
+            })
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.iter().map(|(position, _)| position)
+    }
+
+    /// The number of drawn tiles.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Screen {
+    pub framebuffer: Framebuffer,
+    pub last_instruction: Option<Instruction>,
+    pub score: i64,
+    pub ready: bool,
+    pub paddle_x: i64,
+    pub paddle_y: i64,
+    pub ball_x: i64,
+    pub ball_y: i64,
+    pub num_blocks: usize,
+    dirty: BTreeSet<(i64, i64)>,
+    ball: Option<(i64, i64)>,
+    ball_dx: i64,
+    ball_dy: i64,
+    stats: Stats,
+    frame: FrameEvent,
+}
+
+impl Screen {
+    pub fn run_instruction(&mut self, instruction: &Instruction) {
+        debug!("screen: instruction: {:?}", instruction);
+        self.last_instruction = Some(instruction.clone());
+        match instruction {
+            Instruction::Draw { x, y, tile } => {
+                match tile {
+                    Tile::Paddle => {
+                        self.paddle_x = *x;
+                        self.paddle_y = *y;
+                    },
+                    Tile::Ball => {
+                        if let Some((previous_x, previous_y)) = self.ball {
+                            let (dx, dy) = (*x - previous_x, *y - previous_y);
+                            if dy < 0 && self.ball_dy > 0 && previous_y + 1 == self.paddle_y {
+                                self.stats.paddle_hits += 1;
+                            }
+                            if *y > self.paddle_y && previous_y <= self.paddle_y {
+                                self.stats.balls_lost += 1;
+                                self.frame.ball_lost = true;
+                            }
+                            if dx * self.ball_dx < 0 || dy * self.ball_dy < 0 {
+                                self.frame.bounced = true;
+                            }
+                            if dx != 0 {
+                                self.ball_dx = dx;
+                            }
+                            if dy != 0 {
+                                self.ball_dy = dy;
+                            }
+                        }
+                        self.ball = Some((*x, *y));
+                        self.ball_x = *x;
+                        self.ball_y = *y;
+                    },
+                    _ => {},
+                }
+
+                let previous = self.framebuffer.insert((*x, *y), *tile);
+                if previous != Some(*tile) {
+                    if previous == Some(Tile::Block) {
+                        self.num_blocks -= 1;
+                        self.frame.blocks_broken += 1;
+                    }
+                    if *tile == Tile::Block {
+                        self.num_blocks += 1;
+                    }
+                    self.dirty.insert((*x, *y));
+                }
+            }
+            Instruction::Score { score } => {
+                debug!("score: {}", score);
+                if self.score != *score {
+                    self.frame.score_changed = true;
+                }
+                self.score = *score;
+            }
+        }
+
+    }
+
+    /// Returns the changes accumulated since the last call and starts a new frame.
+    pub fn take_frame(&mut self) -> FrameEvent {
+        self.stats.frames += 1;
+        let dirty = std::mem::take(&mut self.dirty);
+        FrameEvent {
+            dirty: dirty.into_iter().collect(),
+            ..std::mem::take(&mut self.frame)
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            blocks: self.num_blocks,
+            ..self.stats
+        }
+    }
+
+    pub fn screen_size(&self) -> Option<(i64, i64)> {
+        let (_, max) = self.framebuffer.bounds()?;
+        debug!("screen size: {} x {}", max.0, max.1);
+        Some((max.0 + 1, max.1 + 1))
+    }
+
+    /// The screen as a frame for visualizations, with the score as caption.
+    pub fn to_frame(&self) -> Frame {
+        let (width, height) = self.screen_size().unwrap_or((0, 0));
+        let mut frame = Frame::new(width as usize, height as usize);
+        for ((x, y), tile) in self.framebuffer.iter() {
+            let color = match tile {
+                Tile::Empty => viz::BACKGROUND,
+                Tile::Wall => viz::DIM,
+                Tile::Block => viz::NORMAL,
+                Tile::Paddle => viz::BRIGHT,
+                Tile::Ball => viz::HIGHLIGHT,
+            };
+            frame.set(x as usize, y as usize, Cell::new(tile.into(), color));
+        }
+        frame.caption = format!("Score: {}", self.score);
+        frame
+    }
+
+    pub fn find(&self, tile: Tile) -> Option<(i64, i64)> {
+        self.framebuffer.iter()
+            .find(|(_, other)| tile == *other)
+            .map(|(pos, _)| pos)
+    }
+}
+
+impl fmt::Display for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if let Some((min, max)) = self.framebuffer.bounds() {
+            for y in min.1 ..= max.1 {
+                for x in min.0 ..= max.0 {
+                    let tile = self.framebuffer.get(&(x, y))
+                        .copied()
+                        .unwrap_or_default();
+                    f.write_char(tile.into())?;
+                }
+                f.write_char('\n')?;
+            }
+        }
+        write!(f, "Score: {}", self.score)?;
+        Ok(())
+    }
+}
+
+impl Grid for Screen {
+    fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        self.framebuffer.bounds()
+    }
+
+    fn glyph(&self, x: i64, y: i64) -> char {
+        self.framebuffer.get(&(x, y)).map_or(' ', |&tile| tile.into())
+    }
+}
+
+/// Just the screen as it is.
+impl Visualize for Screen {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame>> {
+        Box::new(std::iter::once(self.to_frame()))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum JoystickPosition {
+    Neutral,
+    Left,
+    Right,
+}
+
+impl From<JoystickPosition> for i64 {
+    fn from(value: JoystickPosition) -> Self {
+        match value {
+            JoystickPosition::Neutral => 0,
+            JoystickPosition::Left => -1,
+            JoystickPosition::Right => 1,
+        }
+    }
+}
+
+impl TryFrom<i64> for JoystickPosition {
+    type Error = Error;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Neutral),
+            -1 => Ok(Self::Left),
+            1 => Ok(Self::Right),
+            _ => Err(Error::InvalidJoystick(value)),
+        }
+    }
+}
+
+/// How the autopilot steers the paddle.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Autopilot {
+    /// Follows the ball.
+    Chase,
+    /// Moves to where the ball will reach the paddle.
+    #[default]
+    Predict,
+}
+
+/// How well the autopilot plays. Below `Perfect` it reacts late, its hand shakes and now and then it aims for the
+/// wrong column, which makes it a lot more fun to watch.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    #[default]
+    Perfect,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Perfect];
+
+    /// Frames the autopilot lags behind the game.
+    pub fn reaction_delay(self) -> usize {
+        match self {
+            Self::Easy => 6,
+            Self::Normal => 3,
+            Self::Hard => 1,
+            Self::Perfect => 0,
+        }
+    }
+
+    /// Chance to move the joystick randomly in a frame.
+    pub fn jitter(self) -> f64 {
+        match self {
+            Self::Easy => 0.15,
+            Self::Normal => 0.08,
+            Self::Hard => 0.03,
+            Self::Perfect => 0.,
+        }
+    }
+
+    /// Chance to aim one column next to the ball, whenever the target changes.
+    pub fn mistakes(self) -> f64 {
+        match self {
+            Self::Easy => 0.25,
+            Self::Normal => 0.1,
+            Self::Hard => 0.03,
+            Self::Perfect => 0.,
+        }
+    }
+
+    /// The next harder difficulty, wrapping around to `Easy`.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|difficulty| *difficulty == self).unwrap();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Easy => "easy",
+            Self::Normal => "normal",
+            Self::Hard => "hard",
+            Self::Perfect => "perfect",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.iter()
+            .find(|difficulty| difficulty.to_string() == s)
+            .copied()
+            .ok_or_else(|| Error::UnknownDifficulty(s.to_owned()))
+    }
+}
+
+/// Turns where the autopilot wants the paddle into joystick moves, with the flaws of its difficulty.
+#[derive(Clone, Debug)]
+struct Pilot {
+    difficulty: Difficulty,
+    seed: u64,
+    rng: util::Pcg32,
+    /// Targets of the last frames. The autopilot acts on the oldest one.
+    targets: VecDeque<i64>,
+    /// The target the autopilot acted on last, and how many columns it's off by.
+    aim: Option<(i64, i64)>,
+}
+
+impl Pilot {
+    fn new(difficulty: Difficulty, seed: u64) -> Self {
+        Self {
+            difficulty,
+            seed,
+            rng: util::rng(seed),
+            targets: VecDeque::new(),
+            aim: None,
+        }
+    }
+
+    /// `frames_left` is how soon the ball may reach the paddle. The autopilot only dawdles when there's time to make
+    /// up for it, so its mistakes are what loses the ball.
+    fn steer(&mut self, target_x: i64, paddle_x: i64, frames_left: usize) -> JoystickPosition {
+        let slack = |x: i64| frames_left.saturating_sub((x - paddle_x).unsigned_abs() as usize);
+
+        self.targets.push_back(target_x);
+        while self.targets.len() > self.difficulty.reaction_delay() + 1 {
+            self.targets.pop_front();
+        }
+        let lag = slack(target_x).min(self.targets.len() - 1);
+        let seen = self.targets[self.targets.len() - 1 - lag];
+
+        let offset = match self.aim {
+            Some((target, offset)) if target == seen => offset,
+            _ => {
+                let offset = if self.rng.gen_bool(self.difficulty.mistakes()) {
+                    *[-1, 1].choose(&mut self.rng).unwrap()
+                }
+                else {
+                    0
+                };
+                self.aim = Some((seen, offset));
+                offset
+            },
+        };
+        let aim_x = seen + offset;
+
+        // A wrong move takes two frames to undo.
+        if slack(aim_x) >= 2 && self.rng.gen_bool(self.difficulty.jitter()) {
+            return *[JoystickPosition::Left, JoystickPosition::Neutral, JoystickPosition::Right]
+                .choose(&mut self.rng)
+                .unwrap();
+        }
+
+        match aim_x.cmp(&paddle_x) {
+            Ordering::Equal => JoystickPosition::Neutral,
+            Ordering::Less => JoystickPosition::Left,
+            Ordering::Greater => JoystickPosition::Right,
+        }
+    }
+}
+
+impl Default for JoystickPosition {
+    fn default() -> Self {
+        Self::Neutral
+    }
+}
+
+#[derive(Debug, Fail)]
+pub enum InputLogError {
+    #[fail(display = "IO error: {}", _0)]
+    Io(#[cause] io::Error),
+    #[fail(display = "Invalid input log in line {}", _0)]
+    Parse(usize),
+}
+
+/// Joystick inputs of a game. Time is counted in joystick reads, so replaying a log is frame-perfect.
+///
+/// On disk, the first line holds the total number of reads and every following line the read at which the joystick
+/// changed and its new position, e.g.:
+///
+/// ```text
+/// inputs 240
+/// 3 -1
+/// 7 0
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputLog {
+    inputs: usize,
+    changes: Vec<(usize, JoystickPosition)>,
+}
+
+impl InputLog {
+    fn push(&mut self, index: usize, joystick: JoystickPosition) {
+        if let Some((last_index, last_joystick)) = self.changes.last_mut() {
+            if *last_index == index {
+                *last_joystick = joystick;
+                return;
+            }
+        }
+        if self.position_at(index) != joystick {
+            self.changes.push((index, joystick));
+        }
+    }
+
+    /// Number of joystick reads in the log.
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    /// Number of times the joystick was moved.
+    pub fn changes(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Joystick position at the given read.
+    pub fn position_at(&self, index: usize) -> JoystickPosition {
+        self.changes.iter()
+            .take_while(|(change, _)| *change <= index)
+            .last()
+            .map(|(_, joystick)| *joystick)
+            .unwrap_or_default()
+    }
+
+    /// Joystick position for every read.
+    pub fn positions(&self) -> impl Iterator<Item=JoystickPosition> + '_ {
+        (0 .. self.inputs).map(move |index| self.position_at(index))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, InputLogError> {
+        fs::read_to_string(path)
+            .map_err(InputLogError::Io)?
+            .parse()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), InputLogError> {
+        fs::write(path, self.to_string())
+            .map_err(InputLogError::Io)
+    }
+}
+
+impl fmt::Display for InputLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        writeln!(f, "inputs {}", self.inputs)?;
+        for (index, joystick) in &self.changes {
+            writeln!(f, "{} {}", index, i64::from(*joystick))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InputLog {
+    type Err = InputLogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let inputs = lines.next()
+            .and_then(|(_, line)| line.trim().strip_prefix("inputs "))
+            .and_then(|inputs| inputs.trim().parse().ok())
+            .ok_or(InputLogError::Parse(1))?;
+
+        let mut log = InputLog { inputs, changes: Vec::new() };
+        for (i, line) in lines {
+            let (index, joystick) = line.split_whitespace()
+                .collect_tuple()
+                .and_then(|(index, joystick)| {
+                    let joystick = joystick.parse::<i64>().ok()?.try_into().ok()?;
+                    Some((index.parse().ok()?, joystick))
+                })
+                .ok_or(InputLogError::Parse(i + 1))?;
+            log.push(index, joystick);
+        }
+
+        Ok(log)
+    }
+}
+
+/// Measurements of a game played by the autopilot.
+#[derive(Clone, Debug)]
+pub struct Benchmark {
+    pub score: i64,
+    pub frames: usize,
+    /// Instructions executed by the game itself, without the autopilot's lookahead.
+    pub instructions: u64,
+    pub elapsed: Duration,
+}
+
+impl Benchmark {
+    pub fn frames_per_second(&self) -> f64 {
+        self.frames as f64 / self.elapsed.as_secs_f64()
+    }
+
+    pub fn instructions_per_second(&self) -> f64 {
+        self.instructions as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl fmt::Display for Benchmark {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        writeln!(f, "score:        {}", self.score)?;
+        writeln!(f, "frames:       {} ({:.0}/s)", self.frames, self.frames_per_second())?;
+        writeln!(f, "instructions: {} ({:.0}/s)", self.instructions, self.instructions_per_second())?;
+        write!(f, "time:         {:.3}s", self.elapsed.as_secs_f64())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Arcade {
+    pub machine: Machine,
+    pub screen: Screen,
+    pub program: Program,
+    input_log: InputLog,
+    replay_until: usize,
+    /// Joystick read at which the ball reaches the paddle and the ball's x position then.
+    prediction: Option<(usize, i64)>,
+    pilot: Pilot,
+}
+
+impl Arcade {
+    /// Upper bound for the autopilot's lookahead, in case the ball never comes down.
+    const PREDICTION_FRAMES: usize = 10000;
+    /// Seed of the autopilot's mistakes, unless `set_difficulty` gets another one.
+    pub const PILOT_SEED: u64 = 0;
+
+    pub fn new(program: Program) -> Self {
+        let mut arcade = Self {
+            machine: Machine::new(program.clone()),
+            screen: Screen::default(),
+            program,
+            input_log: InputLog::default(),
+            replay_until: 0,
+            prediction: None,
+            pilot: Pilot::new(Difficulty::default(), Self::PILOT_SEED),
+        };
+
+        arcade.init_machine();
+
+        arcade
+    }
+
+    fn read_instruction(&mut self) -> Result<Option<Instruction>, Error> {
+        self.read_instruction_with(Machine::next_output)
+    }
+
+    fn read_instruction_with<F>(&mut self, mut next_output: F) -> Result<Option<Instruction>, Error>
+        where F: FnMut(&mut Machine) -> Result<Option<i64>, IntcodeError>
+    {
+        debug!("read instruction");
+        let a = if let Some(a) = next_output(&mut self.machine)? {
+            a
+        }
+        else {
+            return Ok(None);
+        };
+        debug!("read instruction: a = {:?}", a);
+        let b = next_output(&mut self.machine)?
+            .ok_or(Error::IncompleteInstruction)?;
+        debug!("read instruction: b = {:?}", b);
+        let c = next_output(&mut self.machine)?
+            .ok_or(Error::IncompleteInstruction)?;
+        debug!("read instruction: c = {:?}", c);
+
+        let instruction = match (a, b, c) {
+            (-1, 0, score) => {
+                Instruction::Score {
+                    score,
+                }
+            },
+            (x, y, tile) => {
+                Instruction::Draw {
+                    x,
+                    y,
+                    tile: tile.try_into()?,
+                }
+            }
+        };
+
+        Ok(Some(instruction))
+    }
+
+    pub fn step(&mut self) -> Result<(), Error> {
+        debug!("arcade: step");
+        if let Some(instruction) = self.read_instruction()? {
+            self.screen.run_instruction(&instruction);
+            debug!("instruction: {:?}", instruction);
+        }
+        Ok(())
+    }
+
+    pub fn run(&mut self) -> Result<(), Error> {
+        while !self.machine.is_halted() {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    pub fn run_until<F: FnMut(&mut Self) -> bool>(&mut self, mut f: F) -> Result<(), Error> {
+        debug!("run_until: f() = {:?}", f(self));
+        while !f(self) {
+            debug!("run_until: step");
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    pub fn wait_until<F: FnMut(&mut Self) -> bool>(&mut self, f: F) -> Result<(), Error> {
+        self.run_until(|arcade| {
+            arcade.screen.last_instruction
+                .as_ref()
+                .map(|instruction| instruction.is_empty()).unwrap_or_default()
+        })?;
+        self.run_until(f)?;
+        Ok(())
+    }
+
+    /// Draws everything up to the next joystick read.
+    fn draw_until_input(&mut self) -> Result<(), Error> {
+        while let Some(instruction) = self.read_instruction_with(Machine::next_output_before_input)? {
+            self.screen.run_instruction(&instruction);
+        }
+        Ok(())
+    }
+
+    /// Runs until the game asks for the joystick again. Everything drawn in between is one frame.
+    pub fn wait_frame(&mut self) -> Result<FrameEvent, Error> {
+        if self.machine.awaits_input() {
+            self.machine.step()?;
+        }
+        self.draw_until_input()?;
+        Ok(self.screen.take_frame())
+    }
+
+    /// Runs until the initial screen is drawn. The game draws the whole board before it reads the joystick for the
+    /// first time, so this works for any board size.
+    pub fn load_screen(&mut self) -> Result<(), Error> {
+        match self.draw_until_input() {
+            Err(Error::Intcode(IntcodeError::Halted)) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Finds the board in memory by looking for the loaded screen. The block counter is found by playing a copy of the
+    /// game until the first block breaks and looking for the memory cell that counted down with it.
+    pub fn locate_board(&self) -> Result<Board, Error> {
+        let (width, height) = self.screen.screen_size()
+            .ok_or(Error::BoardNotFound)?;
+        let (width, height) = (width as usize, height as usize);
+
+        let tiles = (0 .. height as i64)
+            .flat_map(|y| (0 .. width as i64).map(move |x| (x, y)))
+            .map(|position| i64::from(self.screen.framebuffer.get(&position).copied().unwrap_or_default()))
+            .collect::<Vec<i64>>();
+
+        let memory = self.machine.memory();
+        let address = memory.windows(tiles.len())
+            .position(|window| window == tiles.as_slice())
+            .ok_or(Error::BoardNotFound)?;
+        let board_range = address .. address + tiles.len();
+
+        let blocks = self.screen.num_blocks as i64;
+        let mut arcade = self.clone();
+        while arcade.screen.num_blocks as i64 == blocks && arcade.machine.inputs_read() < Self::PREDICTION_FRAMES {
+            arcade.autopilot()?;
+            match arcade.wait_frame() {
+                Err(Error::Intcode(IntcodeError::Halted)) => break,
+                result => { result?; },
+            }
+        }
+        let remaining = arcade.screen.num_blocks as i64;
+
+        let candidates = memory.iter()
+            .zip(arcade.machine.memory())
+            .enumerate()
+            .filter(|(address, (before, after))| {
+                !board_range.contains(address) && **before == blocks && **after == remaining
+            })
+            .map(|(address, _)| address)
+            .collect::<Vec<usize>>();
+        debug!("block counter candidates: {:?}", candidates);
+
+        Ok(Board {
+            address,
+            width,
+            height,
+            block_counter: if remaining < blocks && candidates.len() == 1 { Some(candidates[0]) } else { None },
+        })
+    }
+
+    /// Replaces the blocks with a custom level. Call this after `load_screen` and before the game starts.
+    pub fn load_level(&mut self, level: &Level) -> Result<(), Error> {
+        let board = self.locate_board()?;
+
+        for y in 0 .. board.height as i64 {
+            for x in 0 .. board.width as i64 {
+                let current = self.screen.framebuffer.get(&(x, y)).copied().unwrap_or_default();
+                let tile = match current {
+                    Tile::Empty | Tile::Block => {
+                        if level.blocks.contains(&(x, y)) { Tile::Block } else { Tile::Empty }
+                    },
+                    _ => continue,
+                };
+                if tile == current {
+                    continue;
+                }
+                let address = board.address + y as usize * board.width + x as usize;
+                self.machine.set_data(address, tile.into());
+                self.screen.run_instruction(&Instruction::Draw { x, y, tile });
+            }
+        }
+
+        match board.block_counter {
+            Some(address) => self.machine.set_data(address, self.screen.num_blocks as i64),
+            None => warn!("Block counter not found. The game might not end when all blocks are broken."),
+        }
+
+        self.prediction = None;
+
+        Ok(())
+    }
+
+    pub fn set_joystick(&mut self, joystick: JoystickPosition) {
+        if self.is_replaying() {
+            return;
+        }
+        self.input_log.push(self.machine.inputs_read(), joystick);
+        self.machine.set_contant_input(joystick.into())
+    }
+
+    /// Returns the joystick inputs of this game so far.
+    pub fn record(&self) -> InputLog {
+        InputLog {
+            inputs: self.machine.inputs_read(),
+            changes: self.input_log.changes.clone(),
+        }
+    }
+
+    /// Feeds the recorded joystick inputs to the game. The log must have been recorded from the same point of the game,
+    /// usually right after `load_screen`. The joystick is ignored until the log runs out.
+    pub fn replay(&mut self, log: &InputLog) {
+        for joystick in log.positions().skip(self.machine.inputs_read()) {
+            self.machine.push_input(joystick.into());
+        }
+        self.machine.set_contant_input(log.position_at(log.inputs).into());
+        self.input_log = log.clone();
+        self.replay_until = log.inputs;
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.machine.inputs_read() < self.replay_until
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.pilot.difficulty
+    }
+
+    /// The autopilot's mistakes come from `seed`, so that a game with the same seed and inputs plays the same.
+    pub fn set_difficulty(&mut self, difficulty: Difficulty, seed: u64) {
+        self.pilot = Pilot::new(difficulty, seed);
+    }
+
+    pub fn pilot_seed(&self) -> u64 {
+        self.pilot.seed
+    }
+
+    pub fn autopilot(&mut self) -> Result<(), Error> {
+        self.autopilot_with(Autopilot::default())
+    }
+
+    pub fn autopilot_with(&mut self, strategy: Autopilot) -> Result<(), Error> {
+        let target_x = match strategy {
+            Autopilot::Chase => self.screen.ball_x,
+            Autopilot::Predict => self.predicted_x()?,
+        };
+
+        // The ball moves a row per frame, so it can't be at the paddle any sooner.
+        let frames_left = (self.screen.paddle_y - 1 - self.screen.ball_y).max(0) as usize;
+        let joystick = self.pilot.steer(target_x, self.screen.paddle_x, frames_left);
+
+        debug!("autopilot: strategy={:?}, target_x={}, paddle_x={}, joystick={:?}", strategy, target_x, self.screen.paddle_x, joystick);
+
+        self.set_joystick(joystick);
+
+        Ok(())
+    }
+
+    /// Lets the autopilot play until the game is over, and measures how long that takes.
+    pub fn benchmark(&mut self, strategy: Autopilot) -> Result<Benchmark, Error> {
+        let start = Instant::now();
+        let instructions = self.machine.stats().instructions;
+        let mut frames = 0;
+
+        loop {
+            self.autopilot_with(strategy)?;
+            match self.wait_frame() {
+                Ok(_) => frames += 1,
+                Err(Error::Intcode(IntcodeError::Halted)) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Benchmark {
+            score: self.screen.score,
+            frames,
+            instructions: self.machine.stats().instructions - instructions,
+            elapsed: start.elapsed(),
+        })
+    }
+
+    /// Simulates a copy of the game until the ball reaches the row above the paddle, and returns the ball position for
+    /// every frame until then. The ball doesn't touch the paddle before that, so the paddle is left alone.
+    pub fn predict_ball(&self) -> Result<Vec<(i64, i64)>, Error> {
+        Ok(self.simulate_ball()?.0)
+    }
+
+    fn simulate_ball(&self) -> Result<(Vec<(i64, i64)>, usize), Error> {
+        let mut arcade = self.clone();
+        arcade.machine.set_contant_input(JoystickPosition::Neutral.into());
+
+        let mut path = Vec::new();
+        loop {
+            match arcade.wait_frame() {
+                Err(Error::Intcode(IntcodeError::Halted)) => break,
+                result => { result?; },
+            }
+
+            let ball = (arcade.screen.ball_x, arcade.screen.ball_y);
+            path.push(ball);
+
+            if ball.1 + 1 >= arcade.screen.paddle_y || path.len() >= Self::PREDICTION_FRAMES {
+                break;
+            }
+        }
+
+        Ok((path, arcade.machine.inputs_read()))
+    }
+
+    fn predicted_x(&mut self) -> Result<i64, Error> {
+        if self.screen.ball_y + 1 == self.screen.paddle_y {
+            return Ok(self.screen.ball_x);
+        }
+
+        match self.prediction {
+            Some((arrival, x)) if self.machine.inputs_read() <= arrival => Ok(x),
+            _ => {
+                let (path, arrival) = self.simulate_ball()?;
+                let x = path.last()
+                    .map(|(x, _)| *x)
+                    .unwrap_or(self.screen.ball_x);
+                debug!("autopilot: ball arrives at x={} after joystick read {}", x, arrival);
+                self.prediction = Some((arrival, x));
+                Ok(x)
+            },
+        }
+    }
+
+    fn init_machine(&mut self) {
+        // Initialize joystick position
+        debug!("initialize joystick");
+        self.machine.set_contant_input(JoystickPosition::default().into());
+
+        // Make machine free
+        info!("Hacking coin slot");
+        self.machine.set_data(0, 2);
+    }
+}
+
+
+/// A game played by the autopilot, with one frame per joystick read.
+#[derive(Clone, Debug)]
+pub struct Autoplay {
+    pub program: Program,
+    pub level: Option<Level>,
+}
+
+impl Visualize for Autoplay {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame>> {
+        let mut arcade = Arcade::new(self.program.clone());
+        let loaded = arcade.load_screen()
+            .and_then(|_| self.level.as_ref().map_or(Ok(()), |level| arcade.load_level(level)));
+        if let Err(e) = loaded {
+            warn!("Failed to load the game: {}", e);
+            return Box::new(std::iter::empty());
+        }
+
+        // Whether the game is still running, and `None` after the last frame.
+        let mut playing = Some(true);
+        Box::new(std::iter::from_fn(move || {
+            if !playing? {
+                playing = None;
+                return Some(arcade.screen.to_frame());
+            }
+
+            let frame = arcade.screen.to_frame();
+            playing = match arcade.autopilot().and_then(|_| arcade.wait_frame()) {
+                Ok(_) => Some(true),
+                Err(Error::Intcode(IntcodeError::Halted)) => Some(false),
+                Err(e) => {
+                    warn!("Game crashed: {}", e);
+                    None
+                },
+            };
+            Some(frame)
+        }))
+    }
+}
+
+#[aoc_generator(day13)]
+pub fn input_generator(input: &str) -> Result<Program, crate::Error> {
+    util::init();
+    let input = util::normalize_input(input);
+    Program::parse_input(13, &input)
+}
+
+#[aoc(day13, part1)]
+pub fn solve_part1(program: &Program) -> Result<usize, crate::Error> {
+    let mut arcade = Arcade::new(program.clone());
+
+    info!("Waiting for screen");
+    arcade.load_screen()?;
+    info!("Number of blocks: {}", arcade.screen.num_blocks);
+
+    Ok(arcade.screen.num_blocks)
+}
+
+#[aoc(day13, part2)]
+pub fn solve_part2(program: &Program) -> Result<i64, crate::Error> {
+    let mut arcade = Arcade::new(program.clone());
+
+    info!("Waiting for screen");
+    arcade.load_screen()?;
+
+    let mut i = 0;
+
+    let score = loop {
+        if i % 100 == 0 {
+            info!("Progress: blocks={}, score={}", arcade.screen.num_blocks, arcade.screen.score);
+        }
+
+        match arcade.step() {
+            Err(Error::Intcode(IntcodeError::Halted)) => {
+                break arcade.screen.score;
+            },
+            Err(e) => return Err(e.into()),
+            _ => {},
+        }
+
+        arcade.autopilot()?;
+
+        i += 1;
+    };
+
+    info!("Score: {}", score);
+
+    Ok(score)
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<usize, crate::Error> {
+    solve_part1(&input_generator(input)?)
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<i64, crate::Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Program;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 13;
+
+    fn parse(input: &str) -> Result<Self::Input, crate::Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, crate::Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, crate::Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_blocks() {
+        // Draws a wall, two blocks and the paddle, then halts. It starts with an instruction that still works after the
+        // coin is put into address 0.
+        let program = input_generator(
+            "1,0,0,0,104,0,104,0,104,1,104,1,104,0,104,2,104,2,104,0,104,2,104,3,104,1,104,3,99"
+        ).unwrap();
+        assert_eq!(solve_part1(&program).unwrap(), 2);
+    }
+
+    #[test]
+    fn framebuffer_grows_in_all_directions() {
+        let mut framebuffer = Framebuffer::default();
+        assert_eq!(framebuffer.bounds(), None);
+        assert_eq!(framebuffer.insert((1, 1), Tile::Wall), None);
+        assert_eq!(framebuffer.insert((3, 2), Tile::Block), None);
+        assert_eq!(framebuffer.insert((-1, 0), Tile::Ball), None);
+        assert_eq!(framebuffer.insert((3, 2), Tile::Empty), Some(Tile::Block));
+
+        assert_eq!(framebuffer.bounds(), Some(((-1, 0), (3, 2))));
+        assert_eq!(framebuffer.len(), 3);
+        assert_eq!(framebuffer.get(&(1, 1)), Some(&Tile::Wall));
+        assert_eq!(framebuffer.get(&(0, 1)), None);
+        assert_eq!(framebuffer.get(&(4, 1)), None);
+        assert_eq!(
+            framebuffer.iter().collect_vec(),
+            vec![((-1, 0), Tile::Ball), ((1, 1), Tile::Wall), ((3, 2), Tile::Empty)]
+        );
+    }
+
+    #[test]
+    fn screen_frame() {
+        let mut arcade = Arcade::new(input_generator(
+            "1,0,0,0,104,0,104,0,104,1,104,1,104,0,104,2,104,2,104,0,104,2,104,3,104,1,104,3,99"
+        ).unwrap());
+        arcade.load_screen().unwrap();
+        let frames = arcade.screen.frames().collect_vec();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].to_string(), "#██ \n   |\nScore: 0");
+    }
+
+    #[test]
+    fn parses_level() {
+        let level: Level = "#####\n# X #\n#X █#".parse().unwrap();
+        assert_eq!(level.blocks.into_iter().collect_vec(), vec![(1, 2), (2, 1), (3, 2)]);
+        assert!("#?#".parse::<Level>().is_err());
+    }
+
+    #[test]
+    fn difficulty_names() {
+        for difficulty in &Difficulty::ALL {
+            assert_eq!(difficulty.to_string().parse::<Difficulty>().unwrap(), *difficulty);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use aoc_runner_derive::{aoc, aoc_generator};
+#[cfg(feature="parallel")]
+use rayon::prelude::*;
+
+use crate::intcode::{Program, Machine};
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::Error;
+
+#[aoc_generator(day2)]
+pub fn input_generator(input: &str) -> Result<Program, Error> {
+    util::init();
+    let input = util::normalize_input(input);
+    Program::parse_input(2, &input)
+}
+
+const TARGET: i64 = 19690720;
+
+fn run(program: &Program, noun: i64, verb: i64) -> Result<i64, Error> {
+    let mut machine = Machine::new(program.clone());
+
+    machine.set_data(1, noun);
+    machine.set_data(2, verb);
+
+    machine.run()?;
+
+    Ok(machine.get_data(0))
+}
+
+#[aoc(day2, part1)]
+pub fn solve_part1(program: &Program) -> Result<i64, Error> {
+    run(program, 12, 2)
+}
+
+#[aoc(day2, part2)]
+pub fn solve_part2(program: &Program) -> Result<i64, Error> {
+    // `100 * noun + verb` for nouns and verbs up to 99, which is also the answer.
+    let try_input = |input: i64| -> Result<bool, Error> {
+        Ok(run(program, input / 100, input % 100)? == TARGET)
+    };
+    // Stops at the first match or error, so that we get the same result with and without `parallel`.
+    let is_done = |(_, result): &(i64, Result<bool, Error>)| !matches!(result, Ok(false));
+
+    #[cfg(feature="parallel")]
+    let found = (0 .. 100 * 100).into_par_iter().map(|input| (input, try_input(input))).find_first(is_done);
+    #[cfg(not(feature="parallel"))]
+    let found = (0 .. 100 * 100).map(|input| (input, try_input(input))).find(is_done);
+
+    match found {
+        Some((input, result)) => {
+            result?;
+            info!("Found result: {}, {}", input / 100, input % 100);
+            Ok(input)
+        },
+        None => Err(Error::NoSolution("No noun and verb give 19690720")),
+    }
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<i64, Error> {
+    solve_part1(&input_generator(input)?)
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<i64, Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Program;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 2;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(program: &str) -> Vec<i64> {
+        let mut machine = Machine::new(program.parse().unwrap());
+        machine.run().unwrap();
+        machine.memory()[.. program.split(',').count()].to_vec()
+    }
+
+    #[test]
+    fn example_programs() {
+        assert_eq!(run("1,9,10,3,2,3,11,0,99,30,40,50"), vec![3500, 9, 10, 70, 2, 3, 11, 0, 99, 30, 40, 50]);
+        assert_eq!(run("1,0,0,0,99"), vec![2, 0, 0, 0, 99]);
+        assert_eq!(run("2,3,0,3,99"), vec![2, 3, 0, 6, 99]);
+        assert_eq!(run("2,4,4,5,99,0"), vec![2, 4, 4, 5, 99, 9801]);
+        assert_eq!(run("1,1,1,4,99,5,6,0,99"), vec![30, 1, 1, 4, 2, 5, 6, 0, 99]);
+    }
+
+    #[test]
+    fn finds_the_first_noun_and_verb() {
+        // Adds the values at the noun and verb. Several pairs add up to the target, the first is 0 and 60.
+        let mut program = vec![0; 100];
+        program[.. 5].copy_from_slice(&[1, 0, 0, 0, 99]);
+        program[60] = TARGET - 1;
+        program[61] = 1;
+        let program = program.iter().map(i64::to_string).collect::<Vec<_>>().join(",");
+
+        assert_eq!(solve_part2(&program.parse().unwrap()).unwrap(), 60);
+    }
+}
@@ -0,0 +1,403 @@
+use std::io::BufRead;
+use std::mem;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use aoc_runner_derive::{aoc, aoc_generator};
+use failure::Fail;
+use itertools::Itertools;
+
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::{Error, ParseError};
+
+
+#[derive(Clone, Debug, Fail)]
+pub enum WireError {
+    #[fail(display = "Empty wire segment")]
+    Empty,
+    #[fail(display = "Invalid direction {:?}, expected L, R, U or D", _0)]
+    InvalidDirection(char),
+    #[fail(display = "Invalid length: {}", _0)]
+    InvalidLength(#[cause] ParseIntError),
+}
+
+#[derive(Clone, Debug)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down
+}
+
+#[derive(Clone, Debug)]
+pub struct WireSegmentDescriptor {
+    direction: Direction,
+    length: u64,
+}
+
+impl FromStr for WireSegmentDescriptor {
+    type Err = WireError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let first = s.chars().next()
+            .ok_or(WireError::Empty)?;
+        let direction = match first {
+            'L' => Direction::Left,
+            'R' => Direction::Right,
+            'U' => Direction::Up,
+            'D' => Direction::Down,
+            c => return Err(WireError::InvalidDirection(c))
+        };
+
+        let length = s[first.len_utf8() ..].parse::<u64>()
+            .map_err(WireError::InvalidLength)?;
+
+        Ok(Self {
+            direction,
+            length,
+        })
+    }
+}
+
+type Position = (i64, i64);
+
+/// Where both wires start. They cross there, but that doesn't count.
+const ORIGIN: Position = (0, 0);
+
+#[derive(Clone, Debug)]
+pub struct WireSegment {
+    pub direction: Direction,
+    pub length: u64,
+    pub start: Position,
+    pub total_length: u64,
+}
+
+impl WireSegment {
+    pub fn endpoint(&self) -> Position {
+        let l = self.length as i64;
+
+        let (x, y) = match self.direction {
+            Direction::Left => (-l, 0),
+            Direction::Right => (l, 0),
+            Direction::Up => (0, -l),
+            Direction::Down => (0, l),
+        };
+
+        (self.start.0 + x, self.start.1 + y)
+    }
+
+    fn match_horizontal(&self, x: i64) -> bool {
+        let l = self.length as i64;
+        let mut a = self.start.0;
+        let mut b = a;
+        match self.direction {
+            Direction::Left => a -= l,
+            Direction::Right => b += l,
+            _ => return false,
+        }
+        a <= x && x <= b
+    }
+
+    fn match_vertical(&self, x: i64) -> bool {
+        let l = self.length as i64;
+        let mut a = self.start.1;
+        let mut b = a;
+        match self.direction {
+            Direction::Up => a -= l,
+            Direction::Down => b += l,
+            _ => return false,
+        }
+        a <= x && x <= b
+    }
+
+    pub fn intersects(&self, other: &Self) -> Option<Position> {
+        if self.match_horizontal(other.start.0)
+            && other.match_vertical(self.start.1) {
+            Some((other.start.0, self.start.1))
+        }
+        else if self.match_vertical(other.start.1)
+            && other.match_horizontal(self.start.0) {
+            Some((self.start.0, other.start.1))
+        }
+        else {
+            None
+        }
+    }
+
+    pub fn length_for_point(&self, p: Position) -> u64 {
+        let relative = match self.direction {
+            Direction::Left => self.start.0 - p.0,
+            Direction::Right => p.0 - self.start.0,
+            Direction::Up => self.start.1 - p.1,
+            Direction::Down => p.1 - self.start.1,
+        } as u64;
+
+        self.total_length - self.length + relative
+    }
+}
+
+
+#[derive(Clone, Debug, Default)]
+pub struct Wire {
+    segments: Vec<WireSegment>,
+}
+
+impl Wire {
+    fn push(&mut self, desc: WireSegmentDescriptor) {
+        let (start, total_length) = self.segments.last()
+            .map_or((ORIGIN, 0), |last| (last.endpoint(), last.total_length));
+
+        self.segments.push(WireSegment {
+            direction: desc.direction,
+            length: desc.length,
+            start,
+            total_length: total_length + desc.length,
+        });
+    }
+}
+
+impl FromStr for Wire {
+    type Err = WireError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut wire = Wire::default();
+        for segment in s.split(',') {
+            wire.push(segment.parse()?);
+        }
+        Ok(wire)
+    }
+}
+
+
+
+#[aoc_generator(day3)]
+pub fn input_generator(input: &str) -> Result<Vec<Wire>, Error> {
+    util::init();
+    let input = util::normalize_input(input);
+    let wires = input.lines()
+        .map(|line| {
+            let mut wire = Wire::default();
+            for segment in line.split(',') {
+                wire.push(segment.parse().map_err(|e| ParseError::at(3, &input, segment, e))?);
+            }
+            Ok(wire)
+        })
+        .collect::<Result<Vec<Wire>, ParseError>>()?;
+
+    if wires.len() != 2 {
+        let reason = format!("Expected 2 wires, but got {}", wires.len());
+        let error = match input.lines().nth(2) {
+            Some(extra) => ParseError::at(3, &input, extra, reason),
+            None => ParseError::end(3, &input, reason),
+        };
+        return Err(error.into());
+    }
+    Ok(wires)
+}
+
+/// Like `input_generator`, but reads segment by segment, so that not even a whole wire has to be in memory as text.
+pub fn from_reader<R: BufRead>(reader: R) -> Result<Vec<Wire>, Error> {
+    let mut wires = Vec::new();
+    let mut wire = Wire::default();
+    let mut segment = String::new();
+    // Where we are, and where the third wire starts, if there is one.
+    let (mut line, mut column) = (1, 0);
+    let mut extra = None;
+
+    for byte in reader.bytes().chain(Some(Ok(b'\n'))) {
+        column += 1;
+        match byte? {
+            b'\r' => {},
+            // Blank lines, or the end of the last line.
+            b'\n' if segment.is_empty() && wire.segments.is_empty() => (line, column) = (line + 1, 0),
+            b @ b',' | b @ b'\n' => {
+                let start = column - segment.len();
+                wire.push(segment.parse().map_err(|e| ParseError::new(3, line, start, &segment, e))?);
+                segment.clear();
+                if b == b'\n' {
+                    wires.push(mem::take(&mut wire));
+                    if wires.len() == 3 {
+                        extra = Some(line);
+                    }
+                    (line, column) = (line + 1, 0);
+                }
+            },
+            b => segment.push(b as char),
+        }
+    }
+
+    if wires.len() != 2 {
+        let reason = format!("Expected 2 wires, but got {}", wires.len());
+        return Err(ParseError::new(3, extra.unwrap_or(line), 1, "", reason).into());
+    }
+    Ok(wires)
+}
+
+#[aoc(day3, part1)]
+pub fn solve_part1(wires: &[Wire]) -> Result<u64, Error> {
+    debug!("{:#?}", wires);
+
+    let mut distance = None;
+
+    for (segment_a, segment_b) in wires[0].segments.iter().cartesian_product(wires[1].segments.iter()) {
+        if let Some(intersection) = segment_a.intersects(segment_b).filter(|&p| p != ORIGIN) {
+            let new_distance = (intersection.0.abs() + intersection.1.abs()) as u64;
+
+            debug!("Intersection: {:?} (distance {})", intersection, new_distance);
+
+            if let Some(old_distance) = distance {
+                if new_distance < old_distance {
+                    distance = Some(new_distance);
+                }
+            }
+            else {
+                distance = Some(new_distance);
+            }
+        }
+    }
+
+    distance.ok_or(Error::NoSolution("The wires don't cross"))
+}
+
+#[aoc(day3, part2)]
+pub fn solve_part2(wires: &[Wire]) -> Result<u64, Error> {
+    debug!("{:#?}", wires);
+
+    let mut length = None;
+
+    for (segment_a, segment_b) in wires[0].segments.iter().cartesian_product(wires[1].segments.iter()) {
+        if let Some(intersection) = segment_a.intersects(segment_b).filter(|&p| p != ORIGIN) {
+            let new_length = segment_a.length_for_point(intersection) + segment_b.length_for_point(intersection);
+
+            debug!("New length: {}", new_length);
+
+            if let Some(old_length) = length {
+                if new_length < old_length {
+                    length = Some(new_length);
+                }
+            }
+            else {
+                length = Some(new_length);
+            }
+        }
+    }
+
+    length.ok_or(Error::NoSolution("The wires don't cross"))
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<u64, Error> {
+    solve_part1(&input_generator(input)?)
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<u64, Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Wire>;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 3;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn solve(input: &str) -> (u64, u64) {
+        let wires = input_generator(input).unwrap();
+        (solve_part1(&wires).unwrap(), solve_part2(&wires).unwrap())
+    }
+
+    #[test]
+    fn example_wires() {
+        assert_eq!(solve("R8,U5,L5,D3\nU7,R6,D4,L4"), (6, 30));
+        assert_eq!(solve("R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83"), (159, 610));
+        assert_eq!(
+            solve("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\nU98,R91,D20,R16,D67,R40,U7,R15,U6,R7"),
+            (135, 410)
+        );
+    }
+
+    #[test]
+    fn reads_wires() {
+        let input = "R75,D30,R83,U83,L12,D49,R71,U7,L72\r\nU62,R66,U55,R34,D71,R55,D58,R83\n";
+        let wires = from_reader(input.as_bytes()).unwrap();
+        assert_eq!((solve_part1(&wires).unwrap(), solve_part2(&wires).unwrap()), (159, 610));
+        assert!(from_reader("R8,U5,\nU7,R6".as_bytes()).is_err());
+        assert!(from_reader("R8,U5".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn errors_point_at_the_segment() {
+        let error = input_generator("R8,U5,L5,D3\nU7,X6,D4,L4").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Day 3: Invalid input in line 2, column 4: Invalid direction 'X', expected L, R, U or D at \"X6\""
+        );
+        let error = from_reader("R8,U5,L5,D3\r\nU7,R6,D4x,L4".as_bytes()).unwrap_err();
+        assert!(matches!(error, Error::Parse(ParseError { line: 2, column: 7, .. })), "{}", error);
+    }
+
+    #[test]
+    fn needs_two_wires() {
+        assert!(input_generator("R8,U5,L5,D3").is_err());
+    }
+
+    fn segment(directions: [Direction; 2]) -> impl Strategy<Value = WireSegment> {
+        (proptest::sample::select(directions.to_vec()), 0u64 .. 20, -20i64 .. 20, -20i64 .. 20)
+            .prop_map(|(direction, length, x, y)| WireSegment {
+                direction,
+                length,
+                start: (x, y),
+                total_length: length,
+            })
+    }
+
+    fn points(segment: &WireSegment) -> HashSet<Position> {
+        let (dx, dy) = match segment.direction {
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+        };
+        (0 ..= segment.length as i64)
+            .map(|i| (segment.start.0 + i * dx, segment.start.1 + i * dy))
+            .collect()
+    }
+
+    proptest! {
+        // Collinear segments may overlap in many points, which `intersects` can't report. Wires only cross at right
+        // angles, so only perpendicular segments are compared.
+        #[test]
+        fn intersection_matches_points(
+            horizontal in segment([Direction::Left, Direction::Right]),
+            vertical in segment([Direction::Up, Direction::Down]),
+        ) {
+            let expected = points(&horizontal).intersection(&points(&vertical)).next().copied();
+            prop_assert_eq!(horizontal.intersects(&vertical), expected);
+            prop_assert_eq!(vertical.intersects(&horizontal), expected);
+        }
+    }
+}
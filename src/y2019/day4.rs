@@ -0,0 +1,125 @@
+use std::ops::RangeInclusive;
+
+use aoc_runner_derive::{aoc, aoc_generator};
+#[cfg(feature="parallel")]
+use rayon::prelude::*;
+
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::{Error, ParseError};
+
+
+#[aoc_generator(day4)]
+pub fn input_generator(input: &str) -> Result<RangeInclusive<u64>, Error> {
+    util::init();
+    let input = util::normalize_input(input);
+    let (start, end) = input.split_once('-')
+        .ok_or_else(|| ParseError::at(4, &input, &input, "Expected a range like 123456-654321"))?;
+    let parse = |bound: &str| bound.parse().map_err(|e| ParseError::at(4, &input, bound, e));
+
+    Ok(RangeInclusive::new(parse(start)?, parse(end)?))
+}
+
+fn to_radix(mut x: u64) -> [u8; 6] {
+    let mut radix = [0; 6];
+
+    for i in 0 .. 6 {
+        radix[5 - i] = (x % 10) as u8;
+        x /= 10;
+    }
+
+    radix
+}
+
+fn is_match(num: u64) -> bool {
+    let radix = to_radix(num);
+
+    let mut found_repeating = false;
+    let mut is_increasing = true;
+    let mut repetitions = 0;
+
+    for i in 1..6 {
+        if radix[i - 1] == radix[i] {
+            repetitions += 1;
+        }
+        else {
+            if repetitions == 1 {
+                found_repeating = true;
+            }
+            repetitions = 0;
+        }
+
+        if radix[i - 1] > radix[i] {
+            is_increasing = false;
+        }
+    }
+    if repetitions == 1 {
+        found_repeating = true;
+    }
+
+    if !found_repeating || !is_increasing {
+        return false;
+    }
+
+    debug!("Found match: {}", num);
+    true
+}
+
+#[aoc(day4, part1)]
+pub fn solve_part1(range: &RangeInclusive<u64>) -> u64 {
+    debug!("Range: {} - {}", range.start(), range.end());
+
+    #[cfg(feature="parallel")]
+    let num_matches = range.clone().into_par_iter().filter(|num| is_match(*num)).count();
+    #[cfg(not(feature="parallel"))]
+    let num_matches = range.clone().filter(|num| is_match(*num)).count();
+
+    num_matches as u64
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<u64, Error> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = RangeInclusive<u64>;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 4;
+    const PARTS: usize = 1;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part1(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_valid(password: u64) -> bool {
+        solve_part1(&(password ..= password)) == 1
+    }
+
+    #[test]
+    fn example_passwords() {
+        assert!(is_valid(112233));
+        assert!(!is_valid(123444));
+        assert!(is_valid(111122));
+        assert!(!is_valid(223450));
+        assert!(!is_valid(123789));
+    }
+
+    #[test]
+    fn parses_range() {
+        assert_eq!(input_generator("123456-654321\n").unwrap(), 123456 ..= 654321);
+        assert!(input_generator("123456").is_err());
+    }
+}
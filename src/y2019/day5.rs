@@ -0,0 +1,130 @@
+use aoc_runner_derive::{aoc, aoc_generator};
+
+use crate::intcode::{Program, Machine};
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::Error;
+
+
+#[aoc_generator(day5)]
+pub fn input_generator(input: &str) -> Result<Program, Error> {
+    util::init();
+    let input = util::normalize_input(input);
+    Program::parse_input(5, &input)
+}
+
+#[aoc(day5, part1)]
+pub fn solve_part1(program: &Program) -> Result<i64, Error> {
+    let mut machine = Machine::new(program.clone());
+
+    machine.push_input(1);
+
+    machine.run()?;
+
+    let output = machine.get_output();
+    let (&diagnostic_code, checks) = output.split_last()
+        .ok_or(Error::NoSolution("Expected diagnostics code"))?;
+    for (i, x) in checks.iter().enumerate() {
+        debug!("Check #{}: {}", i, x);
+    }
+    debug!("Diagnostic code: {}", diagnostic_code);
+
+    if checks.iter().any(|&x| x != 0) {
+        return Err(Error::NoSolution("Some checks failed"));
+    }
+    Ok(diagnostic_code)
+}
+
+#[aoc(day5, part2)]
+pub fn solve_part2(program: &Program) -> Result<i64, Error> {
+    let mut machine = Machine::new(program.clone());
+
+    machine.push_input(5);
+
+    machine.run()?;
+
+    let diagnostic_code = machine.pop_output().ok_or(Error::NoSolution("Expected diagnostics code"))?;
+    debug!("Diagnostic code: {}", diagnostic_code);
+
+    Ok(diagnostic_code)
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<i64, Error> {
+    solve_part1(&input_generator(input)?)
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<i64, Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Program;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 5;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(program: &str, input: i64) -> Vec<i64> {
+        let mut machine = Machine::new(program.parse().unwrap());
+        machine.push_input(input);
+        machine.run().unwrap();
+        machine.get_output()
+    }
+
+    #[test]
+    fn parameter_modes() {
+        let mut machine = Machine::new("1002,4,3,4,33".parse().unwrap());
+        machine.run().unwrap();
+        assert_eq!(machine.get_data(4), 99);
+
+        let mut machine = Machine::new("1101,100,-1,4,0".parse().unwrap());
+        machine.run().unwrap();
+        assert_eq!(machine.get_data(4), 99);
+    }
+
+    #[test]
+    fn comparisons() {
+        for program in &["3,9,8,9,10,9,4,9,99,-1,8", "3,3,1108,-1,8,3,4,3,99"] {
+            assert_eq!(run(program, 8), vec![1]);
+            assert_eq!(run(program, 7), vec![0]);
+        }
+        for program in &["3,9,7,9,10,9,4,9,99,-1,8", "3,3,1107,-1,8,3,4,3,99"] {
+            assert_eq!(run(program, 7), vec![1]);
+            assert_eq!(run(program, 8), vec![0]);
+        }
+    }
+
+    #[test]
+    fn jumps() {
+        for program in &["3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9", "3,3,1105,-1,9,1101,0,0,12,4,12,99,1"] {
+            assert_eq!(run(program, 0), vec![0]);
+            assert_eq!(run(program, 5), vec![1]);
+        }
+
+        let program = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,\
+                       1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+        assert_eq!(run(program, 7), vec![999]);
+        assert_eq!(run(program, 8), vec![1000]);
+        assert_eq!(run(program, 9), vec![1001]);
+    }
+}
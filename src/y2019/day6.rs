@@ -0,0 +1,281 @@
+use std::str::FromStr;
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+use std::rc::Rc;
+
+use aoc_runner_derive::{aoc, aoc_generator};
+use failure::Fail;
+
+use crate::memo::Memoized;
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::search;
+use crate::util;
+use crate::ParseError;
+
+
+#[derive(Clone, Debug, Fail)]
+pub enum Error {
+    #[fail(display = "Invalid orbit: {}", _0)]
+    ParseError(String),
+    #[fail(display = "Orbits around {} form a cycle", _0)]
+    Cycle(String),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OrbitMap {
+    orbits: HashMap<String, Rc<Orbit>>,
+    satellites: HashMap<String, Vec<Rc<Orbit>>>,
+}
+
+impl OrbitMap {
+    /// The number of direct and indirect orbits. An object's orbits are the ones of the object it orbits, plus one.
+    pub fn compute_checksum(&self) -> usize {
+        let mut depths = Memoized::new();
+        let depth = |depth: &mut dyn FnMut(&String) -> usize, object: &String| {
+            self.orbits.get(object).map_or(0, |orbit| 1 + depth(&orbit.around))
+        };
+        self.orbits.keys()
+            .map(|object| depths.get_recursive(object, &depth))
+            .sum()
+    }
+
+    pub fn compute_checksum_for_orbit(&self, mut orbit: Rc<Orbit>) -> usize {
+        let mut orbits = 1;
+        while let Some(o) = self.orbits.get(&orbit.around) {
+            orbit = Rc::clone(&o);
+            orbits += 1;
+        }
+        orbits
+    }
+
+    pub fn compute_path_to_com(&self, from: &str) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut current = from.to_owned();
+
+        while let Some(o) = self.orbits.get(&current) {
+            path.push(current);
+            current = o.around.to_owned();
+        }
+
+        path
+    }
+
+    fn insert(&mut self, orbit: Orbit) {
+        let orbit = Rc::new(orbit);
+        self.orbits.insert(orbit.object.clone(), Rc::clone(&orbit));
+        self.satellites.entry(orbit.around.clone())
+            .or_default()
+            .push(orbit)
+    }
+
+    /// Everything on a cycle of orbits would orbit forever.
+    fn check(self) -> Result<Self, Error> {
+        match self.find_cycle() {
+            Some(object) => Err(Error::Cycle(object)),
+            None => Ok(self),
+        }
+    }
+
+    /// An object on a cycle of orbits, if there is one.
+    fn find_cycle(&self) -> Option<String> {
+        let mut acyclic = HashSet::new();
+        for start in self.orbits.keys() {
+            let mut path = HashSet::new();
+            let mut current = start;
+            while let Some(orbit) = self.orbits.get(current) {
+                if acyclic.contains(current) {
+                    break;
+                }
+                if !path.insert(current) {
+                    return Some(current.clone());
+                }
+                current = &orbit.around;
+            }
+            acyclic.extend(path);
+        }
+        None
+    }
+
+    /// The object `object` orbits and its satellites.
+    fn neighbors(&self, object: &str) -> Vec<String> {
+        let around = self.orbits.get(object).map(|orbit| orbit.around.clone());
+        let satellites = self.satellites.get(object)
+            .into_iter()
+            .flatten()
+            .map(|orbit| orbit.object.clone());
+        around.into_iter().chain(satellites).collect()
+    }
+
+    /// The objects on the shortest path between two objects, including both.
+    pub fn compute_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        search::bfs(from.to_owned(), |object| self.neighbors(object), |object| object == to)
+            .map(|(path, _)| path)
+    }
+}
+
+impl FromStr for OrbitMap {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut map = OrbitMap::default();
+        for line in s.lines() {
+            map.insert(line.parse()?);
+        }
+        map.check()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Orbit {
+    object: String,
+    around: String,
+}
+
+impl FromStr for Orbit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split(")").collect::<Vec<&str>>();
+        if parts.len() != 2 {
+            return Err(Error::ParseError(s.to_owned()));
+        }
+        Ok(Orbit {
+            object: parts[1].to_owned(),
+            around: parts[0].to_owned(),
+        })
+    }
+}
+
+#[aoc_generator(day6)]
+pub fn input_generator(input: &str) -> Result<OrbitMap, crate::Error> {
+    util::init();
+    let input = util::normalize_input(input);
+    input.parse().map_err(|e| input_error(&input, e).into())
+}
+
+fn input_error(input: &str, e: Error) -> ParseError {
+    match e {
+        Error::ParseError(line) => {
+            let line = input.lines().find(|other| *other == line).unwrap_or_default();
+            ParseError::at(6, input, line, "Expected an orbit like A)B")
+        },
+        Error::Cycle(ref object) => {
+            // The line that puts the object into its orbit on the cycle. Later orbits replace earlier ones.
+            let line = input.lines().rfind(|line| line.split(')').nth(1) == Some(object.as_str()));
+            ParseError::at(6, input, line.unwrap_or(object), &e)
+        },
+    }
+}
+
+/// Like `input_generator`, but reads line by line.
+pub fn from_reader<R: BufRead>(reader: R) -> Result<OrbitMap, crate::Error> {
+    let mut map = OrbitMap::default();
+    // Only the line numbers, to say where a cycle is without keeping the input.
+    let mut lines = HashMap::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let orbit: Orbit = line.parse().map_err(|e| input_error(&line, e).below(i))?;
+        lines.insert(orbit.object.clone(), i + 1);
+        map.insert(orbit);
+    }
+    map.check().map_err(|e| match e {
+        Error::Cycle(ref object) => ParseError::new(6, lines[object], 1, "", &e).into(),
+        e => input_error("", e).into(),
+    })
+}
+
+#[aoc(day6, part1)]
+pub fn solve_part1(map: &OrbitMap) -> usize {
+    let checksum = map.compute_checksum();
+    debug!("Checksum: {}", checksum);
+
+    checksum
+}
+
+#[aoc(day6, part2)]
+pub fn solve_part2(map: &OrbitMap) -> Result<usize, crate::Error> {
+    if !map.orbits.contains_key("YOU") || !map.orbits.contains_key("SAN") {
+        return Err(crate::Error::NoSolution("YOU and SAN must both be in orbit"));
+    }
+
+    let path = map.compute_path("YOU", "SAN")
+        .ok_or(crate::Error::NoSolution("YOU and SAN aren't connected"))?;
+
+    debug!("Path:");
+    for transfer in &path {
+        debug!("{}", transfer);
+    }
+
+    // We transfer from the object YOU orbits to the one SAN orbits, so YOU and SAN themselves don't count.
+    path.len().checked_sub(3).ok_or(crate::Error::NoSolution("YOU and SAN orbit each other"))
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<usize, crate::Error> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<usize, crate::Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = OrbitMap;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 6;
+
+    fn parse(input: &str) -> Result<Self::Input, crate::Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, crate::Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, crate::Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L";
+
+    #[test]
+    fn counts_orbits() {
+        let map = input_generator(EXAMPLE).unwrap();
+        assert_eq!(map.compute_path_to_com("D").len(), 3);
+        assert_eq!(map.compute_path_to_com("L").len(), 7);
+        assert_eq!(map.compute_path_to_com("COM").len(), 0);
+        assert_eq!(solve_part1(&map), 42);
+    }
+
+    #[test]
+    fn transfers_to_santa() {
+        let map = input_generator(&format!("{}\nK)YOU\nI)SAN", EXAMPLE)).unwrap();
+        assert_eq!(solve_part2(&map).unwrap(), 4);
+    }
+
+    #[test]
+    fn santa_must_be_in_orbit() {
+        let map = input_generator(EXAMPLE).unwrap();
+        assert!(solve_part2(&map).is_err());
+    }
+
+    #[test]
+    fn invalid_maps_are_errors() {
+        assert!(part1("COM)A\nA)B\nB)A").is_err());
+        assert!(from_reader("COM)A\nA)B\nB)A".as_bytes()).is_err());
+        let error = from_reader("COM)A\nAB".as_bytes()).unwrap_err();
+        assert!(matches!(error, crate::Error::Parse(ParseError { line: 2, column: 1, .. })), "{}", error);
+        let error = part1("COM)A\nA)B\nB)A").unwrap_err();
+        assert!(matches!(error, crate::Error::Parse(ParseError { line: 2 ..= 3, .. })), "{}", error);
+        assert!(part2("COM)YOU\nYOU)SAN").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use std::ops::Range;
+
+use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
+#[cfg(feature="parallel")]
+use rayon::prelude::*;
+
+use crate::intcode::{Program, Machine, Error as IntcodeError};
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::Error;
+
+
+type PhaseSettings = [u8; 5];
+
+struct Circuit<'p> {
+    program: &'p Program,
+}
+
+impl<'p> Circuit<'p> {
+    pub fn new(program: &'p Program) -> Self {
+        Self {
+            program
+        }
+    }
+
+    pub fn run_amplifier(&self, amplifier: &mut Machine, signal: i64) -> Result<Option<i64>, IntcodeError> {
+        amplifier.push_input(signal);
+        Ok(loop {
+            if amplifier.is_halted() {
+                break None;
+            }
+            amplifier.step()?;
+            if let Some(output) = amplifier.pop_output() {
+                break Some(output)
+            }
+        })
+    }
+
+    pub fn run_circuit(&self, phase_settings: &PhaseSettings, loopback: bool) -> Result<i64, IntcodeError> {
+        let mut amplifiers = [
+            Machine::new(self.program.clone()),
+            Machine::new(self.program.clone()),
+            Machine::new(self.program.clone()),
+            Machine::new(self.program.clone()),
+            Machine::new(self.program.clone()),
+        ];
+        let mut signal = 0;
+        let mut done = false;
+
+        for i in 0 .. 5 {
+            amplifiers[i].push_input(phase_settings[i] as i64);
+        }
+
+        while !done {
+            for i in 0 .. 5 {
+                if let Some(output) = self.run_amplifier(&mut amplifiers[i], signal)? {
+                    debug!("Amplifier #{}: input={}, output={}", i, signal, output);
+                    signal = output;
+                }
+                else {
+                    debug!("Amplifier #{} halted", i);
+                    done = true;
+                }
+            }
+            if !loopback {
+                done = true;
+            }
+        }
+
+        Ok(signal)
+    }
+}
+
+#[aoc_generator(day7)]
+pub fn input_generator(input: &str) -> Result<Program, Error> {
+    util::init();
+    let input = util::normalize_input(input);
+    Program::parse_input(7, &input)
+}
+
+pub fn try_phase_settings(program: &Program, phase_settings_range: Range<u8>, loopback: bool) -> Result<i64, Error> {
+    let circuit = Circuit::new(program);
+    let try_permutation = |perm: &Vec<u8>| {
+        let mut phase_settings: PhaseSettings = [0; 5];
+        phase_settings.copy_from_slice(perm);
+
+        debug!("Trying phase settings {:?}", phase_settings);
+        let output = circuit.run_circuit(&phase_settings, loopback);
+        debug!("Circuit output: {:?}", output);
+        output
+    };
+
+    let permutations = phase_settings_range.permutations(5).collect_vec();
+    // Collected in order, so that the first error is the same with and without `parallel`.
+    #[cfg(feature="parallel")]
+    let outputs: Vec<_> = permutations.par_iter().map(try_permutation).collect();
+    #[cfg(not(feature="parallel"))]
+    let outputs: Vec<_> = permutations.iter().map(try_permutation).collect();
+
+    let mut best_output = 0;
+    for output in outputs {
+        best_output = best_output.max(output?);
+    }
+
+    debug!("Best thruster output: {}", best_output);
+
+    Ok(best_output)
+}
+
+#[aoc(day7, part1)]
+pub fn solve_part1(program: &Program) -> Result<i64, Error> {
+    try_phase_settings(program, 0 .. 5, false)
+}
+
+#[aoc(day7, part2)]
+pub fn solve_part2(program: &Program) -> Result<i64, Error> {
+    try_phase_settings(program, 5 .. 10, true)
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<i64, Error> {
+    solve_part1(&input_generator(input)?)
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<i64, Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Program;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 7;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_signal(program: &str, loopback: bool) -> i64 {
+        let range = if loopback { 5 .. 10 } else { 0 .. 5 };
+        try_phase_settings(&program.parse().unwrap(), range, loopback).unwrap()
+    }
+
+    #[test]
+    fn amplifiers_in_series() {
+        assert_eq!(max_signal("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0", false), 43210);
+        assert_eq!(
+            max_signal("3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0", false),
+            54321
+        );
+        assert_eq!(
+            max_signal(
+                "3,31,3,32,1002,32,10,32,1001,31,-2,31,1007,31,0,33,1002,33,7,33,1,33,31,31,1,32,31,31,4,31,99,0,0,0",
+                false
+            ),
+            65210
+        );
+    }
+
+    #[test]
+    fn amplifiers_with_feedback() {
+        assert_eq!(
+            max_signal(
+                "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+                true
+            ),
+            139629729
+        );
+        assert_eq!(
+            max_signal(
+                "3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,-5,54,1105,1,12,1,53,54,53,1008,\
+                 54,0,55,1001,55,1,55,2,53,55,53,4,53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10",
+                true
+            ),
+            18216
+        );
+    }
+}
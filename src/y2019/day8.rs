@@ -0,0 +1,311 @@
+use std::str::FromStr;
+use std::convert::{TryFrom, TryInto};
+use std::io::BufRead;
+use std::mem;
+
+use aoc_runner_derive::{aoc, aoc_generator};
+use failure::Fail;
+
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::Error;
+
+
+#[derive(Clone, Debug, Fail)]
+pub enum ParseError {
+    #[fail(display = "Invalid digit: {}", _0)]
+    InvalidDigit(char),
+    #[fail(display = "Invalid pixel: {}", _0)]
+    InvalidPixel(u32),
+    #[fail(display = "Incomplete layer of {} pixels", _0)]
+    IncompleteLayer(usize),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Pixel {
+    Black,
+    White,
+    Transparent,
+}
+
+impl TryFrom<u32> for Pixel {
+    type Error = ParseError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Pixel::Black),
+            1 => Ok(Pixel::White),
+            2 => Ok(Pixel::Transparent),
+            _ => Err(ParseError::InvalidPixel(value))
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Layer {
+    pub pixels: Vec<Pixel>,
+}
+
+impl Layer {
+    pub fn count_pixels(&self, digit: Pixel) -> usize {
+        self.pixels.iter()
+            .filter(|px| **px == digit)
+            .count()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Display<'l> {
+    width: usize,
+    height: usize,
+    layer: &'l Layer,
+}
+
+impl<'l> Display<'l> {
+    const CHAR_WHITE: char = '█';
+    const CHAR_BLACK: char = ' ';
+    const CHAR_TRANSPARENT: char = '░';
+}
+
+impl<'l> std::fmt::Display for Display<'l> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        for y in 0 .. self.height {
+            for x in 0 .. self.width {
+                let px = match self.layer.pixels.get(y * self.width + x).unwrap() {
+                    Pixel::Black => Self::CHAR_BLACK,
+                    Pixel::White => Self::CHAR_WHITE,
+                    Pixel::Transparent => Self::CHAR_TRANSPARENT,
+                };
+                write!(f, "{}", px)?;
+            }
+            writeln!(f, "")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpaceImage {
+    pub width: usize,
+    pub height: usize,
+    pub layers: Vec<Layer>,
+}
+
+impl SpaceImage {
+    pub fn merge_layers(&self) -> Option<Layer> {
+        let mut layer_iter = self.layers.iter();
+
+        let mut merged = layer_iter.next()?.clone();
+        let mut done;
+
+        while let Some(layer) = layer_iter.next() {
+            done = true;
+            for (merged_px, px) in merged.pixels.iter_mut().zip(layer.pixels.iter()) {
+                if *merged_px == Pixel::Transparent {
+                    *merged_px = *px;
+                    if *px == Pixel::Transparent {
+                        done = false
+                    }
+                }
+            }
+            if done {
+                break;
+            }
+        }
+
+        Some(merged)
+    }
+
+    pub fn display<'l>(&self, layer: &'l Layer) -> Display<'l> {
+        Display {
+            width: self.width,
+            height: self.height,
+            layer,
+        }
+    }
+}
+
+fn to_radix(s: &str) -> Result<Vec<Pixel>, ParseError> {
+    s.chars()
+        .map(|c| {
+            Ok(c.to_digit(10)
+                .ok_or_else(|| ParseError::InvalidDigit(c))?
+                .try_into()?)
+        })
+        .collect::<Result<Vec<Pixel>, ParseError>>()
+}
+
+impl SpaceImage {
+    /// Parses an image of any size. `from_str` parses the 25x6 images of the puzzle.
+    pub fn with_size(s: &str, width: usize, height: usize) -> Result<Self, ParseError> {
+        let mut layers = Vec::new();
+        let mut current = s;
+
+        while current.len() >= width * height {
+            let (layer, rest) = current.split_at(width * height);
+            current = rest;
+
+            let pixels = to_radix(layer)?;
+            assert_eq!(pixels.len(), width * height);
+
+            layers.push(Layer {
+                pixels,
+            })
+        }
+
+        if current.is_empty() {
+            Ok(SpaceImage {
+                width,
+                height,
+                layers,
+            })
+        }
+        else {
+            Err(ParseError::IncompleteLayer(current.len()))
+        }
+    }
+
+    /// Like `with_size`, but reads layer by layer. Whitespace, like the final newline, is skipped.
+    pub fn from_reader_with_size<R: BufRead>(reader: R, width: usize, height: usize) -> Result<Self, Error> {
+        let mut layers = Vec::new();
+        let mut pixels = Vec::with_capacity(width * height);
+        let (mut line, mut column) = (1, 0);
+
+        for byte in reader.bytes() {
+            let c = byte? as char;
+            column += 1;
+            if c == '\n' {
+                (line, column) = (line + 1, 0);
+            }
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+            let pixel = c.to_digit(10)
+                .ok_or(ParseError::InvalidDigit(c))
+                .and_then(Pixel::try_from)
+                .map_err(|e| crate::ParseError::new(8, line, column, &c.to_string(), e))?;
+            pixels.push(pixel);
+
+            if pixels.len() == width * height {
+                layers.push(Layer {
+                    pixels: mem::replace(&mut pixels, Vec::with_capacity(width * height)),
+                });
+            }
+        }
+
+        if !pixels.is_empty() {
+            let e = ParseError::IncompleteLayer(pixels.len());
+            return Err(crate::ParseError::new(8, line, column + 1, "", e).into());
+        }
+        Ok(SpaceImage {
+            width,
+            height,
+            layers,
+        })
+    }
+}
+
+impl FromStr for SpaceImage {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::with_size(s, 25, 6)
+    }
+}
+
+#[aoc_generator(day8)]
+pub fn input_generator(input: &str) -> Result<SpaceImage, Error> {
+    let input = util::normalize_input(input);
+    input.parse().map_err(|e| {
+        let fragment = match e {
+            // The first pixel that isn't one, since we parse from the start.
+            ParseError::InvalidDigit(_) | ParseError::InvalidPixel(_) => input.char_indices()
+                .find(|(_, c)| !('0' ..= '2').contains(c))
+                .map_or("", |(i, c)| &input[i .. i + c.len_utf8()]),
+            ParseError::IncompleteLayer(n) => &input[input.len() - n ..],
+        };
+        crate::ParseError::at(8, &input, fragment, e).into()
+    })
+}
+
+/// Like `input_generator`, but reads layer by layer.
+pub fn from_reader<R: BufRead>(reader: R) -> Result<SpaceImage, Error> {
+    SpaceImage::from_reader_with_size(reader, 25, 6)
+}
+
+#[aoc(day8, part1)]
+pub fn solve_part1(image: &SpaceImage) -> Result<usize, Error> {
+    let layer = image.layers.iter()
+        .min_by_key(|layer| layer.count_pixels(Pixel::Black))
+        .ok_or(Error::NoSolution("Image has no layers"))?;
+
+    Ok(layer.count_pixels(Pixel::White) * layer.count_pixels(Pixel::Transparent))
+}
+
+#[aoc(day8, part2)]
+pub fn solve_part2(image: &SpaceImage) -> Result<String, Error> {
+    let merged = image.merge_layers().ok_or(Error::NoSolution("Image has no layers"))?;
+    Ok(format!("Image:\n{}", image.display(&merged)))
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<usize, Error> {
+    solve_part1(&input_generator(input)?)
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<String, Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = SpaceImage;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 8;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_layers() {
+        let image = SpaceImage::with_size("012012112200", 3, 2).unwrap();
+        assert_eq!(image.layers.len(), 2);
+        assert_eq!(image.layers[0].count_pixels(Pixel::Black), 2);
+        assert_eq!(image.layers[1].count_pixels(Pixel::White), 2);
+        assert!(SpaceImage::with_size("0120121", 3, 2).is_err());
+    }
+
+    #[test]
+    fn reads_layers() {
+        let image = SpaceImage::from_reader_with_size("012012\n112200\n".as_bytes(), 3, 2).unwrap();
+        assert_eq!(image.layers.len(), 2);
+        assert_eq!(image.layers[1].count_pixels(Pixel::White), 2);
+        assert!(SpaceImage::from_reader_with_size("0120121".as_bytes(), 3, 2).is_err());
+        assert!(SpaceImage::from_reader_with_size("012x12".as_bytes(), 3, 2).is_err());
+    }
+
+    #[test]
+    fn merges_layers() {
+        let image = SpaceImage::with_size("0222112222120000", 2, 2).unwrap();
+        let merged = image.merge_layers().unwrap();
+        assert_eq!(merged.pixels, vec![Pixel::Black, Pixel::White, Pixel::White, Pixel::Black]);
+        assert_eq!(image.display(&merged).to_string(), " █\n█ \n");
+    }
+}
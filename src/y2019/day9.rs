@@ -0,0 +1,99 @@
+use aoc_runner_derive::{aoc, aoc_generator};
+
+use crate::intcode::{Program, Machine};
+use crate::registry::{Answer, IntoAnswer, Solution};
+use crate::util;
+use crate::Error;
+
+
+#[aoc_generator(day9)]
+pub fn input_generator(input: &str) -> Result<Program, Error> {
+    util::init();
+    let input = util::normalize_input(input);
+    Program::parse_input(9, &input)
+}
+
+#[aoc(day9, part1)]
+pub fn solve_part1(program: &Program) -> Result<i64, Error> {
+    let mut machine = Machine::new(program.clone());
+
+    machine.push_input(1);
+
+    machine.run()?;
+
+    let outputs = machine.get_output();
+
+    if outputs.len() > 1 {
+        debug!("Some checks failed:");
+        for (i, output) in outputs.iter().enumerate() {
+            debug!("Output #{}: {:?}", i, output);
+        }
+        Err(Error::NoSolution("Some checks failed"))
+    }
+    else {
+        outputs.first().copied().ok_or(Error::NoSolution("Expected BOOST keycode"))
+    }
+}
+
+#[aoc(day9, part2)]
+pub fn solve_part2(program: &Program) -> Result<i64, Error> {
+    let mut machine = Machine::new(program.clone());
+    machine.push_input(2);
+    machine.run()?;
+    machine.pop_output().ok_or(Error::NoSolution("Expected coordinates"))
+}
+
+/// Parses the input and solves part 1.
+pub fn part1(input: &str) -> Result<i64, Error> {
+    solve_part1(&input_generator(input)?)
+}
+
+/// Parses the input and solves part 2.
+pub fn part2(input: &str) -> Result<i64, Error> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Program;
+
+    const YEAR: u32 = super::YEAR;
+    const DAY: u32 = 9;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part1(input).into_answer()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, Error> {
+        solve_part2(input).into_answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(program: &str) -> Vec<i64> {
+        let mut machine = Machine::new(program.parse().unwrap());
+        machine.run().unwrap();
+        machine.get_output()
+    }
+
+    #[test]
+    fn quine() {
+        let program = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        let expected: Vec<i64> = program.split(',').map(|x| x.parse().unwrap()).collect();
+        assert_eq!(run(program), expected);
+    }
+
+    #[test]
+    fn large_numbers() {
+        assert_eq!(run("1102,34915192,34915192,7,4,7,99,0"), vec![1219070632396864]);
+        assert_eq!(run("104,1125899906842624,99"), vec![1125899906842624]);
+    }
+}
@@ -0,0 +1,359 @@
+//! Plays whole games of day 13 with the autopilot, replays and custom levels, without a window or the puzzle input. The
+//! game is a small breakout written below in a tiny assembler, since the puzzle's own program can't be bundled.
+
+use std::collections::HashMap;
+
+use aoc_2019::intcode::{Error as IntcodeError, Program};
+use aoc_2019::y2019::day13::{Arcade, Difficulty, Error, InputLog, Level, Tile};
+
+
+const LEVEL: [&str; 8] = [
+    "#########",
+    "#.......#",
+    "#.XXXXX.#",
+    "#.XX.XX.#",
+    "#.......#",
+    "#.......#",
+    "#.......#",
+    "#.......#",
+];
+const WIDTH: i64 = LEVEL[0].len() as i64;
+/// The paddle moves in the last row. A ball below it is lost.
+const PADDLE_Y: i64 = LEVEL.len() as i64 - 1;
+
+/// Enough for the autopilot to clear the level a few times over.
+const MAX_FRAMES: usize = 10_000;
+
+#[derive(Clone, Copy)]
+enum Arg {
+    Imm(i64),
+    /// The value at a label, i.e. a variable.
+    Var(&'static str),
+    /// The address of a label, e.g. to jump to.
+    Addr(&'static str),
+    /// The value at an address.
+    At(i64),
+}
+
+use Arg::*;
+
+/// Assembles intcode with labels for jumps and variables.
+#[derive(Default)]
+struct Asm {
+    code: Vec<i64>,
+    labels: HashMap<&'static str, i64>,
+    fixups: Vec<(usize, &'static str)>,
+}
+
+impl Asm {
+    fn label(&mut self, name: &'static str) {
+        self.labels.insert(name, self.code.len() as i64);
+    }
+
+    fn data(&mut self, name: &'static str, values: &[i64]) {
+        self.label(name);
+        self.code.extend_from_slice(values);
+    }
+
+    fn op(&mut self, opcode: i64, args: &[Arg]) {
+        let modes = args.iter().enumerate()
+            .filter(|(_, arg)| matches!(arg, Imm(_) | Addr(_)))
+            .map(|(i, _)| 10i64.pow(i as u32 + 2))
+            .sum::<i64>();
+        self.code.push(opcode + modes);
+        for arg in args {
+            match *arg {
+                Imm(value) | At(value) => self.code.push(value),
+                Var(name) | Addr(name) => {
+                    self.fixups.push((self.code.len(), name));
+                    self.code.push(0);
+                },
+            }
+        }
+    }
+
+    fn add(&mut self, a: Arg, b: Arg, to: &'static str) {
+        self.op(1, &[a, b, Var(to)]);
+    }
+
+    fn mul(&mut self, a: Arg, b: Arg, to: &'static str) {
+        self.op(2, &[a, b, Var(to)]);
+    }
+
+    fn set(&mut self, to: &'static str, a: Arg) {
+        self.add(a, Imm(0), to);
+    }
+
+    fn eq(&mut self, a: Arg, b: Arg, to: &'static str) {
+        self.op(8, &[a, b, Var(to)]);
+    }
+
+    fn input(&mut self, to: &'static str) {
+        self.op(3, &[Var(to)]);
+    }
+
+    fn output(&mut self, args: &[Arg]) {
+        for arg in args {
+            self.op(4, &[*arg]);
+        }
+    }
+
+    fn jnz(&mut self, a: Arg, label: &'static str) {
+        self.op(5, &[a, Addr(label)]);
+    }
+
+    fn jz(&mut self, a: Arg, label: &'static str) {
+        self.op(6, &[a, Addr(label)]);
+    }
+
+    fn halt(&mut self) {
+        self.code.push(99);
+    }
+
+    /// Loads `board[index]`, by writing its address into the next instruction.
+    fn load(&mut self, to: &'static str, index: &'static str) {
+        let operand = self.code.len() as i64 + 5;
+        self.op(1, &[Var(index), Addr("board"), At(operand)]);
+        self.op(1, &[At(0), Imm(0), Var(to)]);
+    }
+
+    /// Stores `value` in `board[index]`.
+    fn store(&mut self, index: &'static str, value: Arg) {
+        let operand = self.code.len() as i64 + 7;
+        self.op(1, &[Var(index), Addr("board"), At(operand)]);
+        self.op(1, &[value, Imm(0), At(0)]);
+    }
+
+    fn assemble(mut self) -> Program {
+        for (at, name) in self.fixups {
+            self.code[at] = self.labels[name];
+        }
+        self.code.iter().map(i64::to_string).collect::<Vec<_>>().join(",").parse().unwrap()
+    }
+}
+
+fn tile(c: char) -> Tile {
+    match c {
+        '#' => Tile::Wall,
+        'X' => Tile::Block,
+        _ => Tile::Empty,
+    }
+}
+
+/// Breaks the block at `board[idx]`, i.e. at `(x, y)`, and scores for it.
+fn break_block(asm: &mut Asm, x: &'static str, y: &'static str) {
+    asm.store("idx", Imm(0));
+    asm.output(&[Var(x), Var(y), Imm(0)]);
+    asm.add(Var("blocks"), Imm(-1), "blocks");
+    asm.add(Var("score"), Imm(10), "score");
+    asm.output(&[Imm(-1), Imm(0), Var("score")]);
+}
+
+/// `idx = y * WIDTH + x`, then `tile = board[idx]`.
+fn load_tile(asm: &mut Asm, x: &'static str, y: &'static str) {
+    asm.mul(Var(y), Imm(WIDTH), "idx");
+    asm.add(Var("idx"), Var(x), "idx");
+    asm.load("tile", "idx");
+}
+
+/// `idx = y * WIDTH + x`, then `board[idx] = tile`.
+fn store_tile(asm: &mut Asm, x: &'static str, y: &'static str, tile: Tile) {
+    asm.mul(Var(y), Imm(WIDTH), "idx");
+    asm.add(Var("idx"), Var(x), "idx");
+    asm.store("idx", Imm(tile.into()));
+}
+
+/// Draws the board, then each frame reads the joystick, moves the paddle and moves the ball by one row and column,
+/// bouncing off walls, blocks and the paddle. It halts when all blocks are broken or the ball is lost. Like the puzzle's
+/// game, it keeps the board, with the paddle and the ball, row by row in memory, so that levels can be loaded.
+fn breakout() -> Program {
+    let mut asm = Asm::default();
+    // Still an instruction after the coin is put into address 0.
+    asm.code.extend_from_slice(&[1, 0, 0, 0]);
+
+    let (px, bx, by) = (4, 2, 4);
+    let mut board = LEVEL.iter()
+        .flat_map(|row| row.chars().map(|c| i64::from(tile(c))))
+        .collect::<Vec<_>>();
+    board[(PADDLE_Y * WIDTH + px) as usize] = Tile::Paddle.into();
+    board[(by * WIDTH + bx) as usize] = Tile::Ball.into();
+    for (i, tile) in board.iter().enumerate() {
+        asm.output(&[Imm(i as i64 % WIDTH), Imm(i as i64 / WIDTH), Imm(*tile)]);
+    }
+    asm.output(&[Imm(-1), Imm(0), Imm(0)]);
+
+    asm.label("frame");
+    asm.input("joystick");
+    asm.add(Var("px"), Var("joystick"), "x");
+    asm.set("y", Imm(PADDLE_Y));
+    load_tile(&mut asm, "x", "y");
+    asm.jnz(Var("tile"), "horizontal");
+    asm.store("idx", Imm(Tile::Paddle.into()));
+    store_tile(&mut asm, "px", "y", Tile::Empty);
+    asm.output(&[Var("px"), Imm(PADDLE_Y), Imm(0), Var("x"), Imm(PADDLE_Y), Imm(3)]);
+    asm.set("px", Var("x"));
+
+    asm.label("horizontal");
+    asm.add(Var("bx"), Var("dx"), "x");
+    load_tile(&mut asm, "x", "by");
+    asm.jz(Var("tile"), "vertical");
+    asm.mul(Var("dx"), Imm(-1), "dx");
+    asm.eq(Var("tile"), Imm(2), "c");
+    asm.jz(Var("c"), "vertical");
+    break_block(&mut asm, "x", "by");
+
+    asm.label("vertical");
+    asm.add(Var("by"), Var("dy"), "y");
+    asm.eq(Var("y"), Imm(PADDLE_Y), "c");
+    asm.jz(Var("c"), "not_paddle");
+    asm.eq(Var("px"), Var("bx"), "c");
+    asm.jz(Var("c"), "lost");
+    asm.set("dy", Imm(-1));
+    asm.jnz(Imm(1), "diagonal");
+    asm.label("not_paddle");
+    load_tile(&mut asm, "bx", "y");
+    asm.jz(Var("tile"), "diagonal");
+    asm.mul(Var("dy"), Imm(-1), "dy");
+    asm.eq(Var("tile"), Imm(2), "c");
+    asm.jz(Var("c"), "diagonal");
+    break_block(&mut asm, "bx", "y");
+
+    // If the corner ahead is taken, the ball turns around and stays for this frame.
+    asm.label("diagonal");
+    asm.add(Var("bx"), Var("dx"), "x");
+    asm.add(Var("by"), Var("dy"), "y");
+    load_tile(&mut asm, "x", "y");
+    asm.jz(Var("tile"), "move");
+    asm.mul(Var("dx"), Imm(-1), "dx");
+    asm.mul(Var("dy"), Imm(-1), "dy");
+    asm.eq(Var("tile"), Imm(2), "c");
+    asm.jz(Var("c"), "done");
+    break_block(&mut asm, "x", "y");
+    asm.jnz(Imm(1), "done");
+
+    asm.label("move");
+    asm.store("idx", Imm(Tile::Ball.into()));
+    store_tile(&mut asm, "bx", "by", Tile::Empty);
+    asm.output(&[Var("bx"), Var("by"), Imm(0), Var("x"), Var("y"), Imm(4)]);
+    asm.set("bx", Var("x"));
+    asm.set("by", Var("y"));
+
+    asm.label("done");
+    asm.jnz(Var("blocks"), "frame");
+    asm.halt();
+
+    asm.label("lost");
+    asm.add(Var("bx"), Var("dx"), "x");
+    asm.output(&[Var("bx"), Var("by"), Imm(0), Var("x"), Imm(PADDLE_Y + 1), Imm(4)]);
+    asm.halt();
+
+    let blocks = board.iter().filter(|&&tile| tile == i64::from(Tile::Block)).count() as i64;
+    for &(name, value) in &[
+        ("px", px), ("bx", bx), ("by", by), ("dx", 1), ("dy", 1), ("blocks", blocks), ("score", 0),
+        ("joystick", 0), ("x", 0), ("y", 0), ("idx", 0), ("tile", 0), ("c", 0),
+    ] {
+        asm.data(name, &[value]);
+    }
+    asm.data("board", &board);
+
+    asm.assemble()
+}
+
+/// Plays until the game is over, with the autopilot or else the joystick as it is. Returns the number of frames.
+fn play(arcade: &mut Arcade, autopilot: bool) -> usize {
+    let mut frames = 0;
+    loop {
+        assert!(frames < MAX_FRAMES, "Still playing after {} frames:\n{}", frames, arcade.screen);
+        if autopilot {
+            arcade.autopilot().unwrap();
+        }
+        match arcade.wait_frame() {
+            Ok(_) => frames += 1,
+            Err(Error::Intcode(IntcodeError::Halted)) => break frames,
+            Err(e) => panic!("Game crashed: {}", e),
+        }
+    }
+}
+
+#[test]
+fn autopilot_clears_the_board() {
+    let mut arcade = Arcade::new(breakout());
+    arcade.load_screen().unwrap();
+    assert_eq!(arcade.screen.num_blocks, 9);
+    assert_eq!((arcade.screen.paddle_x, arcade.screen.paddle_y), (4, PADDLE_Y));
+    assert_eq!(arcade.screen.to_string().lines().next(), Some("#########"));
+
+    play(&mut arcade, true);
+
+    let stats = arcade.screen.stats();
+    assert_eq!(stats.balls_lost, 0, "Lost the ball:\n{}", arcade.screen);
+    assert_eq!(arcade.screen.num_blocks, 0);
+    assert_eq!(arcade.screen.score, 90);
+    assert!(stats.paddle_hits > 0);
+}
+
+#[test]
+fn predicts_where_the_ball_meets_the_paddle() {
+    let mut arcade = Arcade::new(breakout());
+    arcade.load_screen().unwrap();
+    // The ball starts at (2, 4) and goes down and right.
+    assert_eq!(arcade.predict_ball().unwrap(), vec![(3, 5), (4, 6)]);
+    assert_eq!(arcade.screen.ball_y, 4);
+}
+
+#[test]
+fn pilot_seed_reproduces_mistakes() {
+    let game = |seed| {
+        let mut arcade = Arcade::new(breakout());
+        arcade.set_difficulty(Difficulty::Easy, seed);
+        arcade.load_screen().unwrap();
+        play(&mut arcade, true);
+        assert_eq!(arcade.pilot_seed(), seed);
+        (arcade.record(), arcade.screen.score)
+    };
+    assert_eq!(game(Arcade::PILOT_SEED), game(Arcade::PILOT_SEED));
+    assert_eq!(game(7), game(7));
+}
+
+#[test]
+fn replays_a_saved_game() {
+    let mut arcade = Arcade::new(breakout());
+    arcade.load_screen().unwrap();
+    let frames = play(&mut arcade, true);
+    let log = arcade.record();
+    assert!(log.changes() > 0);
+
+    let path = std::env::temp_dir().join(format!("arcade-replay-test-{}.txt", std::process::id()));
+    log.save(&path).unwrap();
+    let loaded = InputLog::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, log);
+
+    let mut replay = Arcade::new(breakout());
+    replay.load_screen().unwrap();
+    replay.replay(&loaded);
+    assert_eq!(play(&mut replay, false), frames);
+    assert_eq!(replay.screen.score, arcade.screen.score);
+    assert_eq!(replay.screen.framebuffer, arcade.screen.framebuffer);
+}
+
+#[test]
+fn loads_a_level() {
+    let mut arcade = Arcade::new(breakout());
+    arcade.load_screen().unwrap();
+
+    let board = arcade.locate_board().unwrap();
+    assert_eq!((board.width, board.height), (WIDTH as usize, LEVEL.len()));
+    assert!(board.block_counter.is_some());
+
+    let level: Level = "#########\n#.......#\n#..X.X..#\n#...X...#".parse().unwrap();
+    arcade.load_level(&level).unwrap();
+    assert_eq!(arcade.screen.num_blocks, 3);
+    assert_eq!(arcade.screen.to_string().lines().nth(3), Some("#   █   #"));
+    assert_eq!(arcade.machine.get_data(board.address + 3 * board.width + 4), i64::from(Tile::Block));
+
+    // The game only ends if the block counter was found and set, too.
+    play(&mut arcade, true);
+    assert_eq!(arcade.screen.num_blocks, 0);
+    assert_eq!(arcade.screen.score, 30);
+}
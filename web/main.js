@@ -0,0 +1,139 @@
+// Steps the simulations from the WebAssembly build of aoc_2019 (see README) and draws them to the canvas.
+import init, { PaintingRobot, BreakoutGame } from "./pkg/aoc_2019.js";
+
+const canvas = document.getElementById("canvas");
+const context = canvas.getContext("2d");
+const status = document.getElementById("status");
+const help = document.getElementById("help");
+
+// Game Boy colors, like the desktop arcade.
+const TILE_COLORS = ["#0f380f", "#306230", "#8bac0f", "#9bbc0f", "#ffffff"];
+const PANEL_COLORS = ["#306230", "#9bbc0f"];
+
+let running = null;
+
+function stop() {
+    if (running) {
+        cancelAnimationFrame(running.frame);
+        running.simulation.free();
+        running = null;
+        document.onkeydown = document.onkeyup = null;
+    }
+}
+
+function loop(simulation, tick) {
+    const step = () => {
+        if (tick()) {
+            running.frame = requestAnimationFrame(step);
+        }
+    };
+    running = { simulation, frame: requestAnimationFrame(step) };
+}
+
+function drawHull(robot) {
+    const panels = robot.panels();
+    let minX = robot.x(), maxX = robot.x(), minY = robot.y(), maxY = robot.y();
+    for (let i = 0; i < panels.length; i += 3) {
+        minX = Math.min(minX, panels[i]);
+        maxX = Math.max(maxX, panels[i]);
+        minY = Math.min(minY, panels[i + 1]);
+        maxY = Math.max(maxY, panels[i + 1]);
+    }
+    const size = Math.max(1, Math.floor(Math.min(canvas.width / (maxX - minX + 1), canvas.height / (maxY - minY + 1))));
+
+    context.fillStyle = TILE_COLORS[0];
+    context.fillRect(0, 0, canvas.width, canvas.height);
+    for (let i = 0; i < panels.length; i += 3) {
+        context.fillStyle = PANEL_COLORS[panels[i + 2]];
+        context.fillRect((panels[i] - minX) * size, (panels[i + 1] - minY) * size, size, size);
+    }
+    context.fillStyle = "#ff0000";
+    context.fillRect((robot.x() - minX) * size, (robot.y() - minY) * size, size, size);
+}
+
+function runRobot(program, startOnWhite) {
+    const robot = new PaintingRobot(program, startOnWhite);
+    loop(robot, () => {
+        // A few panels per frame, or it takes minutes.
+        let painting = true;
+        for (let i = 0; i < 20 && painting; i++) {
+            painting = robot.step();
+        }
+        drawHull(robot);
+        status.textContent = `Panels painted: ${robot.num_painted()}` + (painting ? "" : " (done)");
+        return painting;
+    });
+}
+
+function runBreakout(program) {
+    const game = new BreakoutGame(program);
+    const keys = new Set();
+    let autopilot = false;
+    let paused = false;
+
+    document.onkeydown = (event) => {
+        if (event.code === "F7") {
+            autopilot = !autopilot;
+            game.set_autopilot(autopilot);
+            event.preventDefault();
+        }
+        else if (event.code === "Space") {
+            paused = !paused;
+            event.preventDefault();
+        }
+        keys.add(event.code);
+    };
+    document.onkeyup = (event) => keys.delete(event.code);
+
+    let last = 0;
+    loop(game, () => {
+        const now = performance.now();
+        let playing = true;
+        // The game runs at 20 frames per second, like in the terminal.
+        if (!paused && now - last >= 50) {
+            last = now;
+            const left = keys.has("KeyA") || keys.has("ArrowLeft");
+            const right = keys.has("KeyD") || keys.has("ArrowRight");
+            playing = game.frame(left === right ? 0 : left ? -1 : 1);
+        }
+
+        const width = game.width(), height = game.height();
+        const size = Math.floor(Math.min(canvas.width / width, canvas.height / height));
+        const tiles = game.tiles();
+        for (let y = 0; y < height; y++) {
+            for (let x = 0; x < width; x++) {
+                context.fillStyle = TILE_COLORS[tiles[y * width + x]];
+                context.fillRect(x * size, y * size, size, size);
+            }
+        }
+        status.textContent = `Score: ${game.score()}  Blocks: ${game.blocks()}` + (autopilot ? "  AUTO" : "")
+            + (paused ? "  PAUSED" : "") + (playing ? "" : "  GAME OVER");
+        return playing;
+    });
+}
+
+document.getElementById("start").onclick = async () => {
+    const file = document.getElementById("input").files[0];
+    if (!file) {
+        status.textContent = "Pick your puzzle input first.";
+        return;
+    }
+    stop();
+
+    const program = await file.text();
+    const puzzle = document.getElementById("puzzle").value;
+    help.hidden = puzzle !== "breakout";
+    try {
+        if (puzzle === "breakout") {
+            runBreakout(program);
+        }
+        else {
+            runRobot(program, puzzle === "robot-white");
+        }
+    }
+    catch (e) {
+        status.textContent = `Error: ${e}`;
+    }
+};
+
+await init();